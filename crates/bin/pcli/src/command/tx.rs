@@ -990,10 +990,14 @@ impl TxCmd {
                     println!("Position id: {}", position.id());
                 }
 
+                let position_open_fee =
+                    app.view().app_params().await?.dex_params.position_open_fee;
+
                 let mut planner = Planner::new(OsRng);
                 planner
                     .set_gas_prices(gas_prices)
-                    .set_fee_tier(order.fee_tier().into());
+                    .set_fee_tier(order.fee_tier().into())
+                    .set_position_open_fee(position_open_fee);
 
                 for position in positions {
                     planner.position_open(position);
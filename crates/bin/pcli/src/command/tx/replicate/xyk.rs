@@ -131,8 +131,12 @@ impl ConstantProduct {
             .expect("gas prices must be available")
             .try_into()?;
 
+        let position_open_fee = app.view().app_params().await?.dex_params.position_open_fee;
+
         let mut planner = Planner::new(OsRng);
-        planner.set_gas_prices(gas_prices);
+        planner
+            .set_gas_prices(gas_prices)
+            .set_position_open_fee(position_open_fee);
         positions.iter().for_each(|position| {
             planner.position_open(position.clone());
         });
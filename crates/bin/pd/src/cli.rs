@@ -89,6 +89,18 @@ pub enum RootCommand {
         /// Enable expensive RPCs, currently a no-op.
         #[clap(short, long, display_order = 500)]
         enable_expensive_rpc: bool,
+        /// The maximum number of transactions whose proofs are verified in parallel.
+        ///
+        /// Transactions delivered faster than they execute, as during catch-up sync,
+        /// have their proofs verified ahead of execution. Execution itself stays
+        /// sequential, so this does not affect the resulting state.
+        #[clap(
+            long,
+            env = "PENUMBRA_PD_VERIFICATION_PARALLELISM",
+            default_value = "1",
+            display_order = 600
+        )]
+        verification_parallelism: usize,
     },
 
     /// Generate, join, or reset a network.
@@ -62,6 +62,7 @@ async fn main() -> anyhow::Result<()> {
             metrics_bind,
             cometbft_addr,
             enable_expensive_rpc,
+            verification_parallelism,
         } => {
             // Use the given `grpc_bind` address if one was specified. If not, we will choose a
             // default depending on whether or not `grpc_auto_https` was set. See the
@@ -82,6 +83,11 @@ async fn main() -> anyhow::Result<()> {
                 grpc_bind.unwrap_or_else(default)
             };
 
+            // Fail fast if the embedded proof parameters are inconsistent, rather than
+            // rejecting every proof once the node is running.
+            penumbra_proof_params::check_verification_keys()
+                .context("proof parameters failed the startup self-check")?;
+
             // Ensure we have all necessary parts in the URL
             if !url_has_necessary_parts(&cometbft_addr) {
                 anyhow::bail!(
@@ -114,6 +120,7 @@ async fn main() -> anyhow::Result<()> {
                 ?metrics_bind,
                 %cometbft_addr,
                 ?enable_expensive_rpc,
+                verification_parallelism,
                 "starting pd"
             );
 
@@ -125,7 +132,8 @@ async fn main() -> anyhow::Result<()> {
             }
 
             let abci_server = tokio::task::spawn(
-                penumbra_app::server::new(storage.clone()).listen_tcp(abci_bind),
+                penumbra_app::server::new(storage.clone(), verification_parallelism)
+                    .listen_tcp(abci_bind),
             );
 
             let tm_proxy = penumbra_tendermint_proxy::TendermintProxy::new(cometbft_addr);
@@ -0,0 +1,176 @@
+//! Valuing the holdings of an account in staking tokens.
+//!
+//! Delegating genesis funds is not a loss of funds, so delegation and unbonding
+//! tokens count towards the balance of an account, at their value in staking tokens.
+//! Both are valued net of any slashing, so that a slashed delegation shows up as a
+//! real reduction in balance.
+//!
+//! Liquidity positions also hold value outside of the notes of an account, so the
+//! reserves of the positions it holds the NFTs of are valued alongside its notes.
+use std::collections::BTreeMap;
+
+use penumbra_asset::asset;
+use penumbra_dex::lp::position::{self, Position};
+use penumbra_num::Amount;
+use penumbra_stake::{rate::RateData, Penalty};
+
+/// The UM-equivalent balance of an account, split by whether it is staked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccountBalance {
+    /// Staking tokens held directly.
+    pub liquid: Amount,
+    /// The value of delegation and unbonding tokens, in staking tokens.
+    pub staked: Amount,
+}
+
+impl AccountBalance {
+    /// The total UM-equivalent balance, staked or not.
+    pub fn total(&self) -> Amount {
+        self.liquid + self.staked
+    }
+
+    /// Count `amount` of staking tokens held directly.
+    pub fn add_liquid(&mut self, amount: Amount) {
+        self.liquid += amount;
+    }
+
+    /// Count `amount` of delegation tokens, at the current exchange rate of their validator.
+    ///
+    /// Slashing is already reflected in the exchange rate.
+    pub fn add_delegation(&mut self, rate_data: &RateData, amount: Amount) {
+        self.staked += rate_data.unbonded_amount(amount);
+    }
+
+    /// Count `amount` of unbonding tokens, net of the `penalty` incurred by their
+    /// validator since unbonding started.
+    pub fn add_unbonding(&mut self, penalty: &Penalty, amount: Amount) {
+        self.staked += penalty.apply_to_amount(amount);
+    }
+}
+
+/// Count the reserves of a liquidity position held by an account into its `holdings`.
+///
+/// A position holds its reserves until they are withdrawn, so the reserves of a
+/// closed position count as much as those of an opened one.
+pub fn add_position_reserves(holdings: &mut BTreeMap<asset::Id, Amount>, position: &Position) {
+    if !matches!(
+        position.state,
+        position::State::Opened | position::State::Closed
+    ) {
+        return;
+    }
+    let pair = position.phi.pair;
+    *holdings.entry(pair.asset_1()).or_default() += position.reserves.r1;
+    *holdings.entry(pair.asset_2()).or_default() += position.reserves.r2;
+}
+
+#[cfg(test)]
+mod tests {
+    use penumbra_asset::STAKING_TOKEN_ASSET_ID;
+    use penumbra_dex::{lp::Reserves, DirectedTradingPair};
+    use penumbra_proto::core::{component::stake::v1 as pb, keys::v1 as pb_keys};
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::check_wallet_compliance;
+
+    /// 1000UM, in millionths.
+    const GENESIS_BALANCE: u64 = 1_000_000_000;
+
+    /// Rate data for a validator whose delegation tokens are worth `exchange_rate_bps`
+    /// basis points of a staking token.
+    fn rate_data(exchange_rate_bps: u128) -> RateData {
+        pb::RateData {
+            identity_key: Some(pb_keys::IdentityKey { ik: vec![0u8; 32] }),
+            validator_reward_rate: Some(Amount::zero().into()),
+            validator_exchange_rate: Some(Amount::from(exchange_rate_bps * 1_0000).into()),
+            ..Default::default()
+        }
+        .try_into()
+        .expect("rate data is valid")
+    }
+
+    #[test]
+    fn fully_delegated_genesis_balance_is_compliant() {
+        let mut balance = AccountBalance::default();
+        // At an exchange rate of 1.25, the genesis balance buys 800UM of delegation tokens.
+        balance.add_delegation(&rate_data(1_2500), Amount::from(800_000_000u64));
+
+        assert_eq!(balance.liquid, Amount::zero());
+        assert_eq!(balance.staked, Amount::from(GENESIS_BALANCE));
+        assert!(check_wallet_compliance(
+            GENESIS_BALANCE.into(),
+            balance.total()
+        ));
+    }
+
+    #[test]
+    fn slashed_delegation_is_a_real_reduction() {
+        let mut balance = AccountBalance::default();
+        balance.add_liquid(Amount::from(GENESIS_BALANCE / 2));
+        balance.add_unbonding(
+            &Penalty::from_percent(50),
+            Amount::from(GENESIS_BALANCE / 2),
+        );
+
+        assert_eq!(balance.staked, Amount::from(250_000_000u64));
+        assert!(!check_wallet_compliance(
+            GENESIS_BALANCE.into(),
+            balance.total()
+        ));
+    }
+
+    /// A position selling `reserves` staking tokens for `other`.
+    fn position(other: asset::Id, reserves: u64) -> Position {
+        Position::new(
+            OsRng,
+            DirectedTradingPair::new(*STAKING_TOKEN_ASSET_ID, other),
+            0u32,
+            1u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: reserves.into(),
+                r2: Amount::zero(),
+            },
+        )
+    }
+
+    #[test]
+    fn open_position_reserves_count_toward_balance() {
+        let other = asset::Cache::with_known_assets()
+            .get_unit("gm")
+            .expect("gm is a known asset")
+            .id();
+        let mut holdings =
+            BTreeMap::from([(*STAKING_TOKEN_ASSET_ID, Amount::from(400_000_000u64))]);
+        add_position_reserves(&mut holdings, &position(other, 600_000_000));
+
+        let mut balance = AccountBalance::default();
+        balance.add_liquid(holdings[&STAKING_TOKEN_ASSET_ID]);
+        assert_eq!(balance.total(), Amount::from(GENESIS_BALANCE));
+        assert!(check_wallet_compliance(
+            GENESIS_BALANCE.into(),
+            balance.total()
+        ));
+    }
+
+    #[test]
+    fn only_unwithdrawn_positions_hold_reserves() {
+        let other = asset::Cache::with_known_assets()
+            .get_unit("gm")
+            .expect("gm is a known asset")
+            .id();
+        let mut closed = position(other, 1_000);
+        closed.state = position::State::Closed;
+        let mut withdrawn = position(other, 1_000);
+        withdrawn.state = position::State::Withdrawn { sequence: 0 };
+
+        let mut holdings = BTreeMap::new();
+        add_position_reserves(&mut holdings, &closed);
+        add_position_reserves(&mut holdings, &withdrawn);
+        assert_eq!(
+            holdings.get(&*STAKING_TOKEN_ASSET_ID),
+            Some(&Amount::from(1_000u64))
+        );
+    }
+}
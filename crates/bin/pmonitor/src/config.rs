@@ -1,7 +1,8 @@
 //! Logic for reading and writing config files for `pmonitor`, in the TOML format.
-use anyhow::Result;
+use anyhow::{anyhow, bail, ensure, Result};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use url::Url;
 use uuid::Uuid;
 
@@ -72,26 +73,93 @@ impl AccountConfig {
     }
 }
 
+/// The version of the config file format written by this version of `pmonitor`.
+///
+/// Bump this whenever the shape of [`PmonitorConfig`] changes, and teach
+/// [`PmonitorConfig::from_toml`] to migrate the previous version forward.
+pub const CONFIG_VERSION: u64 = 2;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 /// The primary TOML file for configuring `pmonitor`, containing all its account info.
 ///
 /// During `pmonitor audit` runs, the config will be automatically updated
 /// if tracked FVKs were detected to migrate, via `pcli migrate balance`, to save time
 /// on future syncs.
+///
+/// Config files should be read with [`PmonitorConfig::from_toml`], which accepts
+/// older versions of the format.
 pub struct PmonitorConfig {
-    /// The gRPC URL for a Penumbra node's `pd` endpoint, used for retrieving account activity.
-    grpc_url: Url,
+    /// The version of the config file format, which is always [`CONFIG_VERSION`] once loaded.
+    version: u64,
+    /// The gRPC URLs for Penumbra nodes' `pd` endpoints, used for retrieving account activity.
+    ///
+    /// The first is used to sync wallets, and the others are tried in order if it is unreachable.
+    grpc_urls: Vec<Url>,
+    /// If set, `pmonitor audit` re-scans on this interval, rather than exiting after one run.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "humantime_duration"
+    )]
+    watch_interval: Option<Duration>,
     /// The list of Penumbra wallets to track.
     accounts: Vec<AccountConfig>,
 }
 
 impl PmonitorConfig {
     pub fn new(grpc_url: Url, accounts: Vec<AccountConfig>) -> Self {
-        Self { grpc_url, accounts }
+        Self {
+            version: CONFIG_VERSION,
+            grpc_urls: vec![grpc_url],
+            watch_interval: None,
+            accounts,
+        }
+    }
+
+    /// Parse a config file, migrating it forward if it is in an older version of the format.
+    ///
+    /// A config without a `version` predates versioning, and is version 1.
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        let table: toml::Table = toml::from_str(contents)?;
+        let version = match table.get("version") {
+            None => 1,
+            Some(version) => version
+                .as_integer()
+                .and_then(|version| u64::try_from(version).ok())
+                .ok_or_else(|| anyhow!("config version must be a positive integer"))?,
+        };
+        let config: Self = match version {
+            1 => toml::Value::Table(table)
+                .try_into::<PmonitorConfigV1>()?
+                .into(),
+            CONFIG_VERSION => toml::Value::Table(table).try_into()?,
+            version if version > CONFIG_VERSION => bail!(
+                "config version {version} is newer than version {CONFIG_VERSION}, the latest supported by this pmonitor: upgrade pmonitor to use this config"
+            ),
+            version => bail!("unknown config version {version}"),
+        };
+        ensure!(
+            !config.grpc_urls.is_empty(),
+            "config must list at least one gRPC URL"
+        );
+        Ok(config)
     }
 
+    /// Get the primary gRPC URL, used for syncing wallets.
     pub fn grpc_url(&self) -> Url {
-        self.grpc_url.clone()
+        self.grpc_urls
+            .first()
+            .expect("config has at least one gRPC URL")
+            .clone()
+    }
+
+    /// Get all gRPC URLs, in the order they should be tried.
+    pub fn grpc_urls(&self) -> &[Url] {
+        &self.grpc_urls
+    }
+
+    pub fn watch_interval(&self) -> Option<Duration> {
+        self.watch_interval
     }
 
     pub fn accounts(&self) -> &Vec<AccountConfig> {
@@ -103,6 +171,26 @@ impl PmonitorConfig {
     }
 }
 
+/// Version 1 of the config file format, which had a single gRPC URL.
+#[derive(Deserialize)]
+struct PmonitorConfigV1 {
+    grpc_url: Url,
+    #[serde(default, with = "humantime_duration")]
+    watch_interval: Option<Duration>,
+    accounts: Vec<AccountConfig>,
+}
+
+impl From<PmonitorConfigV1> for PmonitorConfig {
+    fn from(v1: PmonitorConfigV1) -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            grpc_urls: vec![v1.grpc_url],
+            watch_interval: v1.watch_interval,
+            accounts: v1.accounts,
+        }
+    }
+}
+
 /// Get the destination FVK from a migration memo.
 pub fn parse_dest_fvk_from_memo(memo: &str) -> Result<FullViewingKey> {
     let re = Regex::new(r"Migrating balance from .+ to (.+)")?;
@@ -116,3 +204,64 @@ pub fn parse_dest_fvk_from_memo(memo: &str) -> Result<FullViewingKey> {
     }
     Err(anyhow::anyhow!("Could not parse destination FVK from memo"))
 }
+
+/// (De)serializes an optional [`Duration`] in a human-readable format, e.g. `"5m"`.
+mod humantime_duration {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(d) => serializer.serialize_str(&humantime::format_duration(*d).to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| humantime::parse_duration(&s).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_config_is_migrated() {
+        let legacy = r#"
+grpc_url = "https://testnet.plinfra.net/"
+watch_interval = "5m"
+accounts = []
+"#;
+        let config = PmonitorConfig::from_toml(legacy).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(
+            config.grpc_urls(),
+            &["https://testnet.plinfra.net/".parse::<Url>().unwrap()]
+        );
+        assert_eq!(config.watch_interval(), Some(Duration::from_secs(300)));
+
+        // The migrated config is written back in the current version, and reads back the same.
+        let written = toml::to_string(&config).unwrap();
+        let reread = PmonitorConfig::from_toml(&written).unwrap();
+        assert_eq!(reread.version, CONFIG_VERSION);
+        assert_eq!(reread.grpc_urls(), config.grpc_urls());
+    }
+
+    #[test]
+    fn newer_config_version_is_rejected() {
+        let future = format!(
+            "version = {}\ngrpc_urls = []\naccounts = []\n",
+            CONFIG_VERSION + 1
+        );
+        let error = PmonitorConfig::from_toml(&future).unwrap_err();
+        assert!(error.to_string().contains("upgrade pmonitor"));
+    }
+}
@@ -9,8 +9,21 @@
 //!
 //!     pmonitor audit
 //!
-//! If regular auditing is desired, consider automating the `pmonitor audit` action via
-//! cron or similar. `pmonitor` will cache view databases for each tracked FVK, so that future
+//! The `audit` action exits with a status code suitable for gating CI pipelines:
+//! `0` if all accounts are compliant, `1` if any account is in violation, and `2` if the
+//! audit could not be completed, e.g. because the node was unreachable.
+//!
+//! If regular auditing is desired, run the audit in watch mode, which re-scans on an
+//! interval and only reports changes in compliance after the first run:
+//!
+//!     pmonitor audit --watch 10m
+//!
+//! A one-shot audit can save its results as a JSON report, and compare them against
+//! the report saved by an earlier run, to track how compliance drifted since then:
+//!
+//!     pmonitor audit --report current.json --baseline previous.json
+//!
+//! Alternatively, consider automating the `pmonitor audit` action via cron or similar. `pmonitor` will cache view databases for each tracked FVK, so that future
 //! `audit` actions need only inspect the blocks generated between the previous audit and the
 //! current height.
 
@@ -19,10 +32,15 @@ use camino::Utf8PathBuf;
 use clap::{self, Parser};
 use directories::ProjectDirs;
 use futures::StreamExt;
-use penumbra_asset::STAKING_TOKEN_ASSET_ID;
+use penumbra_asset::{asset, STAKING_TOKEN_ASSET_ID};
 use std::fs;
 use std::io::IsTerminal as _;
+use std::process::ExitCode;
 use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
 use tonic::transport::{Channel, ClientTlsConfig};
 use tracing_subscriber::{prelude::*, EnvFilter};
 use url::Url;
@@ -32,6 +50,7 @@ use colored::Colorize;
 
 use pcli::config::PcliConfig;
 use penumbra_compact_block::CompactBlock;
+use penumbra_dex::lp::{position::Position, LpNft};
 use penumbra_keys::FullViewingKey;
 use penumbra_num::Amount;
 use penumbra_proto::box_grpc_svc;
@@ -40,17 +59,31 @@ use penumbra_proto::view::v1::{
 };
 use penumbra_proto::{
     core::component::compact_block::v1::CompactBlockRequest,
+    core::component::dex::v1::{
+        query_service_client::QueryServiceClient as DexQueryServiceClient,
+        LiquidityPositionByIdRequest,
+    },
+    core::component::sct::v1::{
+        query_service_client::QueryServiceClient as SctQueryServiceClient, EpochByHeightRequest,
+    },
     core::component::stake::v1::query_service_client::QueryServiceClient as StakeQueryServiceClient,
+    core::component::stake::v1::ValidatorPenaltyRequest,
     penumbra::core::component::compact_block::v1::query_service_client::QueryServiceClient as CompactBlockQueryServiceClient,
 };
 use penumbra_stake::rate::RateData;
-use penumbra_stake::DelegationToken;
+use penumbra_stake::{DelegationToken, Penalty, UnbondingToken};
 use penumbra_view::{Storage, ViewClient, ViewServer};
 
+mod balance;
 mod config;
 mod genesis;
+mod report;
+mod watch;
 
+use balance::{add_position_reserves, AccountBalance};
 use config::{parse_dest_fvk_from_memo, AccountConfig, FvkEntry, PmonitorConfig};
+use report::{ComplianceReport, ReportDiff};
+use watch::{CycleReport, ViolationDelta, WatchState};
 
 /// The maximum size of a compact block, in bytes (12MB).
 const MAX_CB_SIZE_BYTES: usize = 12 * 1024 * 1024;
@@ -62,6 +95,84 @@ const VIEW_FILE_NAME: &str = "pcli-view.sqlite";
 /// specified in number of staking tokens.
 const ALLOWED_DISCREPANCY: f64 = 0.1;
 
+/// The exit code used when the audit completed, but found non-compliant accounts.
+const EXIT_CODE_VIOLATIONS: u8 = 1;
+
+/// The exit code used when the audit could not be completed, for instance
+/// because the node was unreachable.
+const EXIT_CODE_OPERATIONAL_ERROR: u8 = 2;
+
+/// The error returned by an audit run that found non-compliant accounts.
+///
+/// This is kept distinct from all other errors, so that a balance violation can be
+/// reported with a different exit code than an operational failure.
+#[derive(Debug)]
+pub struct ViolationsFound {
+    /// The number of accounts deemed in violation.
+    pub count: usize,
+}
+
+impl std::fmt::Display for ViolationsFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "non-compliant balances were detected in {} account(s)",
+            self.count
+        )
+    }
+}
+
+impl std::error::Error for ViolationsFound {}
+
+/// The `pd` query clients used to value the balances of accounts.
+#[derive(Clone)]
+pub struct PdClients {
+    stake: StakeQueryServiceClient<Channel>,
+    sct: SctQueryServiceClient<Channel>,
+    dex: DexQueryServiceClient<Channel>,
+}
+
+impl PdClients {
+    fn new(channel: Channel) -> Self {
+        Self {
+            stake: StakeQueryServiceClient::new(channel.clone()),
+            sct: SctQueryServiceClient::new(channel.clone()),
+            dex: DexQueryServiceClient::new(channel),
+        }
+    }
+}
+
+/// The outcome of a single audit run over all configured accounts.
+#[derive(Debug)]
+pub struct AuditReport {
+    /// All accounts that were audited.
+    pub accounts: Vec<AccountConfig>,
+    /// The accounts deemed in violation.
+    pub failures: Vec<AccountConfig>,
+}
+
+impl AuditReport {
+    /// The set of non-compliant accounts, identified by their original FVK,
+    /// which remains stable across account migrations.
+    pub fn violations(&self) -> BTreeSet<String> {
+        self.failures
+            .iter()
+            .map(|f| f.original_fvk().to_string())
+            .collect()
+    }
+}
+
+/// Map the result of a run to the process exit code.
+fn exit_code(result: &Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if e.downcast_ref::<ViolationsFound>().is_some() => {
+            ExitCode::from(EXIT_CODE_VIOLATIONS)
+        }
+        Err(_) => ExitCode::from(EXIT_CODE_OPERATIONAL_ERROR),
+    }
+}
+
 /// Configure tracing_subscriber for logging messages
 fn init_tracing() -> anyhow::Result<()> {
     // Instantiate tracing layers.
@@ -83,11 +194,18 @@ fn init_tracing() -> anyhow::Result<()> {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let opt = Opt::parse();
-    init_tracing()?;
-    tracing::info!(?opt, version = env!("CARGO_PKG_VERSION"), "running command");
-    opt.exec().await
+    let result = async {
+        init_tracing()?;
+        tracing::info!(?opt, version = env!("CARGO_PKG_VERSION"), "running command");
+        opt.exec().await
+    }
+    .await;
+    if let Err(e) = &result {
+        eprintln!("Error: {:?}", e);
+    }
+    exit_code(&result)
 }
 
 /// The path to the default `pmonitor` home directory.
@@ -132,7 +250,18 @@ pub enum Command {
         grpc_url: Url,
     },
     /// Sync to latest block height and verify all configured wallets have the correct balance.
-    Audit {},
+    Audit {
+        /// Re-run the audit on the given interval (e.g. "10m"), only reporting changes
+        /// after the first run. Overrides the `watch_interval` set in the config file.
+        #[clap(long, display_order = 100, parse(try_from_str = humantime::parse_duration))]
+        watch: Option<Duration>,
+        /// Save the compliance report of this run as JSON to the given path.
+        #[clap(long, display_order = 200, conflicts_with = "watch")]
+        report: Option<Utf8PathBuf>,
+        /// Compare this run against a JSON compliance report saved by a previous run.
+        #[clap(long, display_order = 300, conflicts_with = "watch")]
+        baseline: Option<Utf8PathBuf>,
+    },
     /// Delete `pmonitor` storage to reset local state.
     Reset {},
 }
@@ -241,6 +370,21 @@ impl Opt {
         }
     }
 
+    /// Connect to the first reachable `pd` endpoint of `grpc_urls`, trying them in order.
+    pub async fn pd_channel_with_fallback(&self, grpc_urls: &[Url]) -> anyhow::Result<Channel> {
+        let mut last_error = None;
+        for grpc_url in grpc_urls {
+            match self.pd_channel(grpc_url.clone()).await {
+                Ok(channel) => return Ok(channel),
+                Err(e) => {
+                    tracing::warn!(%grpc_url, ?e, "failed to connect to pd, trying next url");
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("no gRPC URLs configured")))
+    }
+
     /// Create wallet given a path and fvk
     pub async fn create_wallet(
         &self,
@@ -273,26 +417,21 @@ impl Opt {
     }
 
     /// Compute the UM-equivalent balance for a given (synced) wallet.
+    ///
+    /// Delegation and unbonding tokens are counted as staked, at their value in staking tokens.
+    /// The reserves of the liquidity positions held by the wallet are valued like its notes.
     pub async fn compute_um_equivalent_balance(
         &self,
         view_client: &mut ViewServiceClient<box_grpc_svc::BoxGrpcService>,
-        stake_client: &mut StakeQueryServiceClient<Channel>,
-    ) -> Result<Amount> {
+        clients: &mut PdClients,
+    ) -> Result<AccountBalance> {
         let notes = view_client.unspent_notes_by_asset_and_address().await?;
-        let mut total_um_equivalent_amount = Amount::from(0u64);
-        for (asset_id, map) in notes.iter() {
-            if *asset_id == *STAKING_TOKEN_ASSET_ID {
-                let total_amount = map
-                    .iter()
-                    .map(|(_, spendable_notes)| {
-                        spendable_notes
-                            .iter()
-                            .map(|spendable_note| spendable_note.note.amount())
-                            .sum::<Amount>()
-                    })
-                    .sum::<Amount>();
-                total_um_equivalent_amount += total_amount;
-            } else if let Ok(delegation_token) = DelegationToken::from_str(&asset_id.to_string()) {
+        let asset_cache = view_client.assets().await?;
+        // Only needed to value unbonding tokens, so fetched on first use.
+        let mut current_epoch_index = None;
+        let mut holdings: BTreeMap<asset::Id, Amount> = notes
+            .iter()
+            .map(|(asset_id, map)| {
                 let total_amount = map
                     .iter()
                     .map(|(_, spendable_notes)| {
@@ -302,20 +441,93 @@ impl Opt {
                             .sum::<Amount>()
                     })
                     .sum::<Amount>();
+                (*asset_id, total_amount)
+            })
+            .collect();
+
+        // Liquidity positions hold value outside of the wallet's notes, so look up the
+        // positions of the LP NFTs it holds, and count their reserves.
+        let lp_nfts: Vec<LpNft> = holdings
+            .keys()
+            .filter_map(|asset_id| asset_cache.get(asset_id).cloned())
+            .filter_map(|metadata| LpNft::try_from(metadata).ok())
+            .collect();
+        for lp_nft in lp_nfts {
+            let position: Position = clients
+                .dex
+                .liquidity_position_by_id(LiquidityPositionByIdRequest {
+                    position_id: Some(lp_nft.position_id().into()),
+                })
+                .await?
+                .into_inner()
+                .data
+                .context("no position returned for LP NFT")?
+                .try_into()?;
+            add_position_reserves(&mut holdings, &position);
+        }
 
+        let mut balance = AccountBalance::default();
+        for (asset_id, &total_amount) in holdings.iter() {
+            if *asset_id == *STAKING_TOKEN_ASSET_ID {
+                balance.add_liquid(total_amount);
+                continue;
+            }
+            let Some(metadata) = asset_cache.get(asset_id).cloned() else {
+                tracing::warn!("ignoring notes with unknown asset id: {}", asset_id);
+                continue;
+            };
+
+            if let Ok(delegation_token) = DelegationToken::try_from(metadata.clone()) {
                 // We need to convert the amount to the UM-equivalent amount using the appropriate rate data
-                let rate_data: RateData = stake_client
+                let rate_data: RateData = clients
+                    .stake
                     .current_validator_rate(tonic::Request::new(
                         (delegation_token.validator()).into(),
                     ))
                     .await?
                     .into_inner()
                     .try_into()?;
-                let um_equivalent_balance = rate_data.unbonded_amount(total_amount);
-                total_um_equivalent_amount += um_equivalent_balance;
-            };
+                balance.add_delegation(&rate_data, total_amount);
+            } else if let Ok(unbonding_token) = UnbondingToken::try_from(metadata) {
+                // Any slashing since unbonding started will be deducted when the tokens are claimed.
+                let end_epoch_index = match current_epoch_index {
+                    Some(index) => index,
+                    None => {
+                        let height = view_client.status().await?.full_sync_height;
+                        *current_epoch_index.insert(self.epoch_index(clients, height).await?)
+                    }
+                };
+                let start_epoch_index = self
+                    .epoch_index(clients, unbonding_token.unbonding_start_height())
+                    .await?;
+                let penalty: Penalty = clients
+                    .stake
+                    .validator_penalty(tonic::Request::new(ValidatorPenaltyRequest {
+                        identity_key: Some(unbonding_token.validator().into()),
+                        start_epoch_index,
+                        end_epoch_index,
+                    }))
+                    .await?
+                    .into_inner()
+                    .penalty
+                    .context("no penalty returned for validator")?
+                    .try_into()?;
+                balance.add_unbonding(&penalty, total_amount);
+            }
         }
-        Ok(total_um_equivalent_amount)
+        Ok(balance)
+    }
+
+    /// Look up the index of the epoch containing `height`.
+    async fn epoch_index(&self, clients: &mut PdClients, height: u64) -> Result<u64> {
+        Ok(clients
+            .sct
+            .epoch_by_height(EpochByHeightRequest { height })
+            .await?
+            .into_inner()
+            .epoch
+            .context("no epoch returned for height")?
+            .index)
     }
 
     /// Execute the specified command.
@@ -384,168 +596,260 @@ impl Opt {
                 let pmonitor_config = PmonitorConfig::new(grpc_url.clone(), accounts);
 
                 // Save the config
-                let config_path = opt.home.join("pmonitor_config.toml");
+                let config_path = self.config_path();
                 fs::write(config_path, toml::to_string(&pmonitor_config)?)?;
 
                 Ok(())
             }
-            Command::Audit {} => {
-                // Parse the config file to get the accounts to monitor.
-                //
-                // Note that each logical genesis entry might now have one or more FVKs, depending on if the
-                // user migrated their account to a new FVK, i.e. if they migrated once, they'll have two
-                // FVKs. This can happen an unlimited number of times.
-                let config_path = opt.home.join("pmonitor_config.toml");
-                let pmonitor_config: PmonitorConfig =
-                    toml::from_str(&fs::read_to_string(config_path.clone()).context(format!(
-                        "failed to load pmonitor config file: {}",
-                        config_path
-                    ))?)?;
-
-                let mut stake_client = StakeQueryServiceClient::new(
-                    self.pd_channel(pmonitor_config.grpc_url()).await?,
-                );
-
-                // Sync each wallet to the latest block height, check for new migrations, and check the balance.
-                let mut updated_config = pmonitor_config.clone();
-                let mut config_updated = false;
-
-                let num_accounts = pmonitor_config.accounts().len();
-
-                // Create bucket for documenting non-compliant FVKs, for reporting in summary.
-                let mut failures: Vec<&AccountConfig> = vec![];
-
-                for (index, config) in pmonitor_config.accounts().iter().enumerate() {
-                    let active_fvk = config.active_fvk();
-                    let active_path = self.wallet_path(&config.active_uuid());
-                    tracing::info!(
-                        "syncing wallet {}/{}: {}",
-                        index + 1,
-                        num_accounts,
-                        active_path.to_string()
-                    );
-
-                    let mut view_client = self
-                        .view(
-                            active_path.clone(),
-                            active_fvk.clone(),
-                            pmonitor_config.grpc_url(),
-                        )
-                        .await?;
-
-                    // todo: do this in parallel?
-                    self.sync(&mut view_client).await?;
-                    tracing::debug!("finished syncing wallet {}/{}", index + 1, num_accounts);
-
-                    // Check if the account has been migrated
-                    let storage = Storage::load_or_initialize(
-                        Some(active_path.join(VIEW_FILE_NAME)),
-                        &active_fvk,
-                        pmonitor_config.grpc_url(),
-                    )
-                    .await?;
-
-                    let migration_tx = storage
-                        .transactions_matching_memo(format!(
-                            // N.B. the `%` symbol is an SQLite wildcard, required to match the
-                            // remainder of the memo field.
-                            "Migrating balance from {}%",
-                            active_fvk.to_string()
-                        ))
-                        .await?;
-                    if migration_tx.is_empty() {
-                        tracing::debug!(
-                            "account has not been migrated, continuing using existing FVK..."
-                        );
-                    } else if migration_tx.len() == 1 {
-                        tracing::warn!(
-                            "❗ account has been migrated to new FVK, continuing using new FVK..."
-                        );
-                        let (_, _, _tx, memo_text) = &migration_tx[0];
-                        let new_fvk = parse_dest_fvk_from_memo(&memo_text)?;
-                        let wallet_id = Uuid::new_v4();
-                        let wallet_dir = self.wallet_path(&wallet_id);
-                        self.create_wallet(&wallet_dir, &new_fvk, &pmonitor_config.grpc_url())
-                            .await?;
-
-                        let new_fvk_entry = FvkEntry {
-                            fvk: new_fvk.clone(),
-                            wallet_id,
-                        };
-                        // Mark that the config needs to get saved again for the next time we run the audit command.
-                        config_updated = true;
-
-                        // We need to update the config with the new FVK and path on disk
-                        // to the wallet for the next time we run the audit command.
-                        let mut new_config_entry = config.clone();
-                        new_config_entry.add_migration(new_fvk_entry);
-                        updated_config.set_account(index, new_config_entry.clone());
-
-                        view_client = self
-                            .view(wallet_dir, new_fvk.clone(), pmonitor_config.grpc_url())
-                            .await?;
-
-                        tracing::info!("syncing migrated wallet");
-                        self.sync(&mut view_client).await?;
-                        tracing::info!("finished syncing migrated wallet");
-                        // Now we can exit the else if statement and continue by computing the balance,
-                        // which will use the new migrated wallet.
-                    } else {
-                        // we expect a single migration tx per FVK, if this assumption is violated we should bail.
-                        anyhow::bail!(
-                            "Expected a single migration tx, found {}",
-                            migration_tx.len()
+            Command::Audit {
+                watch,
+                report: report_path,
+                baseline,
+            } => {
+                let pmonitor_config = self.load_config()?;
+                match watch.or(pmonitor_config.watch_interval()) {
+                    None => {
+                        // Load the baseline first, so that a bad path fails before syncing.
+                        let baseline = baseline.as_ref().map(load_report).transpose()?;
+                        let mut clients = PdClients::new(
+                            self.pd_channel_with_fallback(pmonitor_config.grpc_urls())
+                                .await?,
                         );
+                        let report = self.audit(&mut clients).await?;
+                        let compliance = ComplianceReport::from(&report);
+                        if let Some(path) = report_path {
+                            fs::write(path, serde_json::to_string_pretty(&compliance)?)
+                                .with_context(|| format!("failed to save report: {path}"))?;
+                        }
+                        if let Some(baseline) = baseline {
+                            emit_diff_message(&compliance.diff(&baseline));
+                        }
+                        // Print summary message
+                        emit_summary_message(&report.accounts, &report.failures)
                     }
+                    Some(interval) => self.watch(interval).await,
+                }
+            }
+        }
+    }
 
-                    let current_um_equivalent_amount = self
-                        .compute_um_equivalent_balance(&mut view_client, &mut stake_client)
-                        .await?;
-
-                    tracing::debug!("original FVK: {:?}", config.original_fvk());
-
-                    let genesis_um_equivalent_amount = config.genesis_balance();
-                    // Let the user know if the balance is unexpected or not
-                    if check_wallet_compliance(
-                        genesis_um_equivalent_amount,
-                        current_um_equivalent_amount,
-                    ) {
-                        tracing::info!(
-                            ?genesis_um_equivalent_amount,
-                            ?current_um_equivalent_amount,
-                            "✅ expected balance! current balance is within compliant range of the genesis balance",
-                        );
-                    } else {
-                        tracing::error!(
-                            ?genesis_um_equivalent_amount,
-                            ?current_um_equivalent_amount,
-                            "❌ unexpected balance! current balance is less than the genesis balance, by more than {ALLOWED_DISCREPANCY}UM",
-                        );
-                        failures.push(config);
+    /// The path to the `pmonitor` config file.
+    fn config_path(&self) -> Utf8PathBuf {
+        self.home.join("pmonitor_config.toml")
+    }
+
+    /// Load the `pmonitor` config file.
+    fn load_config(&self) -> Result<PmonitorConfig> {
+        let config_path = self.config_path();
+        PmonitorConfig::from_toml(&fs::read_to_string(config_path.clone()).context(format!(
+            "failed to load pmonitor config file: {}",
+            config_path
+        ))?)
+        .with_context(|| format!("failed to parse pmonitor config file: {}", config_path))
+    }
+
+    /// Audit all configured wallets continuously, every `interval`.
+    ///
+    /// The first cycle reports the full compliance state, subsequent cycles
+    /// only report violations that appeared or were resolved since the previous cycle.
+    /// The gRPC connection to `pd` is reused across cycles, and re-established
+    /// on the next cycle if a cycle fails.
+    pub async fn watch(&self, interval: Duration) -> Result<()> {
+        tracing::info!(?interval, "running audit in watch mode");
+        let mut clients: Option<PdClients> = None;
+        let mut state = WatchState::default();
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            let client = match clients.take() {
+                Some(client) => clients.insert(client),
+                None => {
+                    let grpc_urls = match self.load_config() {
+                        Ok(config) => config.grpc_urls().to_vec(),
+                        Err(e) => {
+                            tracing::error!(?e, "failed to load config, retrying next cycle");
+                            continue;
+                        }
+                    };
+                    match self.pd_channel_with_fallback(&grpc_urls).await {
+                        Ok(channel) => clients.insert(PdClients::new(channel)),
+                        Err(e) => {
+                            tracing::error!(?e, "failed to connect to pd, retrying next cycle");
+                            continue;
+                        }
                     }
                 }
+            };
 
-                // If at any point we marked the config for updating, we need to save it.
-                if config_updated {
-                    fs::write(config_path.clone(), toml::to_string(&updated_config)?)?;
+            let report = match self.audit(client).await {
+                Ok(report) => report,
+                Err(e) => {
+                    tracing::error!(?e, "audit cycle failed, reconnecting next cycle");
+                    clients = None;
+                    continue;
                 }
+            };
 
-                // Print summary message
-                emit_summary_message(pmonitor_config.accounts(), failures)?;
+            match state.observe(report.violations()) {
+                CycleReport::Full => {
+                    // Violations are reported by the summary itself, so the
+                    // resulting error is only relevant to one-shot runs.
+                    let _ = emit_summary_message(&report.accounts, &report.failures);
+                }
+                CycleReport::Delta(delta) => emit_delta_message(&delta),
+            }
+        }
+    }
 
-                Ok(())
+    /// Sync all configured wallets to the latest block height, and check their compliance.
+    pub async fn audit(&self, clients: &mut PdClients) -> Result<AuditReport> {
+        // Parse the config file to get the accounts to monitor.
+        //
+        // Note that each logical genesis entry might now have one or more FVKs, depending on if the
+        // user migrated their account to a new FVK, i.e. if they migrated once, they'll have two
+        // FVKs. This can happen an unlimited number of times.
+        let config_path = self.config_path();
+        let pmonitor_config = self.load_config()?;
+
+        // Sync each wallet to the latest block height, check for new migrations, and check the balance.
+        let mut updated_config = pmonitor_config.clone();
+        let mut config_updated = false;
+
+        let num_accounts = pmonitor_config.accounts().len();
+
+        // Create bucket for documenting non-compliant FVKs, for reporting in summary.
+        let mut failures: Vec<AccountConfig> = vec![];
+
+        for (index, config) in pmonitor_config.accounts().iter().enumerate() {
+            let active_fvk = config.active_fvk();
+            let active_path = self.wallet_path(&config.active_uuid());
+            tracing::info!(
+                "syncing wallet {}/{}: {}",
+                index + 1,
+                num_accounts,
+                active_path.to_string()
+            );
+
+            let mut view_client = self
+                .view(
+                    active_path.clone(),
+                    active_fvk.clone(),
+                    pmonitor_config.grpc_url(),
+                )
+                .await?;
+
+            // todo: do this in parallel?
+            self.sync(&mut view_client).await?;
+            tracing::debug!("finished syncing wallet {}/{}", index + 1, num_accounts);
+
+            // Check if the account has been migrated
+            let storage = Storage::load_or_initialize(
+                Some(active_path.join(VIEW_FILE_NAME)),
+                &active_fvk,
+                pmonitor_config.grpc_url(),
+            )
+            .await?;
+
+            let migration_tx = storage
+                .transactions_matching_memo(format!(
+                    // N.B. the `%` symbol is an SQLite wildcard, required to match the
+                    // remainder of the memo field.
+                    "Migrating balance from {}%",
+                    active_fvk.to_string()
+                ))
+                .await?;
+            if migration_tx.is_empty() {
+                tracing::debug!("account has not been migrated, continuing using existing FVK...");
+            } else if migration_tx.len() == 1 {
+                tracing::warn!(
+                    "❗ account has been migrated to new FVK, continuing using new FVK..."
+                );
+                let (_, _, _tx, memo_text) = &migration_tx[0];
+                let new_fvk = parse_dest_fvk_from_memo(&memo_text)?;
+                let wallet_id = Uuid::new_v4();
+                let wallet_dir = self.wallet_path(&wallet_id);
+                self.create_wallet(&wallet_dir, &new_fvk, &pmonitor_config.grpc_url())
+                    .await?;
+
+                let new_fvk_entry = FvkEntry {
+                    fvk: new_fvk.clone(),
+                    wallet_id,
+                };
+                // Mark that the config needs to get saved again for the next time we run the audit command.
+                config_updated = true;
+
+                // We need to update the config with the new FVK and path on disk
+                // to the wallet for the next time we run the audit command.
+                let mut new_config_entry = config.clone();
+                new_config_entry.add_migration(new_fvk_entry);
+                updated_config.set_account(index, new_config_entry.clone());
+
+                view_client = self
+                    .view(wallet_dir, new_fvk.clone(), pmonitor_config.grpc_url())
+                    .await?;
+
+                tracing::info!("syncing migrated wallet");
+                self.sync(&mut view_client).await?;
+                tracing::info!("finished syncing migrated wallet");
+                // Now we can exit the else if statement and continue by computing the balance,
+                // which will use the new migrated wallet.
+            } else {
+                // we expect a single migration tx per FVK, if this assumption is violated we should bail.
+                anyhow::bail!(
+                    "Expected a single migration tx, found {}",
+                    migration_tx.len()
+                );
             }
+
+            let current_balance = self
+                .compute_um_equivalent_balance(&mut view_client, clients)
+                .await?;
+            let current_um_equivalent_amount = current_balance.total();
+            let (liquid_amount, staked_amount) = (current_balance.liquid, current_balance.staked);
+
+            tracing::debug!("original FVK: {:?}", config.original_fvk());
+
+            let genesis_um_equivalent_amount = config.genesis_balance();
+            // Let the user know if the balance is unexpected or not
+            if check_wallet_compliance(genesis_um_equivalent_amount, current_um_equivalent_amount) {
+                tracing::info!(
+                    ?genesis_um_equivalent_amount,
+                    ?current_um_equivalent_amount,
+                    ?liquid_amount,
+                    ?staked_amount,
+                    "✅ expected balance! current balance is within compliant range of the genesis balance",
+                );
+            } else {
+                tracing::error!(
+                    ?genesis_um_equivalent_amount,
+                    ?current_um_equivalent_amount,
+                    ?liquid_amount,
+                    ?staked_amount,
+                    "❌ unexpected balance! current balance is less than the genesis balance, by more than {ALLOWED_DISCREPANCY}UM",
+                );
+                failures.push(config.clone());
+            }
+        }
+
+        // If at any point we marked the config for updating, we need to save it.
+        if config_updated {
+            fs::write(config_path.clone(), toml::to_string(&updated_config)?)?;
         }
+
+        Ok(AuditReport {
+            accounts: updated_config.accounts().clone(),
+            failures,
+        })
     }
 }
 
 /// Prepare a human-readable text summary at the end of the audit run.
 /// This is important, as errors logged during scanning are likely to be off-screen
 /// due to backscroll.
-fn emit_summary_message(
-    all_accounts: &Vec<AccountConfig>,
-    failures: Vec<&AccountConfig>,
-) -> Result<()> {
+fn emit_summary_message(all_accounts: &[AccountConfig], failures: &[AccountConfig]) -> Result<()> {
     println!("#######################");
     println!("Summary of FVK scanning");
     println!("#######################");
@@ -563,16 +867,71 @@ fn emit_summary_message(
         println!("{}", failure_count.red());
         println!("The non-compliant FVKs are:");
         println!("");
-        for f in &failures {
+        for f in failures {
             println!("\t* {}", f.active_fvk().to_string());
         }
         println!("");
-        // println!("{}", "Error: non-compliant balances were detected".red());
-        anyhow::bail!("non-compliant balances were detected".red());
+        return Err(ViolationsFound {
+            count: failures.len(),
+        }
+        .into());
     }
     Ok(())
 }
 
+/// Report the compliance changes since the previous audit cycle, in watch mode.
+fn emit_delta_message(delta: &ViolationDelta) {
+    if delta.is_empty() {
+        tracing::info!("no compliance changes since the previous audit cycle");
+        return;
+    }
+    for fvk in &delta.appeared {
+        println!("{}", format!("New violation detected: {}", fvk).red());
+    }
+    for fvk in &delta.resolved {
+        println!("{}", format!("Violation resolved: {}", fvk).green());
+    }
+}
+
+/// Load a compliance report saved by a previous run.
+fn load_report(path: &Utf8PathBuf) -> Result<ComplianceReport> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("failed to load baseline report: {path}"))?;
+    serde_json::from_str(&json).with_context(|| format!("malformed baseline report: {path}"))
+}
+
+/// Report the compliance changes since the baseline report.
+fn emit_diff_message(diff: &ReportDiff) {
+    println!("#################################");
+    println!("Compliance changes since baseline");
+    println!("#################################");
+    for fvk in &diff.newly_violating {
+        println!("{}", format!("Newly in violation: {}", fvk).red());
+    }
+    for fvk in &diff.newly_compliant {
+        println!("{}", format!("Newly compliant: {}", fvk).green());
+    }
+    let status = |compliant: bool| {
+        if compliant {
+            "compliant"
+        } else {
+            "in violation"
+        }
+    };
+    for (fvk, compliant) in &diff.added {
+        println!("Added since baseline ({}): {}", status(*compliant), fvk);
+    }
+    for (fvk, compliant) in &diff.removed {
+        println!(
+            "Removed since baseline (was {}): {}",
+            status(*compliant),
+            fvk
+        );
+    }
+    println!("Number unchanged: {}", diff.unchanged.len());
+    println!("");
+}
+
 /// Check whether the wallet is compliant.
 ///
 /// Rather than a naive comparison that the current balance is greater than or
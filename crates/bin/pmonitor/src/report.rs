@@ -0,0 +1,125 @@
+//! Logic for saving audit results as JSON reports, and comparing them across runs.
+//!
+//! A report records the compliance status of each audited account, identified by
+//! its original FVK. Diffing the current report against a saved baseline shows how
+//! compliance drifted since then.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::AuditReport;
+
+/// The compliance status of every account audited by a single run.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    /// Whether each account was compliant, keyed by its original FVK.
+    pub accounts: BTreeMap<String, bool>,
+}
+
+impl From<&AuditReport> for ComplianceReport {
+    fn from(report: &AuditReport) -> Self {
+        let violations = report.violations();
+        Self {
+            accounts: report
+                .accounts
+                .iter()
+                .map(|account| {
+                    let fvk = account.original_fvk().to_string();
+                    let compliant = !violations.contains(&fvk);
+                    (fvk, compliant)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ComplianceReport {
+    /// Compare this report against an earlier `baseline` report.
+    pub fn diff(&self, baseline: &ComplianceReport) -> ReportDiff {
+        let mut diff = ReportDiff::default();
+        for (fvk, &compliant) in &self.accounts {
+            match baseline.accounts.get(fvk) {
+                None => diff.added.push((fvk.clone(), compliant)),
+                Some(&was_compliant) if was_compliant == compliant => {
+                    diff.unchanged.push(fvk.clone())
+                }
+                Some(_) if compliant => diff.newly_compliant.push(fvk.clone()),
+                Some(_) => diff.newly_violating.push(fvk.clone()),
+            }
+        }
+        for (fvk, &was_compliant) in &baseline.accounts {
+            if !self.accounts.contains_key(fvk) {
+                diff.removed.push((fvk.clone(), was_compliant));
+            }
+        }
+        diff
+    }
+}
+
+/// The changes in compliance between a baseline report and the current one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReportDiff {
+    /// Accounts that are in violation now, but were compliant in the baseline.
+    pub newly_violating: Vec<String>,
+    /// Accounts that are compliant now, but were in violation in the baseline.
+    pub newly_compliant: Vec<String>,
+    /// Accounts whose compliance is the same in both reports.
+    pub unchanged: Vec<String>,
+    /// Accounts only present in the current report, with their compliance.
+    pub added: Vec<(String, bool)>,
+    /// Accounts only present in the baseline report, with their compliance then.
+    pub removed: Vec<(String, bool)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(accounts: &[(&str, bool)]) -> ComplianceReport {
+        ComplianceReport {
+            accounts: accounts
+                .iter()
+                .map(|(fvk, compliant)| (fvk.to_string(), *compliant))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn diff_reports_appearing_violation() {
+        let baseline = report(&[("alice", true), ("bob", true), ("carol", false)]);
+        let current = report(&[("alice", true), ("bob", false), ("carol", true)]);
+
+        assert_eq!(
+            current.diff(&baseline),
+            ReportDiff {
+                newly_violating: vec!["bob".to_string()],
+                newly_compliant: vec!["carol".to_string()],
+                unchanged: vec!["alice".to_string()],
+                added: vec![],
+                removed: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn diff_reports_added_and_removed_accounts() {
+        let baseline = report(&[("alice", true), ("bob", false)]);
+        let current = report(&[("alice", true), ("dave", false)]);
+
+        let diff = current.diff(&baseline);
+        assert_eq!(diff.unchanged, vec!["alice".to_string()]);
+        assert_eq!(diff.added, vec![("dave".to_string(), false)]);
+        assert_eq!(diff.removed, vec![("bob".to_string(), false)]);
+        assert!(diff.newly_violating.is_empty());
+    }
+
+    #[test]
+    fn report_round_trips_through_json() {
+        let report = report(&[("alice", true), ("bob", false)]);
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ComplianceReport>(&json).unwrap(),
+            report
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Logic for running `pmonitor audit` continuously, in "watch" mode.
+//!
+//! The first audit cycle reports the full compliance state. Subsequent cycles
+//! only report accounts whose compliance status changed since the previous cycle.
+use std::collections::BTreeSet;
+
+/// The change in the set of non-compliant accounts between two audit cycles.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ViolationDelta {
+    /// Accounts that are in violation now, but were not in the previous cycle.
+    pub appeared: Vec<String>,
+    /// Accounts that were in violation in the previous cycle, but are compliant now.
+    pub resolved: Vec<String>,
+}
+
+impl ViolationDelta {
+    /// Compute the delta between the violations of two consecutive cycles.
+    pub fn between(previous: &BTreeSet<String>, current: &BTreeSet<String>) -> Self {
+        Self {
+            appeared: current.difference(previous).cloned().collect(),
+            resolved: previous.difference(current).cloned().collect(),
+        }
+    }
+
+    /// Whether compliance status was unchanged between the two cycles.
+    pub fn is_empty(&self) -> bool {
+        self.appeared.is_empty() && self.resolved.is_empty()
+    }
+}
+
+/// What should be reported at the end of an audit cycle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CycleReport {
+    /// This is the first cycle, so the full state should be reported.
+    Full,
+    /// Only the changes since the previous cycle should be reported.
+    Delta(ViolationDelta),
+}
+
+/// Tracks the set of non-compliant accounts across audit cycles.
+#[derive(Debug, Default)]
+pub struct WatchState {
+    previous: Option<BTreeSet<String>>,
+}
+
+impl WatchState {
+    /// Record the violations found by the latest cycle, returning what should be reported.
+    pub fn observe(&mut self, violations: BTreeSet<String>) -> CycleReport {
+        let report = match &self.previous {
+            None => CycleReport::Full,
+            Some(previous) => CycleReport::Delta(ViolationDelta::between(previous, &violations)),
+        };
+        self.previous = Some(violations);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_cycle_reports_only_new_violation() {
+        let mut state = WatchState::default();
+
+        let first = BTreeSet::from(["alice".to_string()]);
+        assert_eq!(state.observe(first), CycleReport::Full);
+
+        let second = BTreeSet::from(["alice".to_string(), "bob".to_string()]);
+        assert_eq!(
+            state.observe(second),
+            CycleReport::Delta(ViolationDelta {
+                appeared: vec!["bob".to_string()],
+                resolved: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn resolved_violation_is_reported() {
+        let mut state = WatchState::default();
+        state.observe(BTreeSet::from(["alice".to_string()]));
+
+        let CycleReport::Delta(delta) = state.observe(BTreeSet::new()) else {
+            panic!("expected a delta report after the first cycle");
+        };
+        assert_eq!(delta.appeared, Vec::<String>::new());
+        assert_eq!(delta.resolved, vec!["alice".to_string()]);
+    }
+
+    #[test]
+    fn unchanged_cycle_has_empty_delta() {
+        let mut state = WatchState::default();
+        state.observe(BTreeSet::from(["alice".to_string()]));
+
+        let CycleReport::Delta(delta) = state.observe(BTreeSet::from(["alice".to_string()])) else {
+            panic!("expected a delta report after the first cycle");
+        };
+        assert!(delta.is_empty());
+    }
+}
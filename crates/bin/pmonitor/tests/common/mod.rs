@@ -259,9 +259,26 @@ impl PmonitorTestRunner {
         }
     }
 
+    /// Run `pmonitor audit` based on the pcli wallets and associated FVKs,
+    /// returning the exit code of the process.
+    pub fn pmonitor_audit_exit_code(&self) -> anyhow::Result<i32> {
+        let p = self.pmonitor_integration_test_dir.join("pmonitor");
+        let output = AssertCommand::cargo_bin("pmonitor")?
+            .args([
+                "--home",
+                p.to_str().expect("failed to convert pmonitor home to str"),
+                "audit",
+            ])
+            .output()?;
+        output
+            .status
+            .code()
+            .context("'pmonitor audit' was terminated by a signal")
+    }
+
     /// Halt any pre-existing local devnet for these integration tests.
     /// We assume that the port `8888` is unique to the process-compose API for this test suite.
-    fn stop_devnet(&self) -> anyhow::Result<()> {
+    pub fn stop_devnet(&self) -> anyhow::Result<()> {
         // Confirm that process-compose is installed, otherwise integration tests can't run.
         Command::new("process-compose")
             .arg("--help")
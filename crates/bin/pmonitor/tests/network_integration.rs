@@ -35,6 +35,33 @@ fn audit_passes_on_compliant_wallets() -> anyhow::Result<()> {
     // std::thread::sleep(std::time::Duration::from_secs(3600));
 
     p.pmonitor_audit()?;
+    assert_eq!(
+        p.pmonitor_audit_exit_code()?,
+        0,
+        "expected pmonitor to exit 0 on an all-compliant run"
+    );
+    Ok(())
+}
+
+#[ignore]
+#[test]
+/// Tests an operational failure for `pmonitor`: the node becomes unreachable after
+/// initialization. In this case `pmonitor` should exit 2, which is distinct from
+/// the exit code used for balance violations.
+fn audit_exits_with_operational_error_when_node_unreachable() -> anyhow::Result<()> {
+    let p = PmonitorTestRunner::new();
+    p.create_pcli_wallets()?;
+    let _network = p.start_devnet()?;
+    p.initialize_pmonitor()?;
+
+    // Halt the devnet, so that the audit can't connect to the node.
+    p.stop_devnet()?;
+
+    assert_eq!(
+        p.pmonitor_audit_exit_code()?,
+        2,
+        "expected pmonitor to exit 2 when the node is unreachable"
+    );
     Ok(())
 }
 
@@ -171,6 +198,11 @@ fn audit_fails_on_misbehaving_wallet_that_sent_funds() -> anyhow::Result<()> {
         result.is_err(),
         "expected pmonitor to fail due to missing funds"
     );
+    assert_eq!(
+        p.pmonitor_audit_exit_code()?,
+        1,
+        "expected pmonitor to exit 1 on a balance violation"
+    );
     Ok(())
 }
 
@@ -246,6 +246,74 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn concurrent_build_matches_serial_build() -> Result<()> {
+        use penumbra_dex::{
+            swap::{SwapPlaintext, SwapPlan},
+            TradingPair,
+        };
+        use penumbra_proto::DomainType;
+
+        // Generate a few notes controlled by the test address, and record them in an SCT.
+        let value = Value {
+            amount: 100u64.into(),
+            asset_id: *STAKING_TOKEN_ASSET_ID,
+        };
+        let notes: Vec<_> = (0..3)
+            .map(|_| Note::generate(&mut OsRng, &test_keys::ADDRESS_0, value))
+            .collect();
+        let mut sct = tct::Tree::new();
+        for note in &notes {
+            sct.insert(tct::Witness::Keep, note.commit()).unwrap();
+        }
+
+        // Plan several actions of each kind, so there are many proofs to generate.
+        let gm = penumbra_asset::asset::Cache::with_known_assets()
+            .get_unit("gm")
+            .unwrap();
+        let mut actions: Vec<penumbra_transaction::ActionPlan> = Vec::new();
+        for note in &notes {
+            let position = sct.witness(note.commit()).unwrap().position();
+            actions.push(SpendPlan::new(&mut OsRng, note.clone(), position).into());
+            actions.push(
+                OutputPlan::new(&mut OsRng, value, test_keys::ADDRESS_1.deref().clone()).into(),
+            );
+            let swap = SwapPlaintext::new(
+                &mut OsRng,
+                TradingPair::new(*STAKING_TOKEN_ASSET_ID, gm.id()),
+                1u64.into(),
+                0u64.into(),
+                Fee::default(),
+                test_keys::ADDRESS_0.deref().clone(),
+            );
+            actions.push(SwapPlan::new(&mut OsRng, swap).into());
+        }
+        let plan = TransactionPlan {
+            transaction_parameters: TransactionParameters {
+                expiry_height: 0,
+                fee: Fee::default(),
+                chain_id: "".into(),
+            },
+            actions,
+            detection_data: None,
+            memo: None,
+        };
+
+        let fvk = &test_keys::FULL_VIEWING_KEY;
+        let auth_data = plan.authorize(OsRng, &test_keys::SPEND_KEY)?;
+        let witness_data = plan.witness_data(&sct)?;
+
+        // The proofs are derived from the blinding factors in the plan, so proving them
+        // concurrently yields the same transaction, with its actions in plan order.
+        let serial = plan.clone().build(fvk, &witness_data, &auth_data)?;
+        let concurrent = plan
+            .build_concurrent(fvk, &witness_data, &auth_data)
+            .await?;
+        assert_eq!(serial.encode_to_vec(), concurrent.encode_to_vec());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn check_stateless_fails_on_auth_path_with_wrong_root() -> Result<()> {
         // Generate a note controlled by the test address.
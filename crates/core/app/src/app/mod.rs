@@ -37,6 +37,7 @@ use tendermint::abci::{self, Event};
 
 use tendermint::v0_37::abci::{request, response};
 use tendermint::validator::Update;
+use tokio::task::JoinHandle;
 use tokio::time::sleep;
 use tracing::{instrument, Instrument};
 
@@ -395,22 +396,41 @@ impl App {
         // Ensure that any normally-delivered transaction (originating from a user) does not contain
         // any Community Pool spends or outputs; the only place those are permitted is transactions originating
         // from the chain itself:
-        anyhow::ensure!(
-            tx.community_pool_spends().peekable().peek().is_none(),
-            "Community Pool spends are not permitted in user-submitted transactions"
-        );
-        anyhow::ensure!(
-            tx.community_pool_outputs().peekable().peek().is_none(),
-            "Community Pool outputs are not permitted in user-submitted transactions"
-        );
+        check_no_community_pool_spends(&tx)?;
 
         // Now that we've ensured that there are not any Community Pool spends or outputs, we can deliver the transaction:
         self.deliver_tx_allowing_community_pool_spends(tx).await
     }
 
+    /// Delivers a transaction whose stateless checks were started ahead of time.
+    ///
+    /// This is equivalent to [`Self::deliver_tx_bytes`], except that the proofs of the
+    /// transaction may already have been verified while earlier transactions executed.
+    pub async fn deliver_verifying_tx(&mut self, tx: VerifyingTx) -> Result<Vec<abci::Event>> {
+        let VerifyingTx { tx, stateless } = tx;
+        if let Err(e) = check_no_community_pool_spends(&tx) {
+            stateless.abort();
+            return Err(e).context("failed to deliver transaction");
+        }
+        self.execute_tx(tx, stateless)
+            .await
+            .context("failed to deliver transaction")
+    }
+
     async fn deliver_tx_allowing_community_pool_spends(
         &mut self,
         tx: Arc<Transaction>,
+    ) -> Result<Vec<abci::Event>> {
+        let stateless = spawn_check_stateless(tx.clone());
+        self.execute_tx(tx, stateless).await
+    }
+
+    /// Executes a transaction once its stateless checks, running on the `stateless`
+    /// task, and its stateful checks have passed.
+    async fn execute_tx(
+        &mut self,
+        tx: Arc<Transaction>,
+        stateless: JoinHandle<Result<()>>,
     ) -> Result<Vec<abci::Event>> {
         // Both stateful and stateless checks take the transaction as
         // verification context.  The separate clone of the Arc<Transaction>
@@ -419,10 +439,6 @@ impl App {
         // We spawn tasks for each set of checks, to do CPU-bound stateless checks
         // and I/O-bound stateful checks at the same time.
         let tx2 = tx.clone();
-        let stateless = tokio::spawn(
-            async move { tx2.check_stateless(()).await }.instrument(tracing::Span::current()),
-        );
-        let tx2 = tx.clone();
         let state2 = self.state.clone();
         let stateful = tokio::spawn(
             async move { tx2.check_historical(state2).await }.instrument(tracing::Span::current()),
@@ -644,6 +660,43 @@ impl App {
     }
 }
 
+/// A transaction whose stateless checks, including the verification of its proofs,
+/// are running ahead of its delivery.
+///
+/// The stateless checks only depend on the transaction itself, so the transactions
+/// of a block can be verified in parallel, while still being delivered to
+/// [`App::deliver_verifying_tx`] one at a time, in order.
+pub struct VerifyingTx {
+    tx: Arc<Transaction>,
+    stateless: JoinHandle<Result<()>>,
+}
+
+impl VerifyingTx {
+    /// Decodes a transaction, and starts its stateless checks on a separate task.
+    pub fn spawn(tx_bytes: &[u8]) -> Result<Self> {
+        let tx = Arc::new(Transaction::decode(tx_bytes).context("decoding transaction")?);
+        let stateless = spawn_check_stateless(tx.clone());
+        Ok(Self { tx, stateless })
+    }
+}
+
+fn spawn_check_stateless(tx: Arc<Transaction>) -> JoinHandle<Result<()>> {
+    tokio::spawn(async move { tx.check_stateless(()).await }.instrument(tracing::Span::current()))
+}
+
+/// Checks that a user-submitted transaction does not spend from or output to the Community Pool.
+fn check_no_community_pool_spends(tx: &Transaction) -> Result<()> {
+    anyhow::ensure!(
+        tx.community_pool_spends().peekable().peek().is_none(),
+        "Community Pool spends are not permitted in user-submitted transactions"
+    );
+    anyhow::ensure!(
+        tx.community_pool_outputs().peekable().peek().is_none(),
+        "Community Pool outputs are not permitted in user-submitted transactions"
+    );
+    Ok(())
+}
+
 #[async_trait]
 pub trait StateReadExt: StateRead {
     async fn get_chain_id(&self) -> Result<String> {
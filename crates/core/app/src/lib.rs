@@ -23,6 +23,7 @@ cfg_if::cfg_if! {
     if #[cfg(feature="component")] {
         pub mod app;
         pub mod metrics;
+        pub mod plan_validation;
         pub mod rpc;
         pub mod server;
 
@@ -1,10 +1,13 @@
-use std::fmt::Display;
+use std::{collections::BTreeSet, fmt::Display};
 
 use anyhow::Context;
 use anyhow::Result;
 use penumbra_auction::params::AuctionParameters;
 use penumbra_community_pool::params::CommunityPoolParameters;
-use penumbra_dex::DexParameters;
+use penumbra_dex::{
+    lp::position::MAX_FEE_BPS, DexParameters, MAX_BATCH_INTERVAL_BLOCKS,
+    MIN_BATCH_OUTPUT_RETENTION_BLOCKS,
+};
 use penumbra_distributions::params::DistributionsParameters;
 use penumbra_fee::FeeParameters;
 use penumbra_funding::params::FundingParameters;
@@ -108,6 +111,16 @@ impl AppParameters {
                     max_hops: _,
                     max_positions_per_pair: _,
                     max_execution_budget: _,
+                    batch_output_retention_blocks,
+                    min_fee_bps: _,
+                    pair_fee_floors: _,
+                    pair_swap_limits: _,
+                    batch_interval_blocks: _,
+                    swap_claim_grace_blocks: _,
+                    position_open_fee: _,
+                    pair_bootstrap_min_reserves: _,
+                    batch_output_compression_blocks: _,
+                    swap_claim_fee_assets: _,
                 },
             // IMPORTANT: Don't use `..` here! We want to ensure every single field is verified!
         } = self;
@@ -148,6 +161,14 @@ impl AppParameters {
                 "proposal slash threshold",
             ),
         ])?;
+        // Pruning batch output data would leave swaps that were never claimed with no way
+        // to recover their value, as there is no refund path, so it can only be enabled
+        // at genesis.
+        check_invariant([(
+            batch_output_retention_blocks,
+            &new.dex_params.batch_output_retention_blocks,
+            "batch output retention",
+        )])?;
 
         Ok(())
     }
@@ -203,6 +224,16 @@ impl AppParameters {
                     max_hops: _,
                     max_positions_per_pair: _,
                     max_execution_budget: _,
+                    batch_output_retention_blocks,
+                    min_fee_bps,
+                    pair_fee_floors,
+                    pair_swap_limits,
+                    batch_interval_blocks,
+                    swap_claim_grace_blocks,
+                    position_open_fee: _,
+                    pair_bootstrap_min_reserves: _,
+                    batch_output_compression_blocks: _,
+                    swap_claim_fee_assets: _,
                 },
             // IMPORTANT: Don't use `..` here! We want to ensure every single field is verified!
         } = self;
@@ -274,6 +305,48 @@ impl AppParameters {
                 *min_validator_stake >= 1_000_000u128.into(),
                 "the minimum validator stake must be at least 1penumbra",
             ),
+            (
+                *batch_output_retention_blocks == 0
+                    || *batch_output_retention_blocks >= MIN_BATCH_OUTPUT_RETENTION_BLOCKS,
+                "batch output retention must be zero or at least the minimum retention window",
+            ),
+            (
+                *batch_output_retention_blocks == 0
+                    || *swap_claim_grace_blocks < *batch_output_retention_blocks,
+                "swap claim grace period must end before batch output data is pruned",
+            ),
+            (
+                *batch_interval_blocks <= MAX_BATCH_INTERVAL_BLOCKS,
+                "batch interval must be at most 100 blocks",
+            ),
+            (
+                *min_fee_bps <= MAX_FEE_BPS,
+                "minimum position fee must be at most the maximum position fee",
+            ),
+            (
+                pair_fee_floors
+                    .iter()
+                    .all(|floor| floor.min_fee_bps <= MAX_FEE_BPS),
+                "pair fee floors must be at most the maximum position fee",
+            ),
+            (
+                pair_fee_floors
+                    .iter()
+                    .map(|floor| floor.pair)
+                    .collect::<BTreeSet<_>>()
+                    .len()
+                    == pair_fee_floors.len(),
+                "pair fee floors must not repeat a trading pair",
+            ),
+            (
+                pair_swap_limits
+                    .iter()
+                    .map(|limit| limit.pair)
+                    .collect::<BTreeSet<_>>()
+                    .len()
+                    == pair_swap_limits.len(),
+                "pair swap limits must not repeat a trading pair",
+            ),
         ])
     }
 }
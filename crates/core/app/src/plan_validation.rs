@@ -0,0 +1,210 @@
+//! Dry-run validation of the DEX actions of a transaction against chain state.
+//!
+//! This lets a client catch DEX actions that would be rejected before signing
+//! and broadcasting a transaction. The checks are advisory: the chain state can
+//! change between validation and execution, so a plan with no problems may still
+//! be rejected, and a swap may still execute at a worse price.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use cnidarium::StateRead;
+use futures::TryStreamExt as _;
+use penumbra_asset::Value;
+use penumbra_dex::{
+    component::{PositionRead as _, StateReadExt as _},
+    lp::position,
+    DirectedTradingPair,
+};
+use penumbra_num::Amount;
+use penumbra_transaction::Action;
+
+/// The share of the direct liquidity available on a pair, in percent, above which
+/// a swap is reported as relying on marginal liquidity.
+pub const MARGINAL_LIQUIDITY_PERCENT: u128 = 90;
+
+/// How serious a [`PlanProblem`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The action should execute, but is likely to fare worse than expected.
+    Warning,
+    /// The action would be rejected, or would not execute as planned.
+    Error,
+}
+
+/// A problem found with one of the actions of a plan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanProblem {
+    /// The index of the offending action in the plan.
+    pub action_index: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Validates the DEX actions of `plan` against the current chain `state`,
+/// returning the problems found, if any.
+///
+/// Swaps are only checked against liquidity on their direct pair, and swaps in the
+/// same direction are checked cumulatively, since they are executed as a batch.
+/// Actions other than DEX actions are ignored.
+pub async fn validate_dex_plan(plan: &[Action], state: &impl StateRead) -> Vec<PlanProblem> {
+    let mut problems = Vec::new();
+    // The input queued so far by the swaps of the plan, in each direction.
+    let mut batch_inputs = BTreeMap::<DirectedTradingPair, Amount>::new();
+
+    for (action_index, action) in plan.iter().enumerate() {
+        let checked = match action {
+            Action::Swap(swap) => {
+                let pair = swap.body.trading_pair;
+                let directions = [
+                    (pair.asset_1(), pair.asset_2(), swap.body.delta_1_i),
+                    (pair.asset_2(), pair.asset_1(), swap.body.delta_2_i),
+                ];
+                let mut checked = check_dex_enabled(state).await;
+                for (start, end, input) in directions {
+                    if !matches!(checked, Ok(None)) {
+                        break;
+                    }
+                    if input == Amount::zero() {
+                        continue;
+                    }
+                    let direction = DirectedTradingPair::new(start, end);
+                    let batch_input = batch_inputs.entry(direction).or_default();
+                    *batch_input += input;
+                    checked = check_swap_liquidity(state, direction, *batch_input).await;
+                }
+                checked
+            }
+            Action::PositionOpen(open) => check_position_open(state, &open.position).await,
+            Action::PositionClose(close) => check_position_close(state, &close.position_id).await,
+            Action::PositionWithdraw(withdraw) => {
+                check_position_withdraw(state, &withdraw.position_id, withdraw.sequence).await
+            }
+            _ => Ok(None),
+        };
+
+        match checked {
+            Ok(None) => {}
+            Ok(Some((severity, message))) => problems.push(PlanProblem {
+                action_index,
+                severity,
+                message,
+            }),
+            Err(e) => problems.push(PlanProblem {
+                action_index,
+                severity: Severity::Error,
+                message: format!("unable to validate action: {e:#}"),
+            }),
+        }
+    }
+
+    problems
+}
+
+/// The outcome of checking a single action: `None` if no problem was found.
+type Checked = Result<Option<(Severity, String)>>;
+
+fn error(message: String) -> Checked {
+    Ok(Some((Severity::Error, message)))
+}
+
+fn warning(message: String) -> Checked {
+    Ok(Some((Severity::Warning, message)))
+}
+
+async fn check_dex_enabled(state: &impl StateRead) -> Checked {
+    if !state.get_dex_params().await?.is_enabled {
+        return error("the dex is disabled".to_string());
+    }
+    Ok(None)
+}
+
+/// Checks that `input` can be filled against the direct liquidity along `pair`.
+async fn check_swap_liquidity(
+    state: &impl StateRead,
+    pair: DirectedTradingPair,
+    input: Amount,
+) -> Checked {
+    let positions: Vec<_> = state.positions_by_price(&pair).try_collect().await?;
+
+    let mut unfilled = input;
+    let mut output = Amount::zero();
+    let mut available = Amount::zero();
+    for (_, position) in positions.iter() {
+        available += position.reserves_for(pair.end).unwrap_or_default();
+        if unfilled == Amount::zero() {
+            continue;
+        }
+        let (remaining, _, filled) = position.fill(Value {
+            amount: unfilled,
+            asset_id: pair.start,
+        })?;
+        unfilled = remaining.amount;
+        output += filled.amount;
+    }
+
+    let (start, end) = (pair.start, pair.end);
+    if output == Amount::zero() {
+        return error(format!("no direct liquidity for {start} => {end}"));
+    }
+    if unfilled > Amount::zero() {
+        return error(format!(
+            "insufficient direct liquidity for {start} => {end}: {unfilled} of {input} would be unfilled"
+        ));
+    }
+    if output.value().saturating_mul(100)
+        > available.value().saturating_mul(MARGINAL_LIQUIDITY_PERCENT)
+    {
+        return warning(format!(
+            "marginal direct liquidity for {start} => {end}: swap takes {output} of {available} available"
+        ));
+    }
+    Ok(None)
+}
+
+async fn check_position_open(state: &impl StateRead, position: &position::Position) -> Checked {
+    if let Some(problem) = check_dex_enabled(state).await? {
+        return Ok(Some(problem));
+    }
+    if state.position_by_id(&position.id()).await?.is_some() {
+        return error(format!("position {} already exists", position.id()));
+    }
+    Ok(None)
+}
+
+async fn check_position_close(state: &impl StateRead, id: &position::Id) -> Checked {
+    let Some(position) = state.position_by_id(id).await? else {
+        return error(format!("position {id} does not exist"));
+    };
+    match position.state {
+        position::State::Opened => Ok(None),
+        position::State::Closed => warning(format!("position {id} is already closed")),
+        other => error(format!("position {id} has state {other}, expected Opened")),
+    }
+}
+
+async fn check_position_withdraw(
+    state: &impl StateRead,
+    id: &position::Id,
+    sequence: u64,
+) -> Checked {
+    let Some(position) = state.position_by_id(id).await? else {
+        return error(format!("position {id} does not exist"));
+    };
+    // The same sequencing as the withdrawal itself, treating "closed" as sequence -1.
+    let expected = match position.state {
+        position::State::Closed => 0,
+        position::State::Withdrawn { sequence } => sequence + 1,
+        other => {
+            return error(format!(
+                "position {id} has state {other}, expected Closed or Withdrawn"
+            ))
+        }
+    };
+    if sequence != expected {
+        return error(format!(
+            "withdrawal of position {id} has sequence {sequence}, expected {expected}"
+        ));
+    }
+    Ok(None)
+}
@@ -21,8 +21,12 @@ pub mod snapshot;
 mod events;
 
 /// Returns a newly instantiated ABCI [`Server`], backed by the provided [`Storage`].
+///
+/// The proofs of up to `verification_parallelism` queued transactions are verified at
+/// once; see [`Consensus::with_verification_parallelism`].
 pub fn new(
     storage: Storage,
+    verification_parallelism: usize,
 ) -> Server<
     // These bounds ensure that the server can be bound to a TCP port, or a Unix socket.
     impl tower_service::Service<
@@ -50,7 +54,10 @@ pub fn new(
             req.create_span()
         }))
         .layer(EventIndexLayer::index_all())
-        .service(Consensus::new(storage.clone()));
+        .service(Consensus::with_verification_parallelism(
+            storage.clone(),
+            verification_parallelism,
+        ));
     let mempool = tower::ServiceBuilder::new()
         .layer(request_span::layer(|req: &MempoolRequest| {
             use penumbra_tower_trace::v037::RequestExt;
@@ -80,7 +87,7 @@ mod bounds_test {
     async fn servers_can_listen() {
         let storage: cnidarium::Storage = todo!();
         let addr: std::net::SocketAddr = todo!();
-        let server = super::new(storage).listen_tcp(addr);
+        let server = super::new(storage, 1).listen_tcp(addr);
         drop(server);
     }
 }
@@ -10,12 +10,14 @@ use tower::BoxError;
 use tower_actor::Message;
 use tracing::Instrument;
 
-use crate::app::App;
+use crate::app::{App, VerifyingTx};
 
 pub struct Consensus {
     queue: mpsc::Receiver<Message<Request, Response, tower::BoxError>>,
     storage: Storage,
     app: App,
+    /// The maximum number of queued transactions whose proofs are verified in parallel.
+    verification_parallelism: usize,
 }
 
 pub type ConsensusService = tower_actor::Actor<Request, Response, BoxError>;
@@ -35,14 +37,32 @@ impl Consensus {
     const QUEUE_SIZE: usize = 10;
 
     pub fn new(storage: Storage) -> ConsensusService {
-        tower_actor::Actor::new(Self::QUEUE_SIZE, |queue: _| {
-            Consensus::new_inner(storage, queue).run()
-        })
+        Self::with_verification_parallelism(storage, 1)
+    }
+
+    /// Constructs a consensus service that verifies the proofs of up to
+    /// `verification_parallelism` transactions at once.
+    ///
+    /// When CometBFT delivers the transactions of a block faster than they execute,
+    /// as during catch-up sync, the transactions waiting in the queue have their
+    /// stateless checks started together. Transactions are still checked against and
+    /// applied to the state one at a time, in order, so the resulting state is the
+    /// same as with serial verification.
+    pub fn with_verification_parallelism(
+        storage: Storage,
+        verification_parallelism: usize,
+    ) -> ConsensusService {
+        let verification_parallelism = verification_parallelism.max(1);
+        tower_actor::Actor::new(
+            Self::QUEUE_SIZE.max(verification_parallelism),
+            move |queue: _| Consensus::new_inner(storage, queue, verification_parallelism).run(),
+        )
     }
 
     fn new_inner(
         storage: Storage,
         queue: mpsc::Receiver<Message<Request, Response, tower::BoxError>>,
+        verification_parallelism: usize,
     ) -> Self {
         let app = App::new(storage.latest_snapshot());
 
@@ -50,16 +70,45 @@ impl Consensus {
             queue,
             storage,
             app,
+            verification_parallelism,
         }
     }
 
     async fn run(mut self) -> Result<(), tower::BoxError> {
-        while let Some(Message {
-            req,
-            rsp_sender,
-            span,
-        }) = self.queue.recv().await
-        {
+        // A request taken from the queue while collecting a batch of transactions.
+        let mut next = None;
+        loop {
+            let message = match next.take() {
+                Some(message) => message,
+                None => match self.queue.recv().await {
+                    Some(message) => message,
+                    None => break,
+                },
+            };
+
+            if self.verification_parallelism > 1 && matches!(message.req, Request::DeliverTx(_)) {
+                let mut batch = vec![message];
+                while batch.len() < self.verification_parallelism {
+                    match self.queue.try_recv() {
+                        Ok(message) if matches!(message.req, Request::DeliverTx(_)) => {
+                            batch.push(message)
+                        }
+                        Ok(message) => {
+                            next = Some(message);
+                            break;
+                        }
+                        Err(_) => break,
+                    }
+                }
+                self.deliver_tx_batch(batch).await;
+                continue;
+            }
+
+            let Message {
+                req,
+                rsp_sender,
+                span,
+            } = message;
             // The send only fails if the receiver was dropped, which happens
             // if the caller didn't propagate the message back to tendermint
             // for some reason -- but that's not our problem.
@@ -197,24 +246,44 @@ impl Consensus {
         // Unlike the other messages, DeliverTx is fallible, so
         // inspect the response to report errors.
         let rsp = self.app.deliver_tx_bytes(deliver_tx.tx.as_ref()).await;
+        deliver_tx_response(rsp)
+    }
 
-        match rsp {
-            Ok(events) => {
-                trace_events(&events);
-                response::DeliverTx {
-                    events,
-                    ..Default::default()
-                }
-            }
-            Err(e) => {
-                tracing::info!(?e, "deliver_tx failed");
-                response::DeliverTx {
-                    code: 1.into(),
-                    // Use the alternate format specifier to include the chain of error causes.
-                    log: format!("{e:#}"),
-                    ..Default::default()
+    /// Delivers a batch of queued transactions, verifying their proofs in parallel.
+    async fn deliver_tx_batch(&mut self, batch: Vec<Message<Request, Response, tower::BoxError>>) {
+        tracing::debug!(
+            batch_size = batch.len(),
+            "verifying transactions in parallel"
+        );
+        let batch: Vec<_> = batch
+            .into_iter()
+            .map(
+                |Message {
+                     req,
+                     rsp_sender,
+                     span,
+                 }| {
+                    let Request::DeliverTx(deliver_tx) = req else {
+                        unreachable!("batches only contain DeliverTx requests");
+                    };
+                    let tx = span.in_scope(|| VerifyingTx::spawn(deliver_tx.tx.as_ref()));
+                    (tx, rsp_sender, span)
+                },
+            )
+            .collect();
+
+        for (tx, rsp_sender, span) in batch {
+            let rsp = match tx {
+                Ok(tx) => {
+                    self.app
+                        .deliver_verifying_tx(tx)
+                        .instrument(span.clone())
+                        .await
                 }
-            }
+                Err(e) => Err(e),
+            };
+            let rsp = span.in_scope(|| deliver_tx_response(rsp));
+            let _ = rsp_sender.send(Ok(Response::DeliverTx(rsp)));
         }
     }
 
@@ -252,3 +321,25 @@ impl Consensus {
         })
     }
 }
+
+/// Converts the result of delivering a transaction into its ABCI response.
+fn deliver_tx_response(rsp: Result<Vec<Event>>) -> response::DeliverTx {
+    match rsp {
+        Ok(events) => {
+            trace_events(&events);
+            response::DeliverTx {
+                events,
+                ..Default::default()
+            }
+        }
+        Err(e) => {
+            tracing::info!(?e, "deliver_tx failed");
+            response::DeliverTx {
+                code: 1.into(),
+                // Use the alternate format specifier to include the chain of error causes.
+                log: format!("{e:#}"),
+                ..Default::default()
+            }
+        }
+    }
+}
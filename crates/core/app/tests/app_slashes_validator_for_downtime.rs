@@ -0,0 +1,126 @@
+use {
+    self::common::{BuilderExt, ValidatorDataReadExt},
+    anyhow::Context,
+    cnidarium::TempStorage,
+    common::TempStorageExt as _,
+    penumbra_app::{
+        genesis::{self, AppState},
+        server::consensus::Consensus,
+    },
+    penumbra_mock_consensus::TestNode,
+    penumbra_proto::event::EventDomainType,
+    penumbra_stake::{
+        component::validator_handler::validator_store::ValidatorDataRead,
+        event::EventSlashingPenaltyApplied, params::StakeParameters, validator, Penalty,
+    },
+    tap::Tap,
+    tracing::{error_span, Instrument},
+};
+
+mod common;
+
+#[tokio::test]
+async fn app_slashes_validator_for_downtime() -> anyhow::Result<()> {
+    // Install a test logger, acquire some temporary storage, and start the test node.
+    let guard = common::set_tracing_subscriber();
+    let storage = TempStorage::new_with_penumbra_prefixes().await?;
+
+    // Use stake parameters that jail a validator after it misses two blocks.
+    let stake_params = StakeParameters {
+        signed_blocks_window_len: 10,
+        missed_blocks_maximum: 2,
+        ..Default::default()
+    };
+
+    // Start the test node, with two genesis validators.
+    let mut node = {
+        let app_state = AppState::Content(
+            genesis::Content {
+                stake_content: penumbra_stake::genesis::Content {
+                    stake_params: stake_params.clone(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .with_chain_id(TestNode::<()>::CHAIN_ID.to_string()),
+        );
+        let consensus = Consensus::new(storage.as_ref().clone());
+        TestNode::builder()
+            .two_validators()
+            .with_penumbra_auto_app_state(app_state)?
+            .init_chain(consensus)
+            .await
+    }?;
+
+    // Find the identity keys of the validator that will miss blocks, and of the one that won't.
+    let absent_consensus_key = *node
+        .keyring()
+        .keys()
+        .next()
+        .context("keyring should not be empty")?;
+    let absent_tendermint_key =
+        tendermint::PublicKey::from_raw_ed25519(absent_consensus_key.as_bytes())
+            .context("consensus key should be a valid ed25519 key")?;
+    let (absent, present): (Vec<_>, Vec<_>) = storage
+        .latest_snapshot()
+        .validator_definitions()
+        .await?
+        .into_iter()
+        .partition(|validator| validator.consensus_key == absent_tendermint_key);
+    let ([absent], [present]) = (absent.as_slice(), present.as_slice()) else {
+        anyhow::bail!("expected one absent and one present validator");
+    };
+    let (absent, present) = (absent.identity_key, present.identity_key);
+
+    // The validator does not sign the third and fourth blocks.
+    node.mark_absent(absent_consensus_key, 3..=4);
+
+    // Execute a few blocks, collecting the slashing penalties applied along the way.
+    let mut penalties = Vec::new();
+    for i in 1..=6 {
+        node.block()
+            .execute()
+            .instrument(error_span!("executing block", %i))
+            .await
+            .context("executing block")?;
+        penalties.extend(
+            node.last_begin_block_events()
+                .iter()
+                .filter_map(|event| EventSlashingPenaltyApplied::try_from_event(event).ok())
+                .map(|event| (i, event)),
+        );
+    }
+
+    // The missing signatures are reported in the following blocks, so the validator is slashed
+    // when the fifth block begins.
+    let [(height, penalty)] = penalties.as_slice() else {
+        anyhow::bail!("expected exactly one slashing penalty, got: {penalties:?}");
+    };
+    assert_eq!(*height, 5, "validator should be slashed at height 5");
+    assert_eq!(penalty.identity_key, absent);
+    assert_eq!(
+        penalty.new_penalty,
+        Penalty::from_percent(0).compound(Penalty::from_bps_squared(
+            stake_params.slashing_penalty_downtime
+        )),
+        "validator should have the downtime penalty applied"
+    );
+
+    // The absent validator was jailed, while the other validator is still active.
+    let snapshot = storage.latest_snapshot();
+    assert_eq!(
+        snapshot.get_validator_state(&absent).await?,
+        Some(validator::State::Jailed),
+        "absent validator should be jailed"
+    );
+    assert_eq!(
+        snapshot.get_validator_state(&present).await?,
+        Some(validator::State::Active),
+        "present validator should still be active"
+    );
+
+    Ok(())
+        .tap(|_| drop(node))
+        .tap(|_| drop(storage))
+        .tap(|_| drop(guard))
+}
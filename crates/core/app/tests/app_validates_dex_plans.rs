@@ -0,0 +1,117 @@
+mod common;
+
+use self::common::TempStorageExt;
+use cnidarium::{StateDelta, TempStorage};
+use penumbra_app::plan_validation::{validate_dex_plan, Severity};
+use penumbra_asset::{asset, Value};
+use penumbra_dex::{
+    component::PositionManager as _,
+    lp::{
+        action::{PositionClose, PositionOpen},
+        plan::PositionWithdrawPlan,
+        position, SellOrder,
+    },
+    swap::{SwapPlaintext, SwapPlan},
+    TradingPair,
+};
+use penumbra_fee::Fee;
+use penumbra_keys::test_keys;
+use penumbra_num::Amount;
+use penumbra_sct::component::clock::EpochManager as _;
+use penumbra_transaction::Action;
+use rand_core::{CryptoRngCore, SeedableRng};
+use std::ops::Deref;
+
+/// Builds a swap of `input` for `output_asset`.
+fn swap(rng: &mut impl CryptoRngCore, input: Value, output_asset: asset::Id) -> Action {
+    let trading_pair = TradingPair::new(input.asset_id, output_asset);
+    let (delta_1, delta_2) = if trading_pair.asset_1() == input.asset_id {
+        (input.amount, Amount::zero())
+    } else {
+        (Amount::zero(), input.amount)
+    };
+    let plaintext = SwapPlaintext::new(
+        rng,
+        trading_pair,
+        delta_1,
+        delta_2,
+        Fee::default(),
+        test_keys::ADDRESS_0.deref().clone(),
+    );
+    Action::Swap(SwapPlan::new(rng, plaintext).swap(&test_keys::FULL_VIEWING_KEY))
+}
+
+#[tokio::test]
+/// Checks that validating a plan mixing valid and invalid DEX actions reports
+/// exactly the problems with the invalid ones.
+async fn app_validates_dex_plans() -> anyhow::Result<()> {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(1312);
+    let storage = TempStorage::new_with_penumbra_prefixes()
+        .await?
+        .apply_default_genesis()
+        .await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_block_height(1);
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let penumbra = asset::Cache::with_known_assets()
+        .get_unit("penumbra")
+        .unwrap();
+
+    // The only liquidity on the chain: 10gn for sale at 1gm each.
+    let sell = SellOrder::parse_str("10gn@1gm")?.into_position(&mut rng);
+    state.open_position(sell.clone()).await?;
+
+    let plan = vec![
+        // Fills comfortably against the position.
+        swap(&mut rng, gm.value(2u64.into()), gn.id()),
+        // Batched with the first swap, takes all of the position's reserves.
+        swap(&mut rng, gm.value(8u64.into()), gn.id()),
+        // Batched with both, more than the position can fill.
+        swap(&mut rng, gm.value(10u64.into()), gn.id()),
+        // There are no positions on this pair at all.
+        swap(&mut rng, gm.value(1u64.into()), penumbra.id()),
+        Action::PositionClose(PositionClose {
+            position_id: sell.id(),
+        }),
+        Action::PositionClose(PositionClose {
+            position_id: position::Id([0u8; 32]),
+        }),
+        // The position is still open, so it can't be withdrawn from.
+        Action::PositionWithdraw(
+            PositionWithdrawPlan {
+                reserves: sell.reserves.clone(),
+                position_id: sell.id(),
+                pair: sell.phi.pair,
+                sequence: 0,
+                rewards: Vec::new(),
+            }
+            .position_withdraw(),
+        ),
+        Action::PositionOpen(PositionOpen {
+            position: sell.clone(),
+            open_fee: Amount::zero(),
+        }),
+    ];
+
+    let problems = validate_dex_plan(&plan, &state).await;
+    let reported: Vec<_> = problems
+        .iter()
+        .map(|problem| (problem.action_index, problem.severity))
+        .collect();
+    assert_eq!(
+        reported,
+        vec![
+            (1, Severity::Warning),
+            (2, Severity::Error),
+            (3, Severity::Error),
+            (5, Severity::Error),
+            (6, Severity::Error),
+            (7, Severity::Error),
+        ],
+        "unexpected problems: {problems:#?}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use {
+    self::common::BuilderExt,
+    anyhow::anyhow,
+    cnidarium::TempStorage,
+    common::TempStorageExt as _,
+    penumbra_app::{
+        genesis::{self, AppState},
+        server::consensus::{Consensus, ConsensusService},
+    },
+    penumbra_keys::test_keys,
+    penumbra_mock_client::MockClient,
+    penumbra_mock_consensus::TestNode,
+    penumbra_proto::DomainType,
+    penumbra_sct::component::tree::SctRead as _,
+    penumbra_shielded_pool::{OutputPlan, SpendPlan},
+    penumbra_transaction::{
+        memo::MemoPlaintext, plan::MemoPlan, Action, Transaction, TransactionParameters,
+        TransactionPlan,
+    },
+    rand_core::OsRng,
+    std::ops::Deref,
+};
+
+mod common;
+
+/// Starts a test chain from a fixed genesis, verifying up to `verification_parallelism`
+/// transactions at once.
+async fn start_chain(
+    verification_parallelism: usize,
+) -> anyhow::Result<(TempStorage, TestNode<ConsensusService>)> {
+    let storage = TempStorage::new_with_penumbra_prefixes().await?;
+    let app_state = AppState::Content(
+        genesis::Content::default().with_chain_id(TestNode::<()>::CHAIN_ID.to_string()),
+    );
+    let consensus = Consensus::with_verification_parallelism(
+        storage.as_ref().clone(),
+        verification_parallelism,
+    );
+    let test_node = TestNode::builder()
+        .with_seed([7; 32])
+        .with_initial_timestamp(tendermint::Time::parse_from_rfc3339(
+            "2022-02-11T17:30:50.425417198Z",
+        )?)
+        .single_validator()
+        .with_penumbra_auto_app_state(app_state)?
+        .init_chain(consensus)
+        .await?;
+    Ok((storage, test_node))
+}
+
+/// Exercises that verifying the proofs of a block's transactions in parallel leaves the
+/// chain in the same state as verifying them one at a time, and that a transaction with
+/// an invalid proof is still rejected.
+#[tokio::test]
+async fn app_verifies_proofs_in_parallel() -> anyhow::Result<()> {
+    // Install a test logger, and start two identical chains.
+    let guard = common::set_tracing_subscriber();
+    let (serial_storage, mut serial_node) = start_chain(1).await?;
+    let (parallel_storage, mut parallel_node) = start_chain(8).await?;
+    assert_eq!(serial_node.last_app_hash(), parallel_node.last_app_hash());
+
+    // Sync the mock client, and write one transaction spending each of its notes.
+    let client = MockClient::new(test_keys::SPEND_KEY.clone())
+        .with_sync_to_storage(&serial_storage)
+        .await?;
+    let mut txs = Vec::new();
+    for note in client.notes.values().cloned() {
+        let plan = TransactionPlan {
+            actions: vec![
+                SpendPlan::new(
+                    &mut OsRng,
+                    note.clone(),
+                    client
+                        .position(note.commit())
+                        .ok_or_else(|| anyhow!("input note commitment was unknown"))?,
+                )
+                .into(),
+                OutputPlan::new(
+                    &mut OsRng,
+                    note.value(),
+                    test_keys::ADDRESS_1.deref().clone(),
+                )
+                .into(),
+            ],
+            memo: Some(MemoPlan::new(
+                &mut OsRng,
+                MemoPlaintext::blank_memo(test_keys::ADDRESS_0.deref().clone()),
+            )),
+            detection_data: None,
+            transaction_parameters: TransactionParameters {
+                chain_id: TestNode::<()>::CHAIN_ID.to_string(),
+                ..Default::default()
+            },
+        }
+        .with_populated_detection_data(OsRng, Default::default());
+        txs.push(client.witness_auth_build(&plan).await?);
+    }
+    assert!(txs.len() > 2, "the test wallet should have several notes");
+
+    // Swapping in the spend proof of another transaction invalidates the first one,
+    // without touching anything its signatures cover.
+    let spend_proof = |tx: &Transaction| {
+        tx.actions()
+            .find_map(|action| match action {
+                Action::Spend(spend) => Some(spend.proof.clone()),
+                _ => None,
+            })
+            .expect("transaction has a spend")
+    };
+    let mut invalid = txs[0].clone();
+    let other_proof = spend_proof(&txs[1]);
+    for action in invalid.transaction_body.actions.iter_mut() {
+        if let Action::Spend(spend) = action {
+            spend.proof = other_proof.clone();
+        }
+    }
+    let (valid, rejected) = (&txs[1..], &txs[0]);
+
+    // Execute the same block on both chains.
+    let data: Vec<_> = std::iter::once(&invalid)
+        .chain(valid)
+        .map(|tx| tx.encode_to_vec())
+        .collect();
+    serial_node
+        .block()
+        .with_data(data.clone())
+        .execute()
+        .await?;
+    parallel_node.block().with_data(data).execute().await?;
+
+    // Both chains end up in the same state...
+    assert_eq!(serial_node.last_app_hash(), parallel_node.last_app_hash());
+
+    // ...in which the valid transactions were applied, and the invalid one was not.
+    for storage in [&serial_storage, &parallel_storage] {
+        let snapshot = storage.latest_snapshot();
+        for tx in valid {
+            for nf in tx.spent_nullifiers() {
+                assert!(snapshot.spend_info(nf).await?.is_some());
+            }
+        }
+        for nf in rejected.spent_nullifiers() {
+            assert!(
+                snapshot.spend_info(nf).await?.is_none(),
+                "a transaction with an invalid proof should be rejected"
+            );
+        }
+    }
+
+    drop(serial_storage);
+    drop(parallel_storage);
+    drop(guard);
+
+    Ok(())
+}
@@ -1,8 +1,14 @@
 use {
+    anyhow::Context as _,
     decaf377_rdsa::VerificationKey,
-    penumbra_app::genesis::AppState,
+    penumbra_app::{
+        genesis::{AppState, Content},
+        params::AppParameters,
+    },
+    penumbra_asset::asset,
     penumbra_keys::keys::{SpendKey, SpendKeyBytes},
-    penumbra_mock_consensus::builder::Builder,
+    penumbra_mock_consensus::{builder::Builder, TestNode},
+    penumbra_num::Amount,
     penumbra_proto::{
         core::keys::v1::{GovernanceKey, IdentityKey},
         penumbra::core::component::stake::v1::Validator as PenumbraValidator,
@@ -11,6 +17,7 @@ use {
     penumbra_stake::DelegationToken,
     rand::Rng,
     rand_core::OsRng,
+    std::collections::BTreeSet,
     tracing::trace,
 };
 
@@ -21,26 +28,83 @@ pub trait BuilderExt: Sized {
     /// Add the provided Penumbra [`AppState`] to the builder.
     ///
     /// This will inject any configured validators into the state before serializing it into bytes.
+    /// This errors if the same consensus key was provided for more than one validator.
     fn with_penumbra_auto_app_state(self, app_state: AppState) -> Result<Self, Self::Error>;
+    /// Add the provided Penumbra [`AppState`] to the builder, giving each configured validator
+    /// its own stake.
+    ///
+    /// The stakes are assigned to the validators in the order of their consensus keys, as they
+    /// are iterated in the keyring. This errors unless there is exactly one stake per validator.
+    fn with_penumbra_weighted_app_state(
+        self,
+        app_state: AppState,
+        stakes: &[Amount],
+    ) -> Result<Self, Self::Error>;
+    /// Add a modified copy of the provided base Penumbra [`AppState`] to the builder.
+    ///
+    /// The base state is left untouched, so that several variants can be derived from it.
+    /// The parameters of the modified state are validated before it is added, so that a
+    /// modification producing an invalid genesis is reported before the chain starts.
+    fn with_penumbra_app_state_modified(
+        self,
+        base: &AppState,
+        modify: impl FnOnce(&mut Content),
+    ) -> Result<Self, Self::Error>;
+    /// Add the provided Penumbra [`AppState`] to the builder, checking that its genesis
+    /// allocations of `denom` add up to at most `supply_cap`.
+    ///
+    /// Only the allocations in the provided state count towards the cap, not the
+    /// delegation tokens allocated to the validators injected by the builder.
+    fn with_penumbra_capped_app_state(
+        self,
+        app_state: AppState,
+        denom: &str,
+        supply_cap: Amount,
+    ) -> Result<Self, Self::Error>;
 }
 
 impl BuilderExt for Builder {
     type Error = anyhow::Error;
-    fn with_penumbra_auto_app_state(mut self, app_state: AppState) -> Result<Self, Self::Error> {
-        let Self { keyring, .. } = &self;
+    fn with_penumbra_auto_app_state(self, app_state: AppState) -> Result<Self, Self::Error> {
+        let stakes = vec![Amount::from(DEFAULT_VALIDATOR_STAKE); self.keyring.len()];
+        self.with_penumbra_weighted_app_state(app_state, &stakes)
+    }
+
+    fn with_penumbra_weighted_app_state(
+        mut self,
+        app_state: AppState,
+        stakes: &[Amount],
+    ) -> Result<Self, Self::Error> {
+        let Self { keyring, keys, .. } = &self;
+        // The keyring is keyed by consensus key, so a key provided twice would silently
+        // collapse two validators into one.
+        let mut consensus_keys = BTreeSet::new();
+        for (_, consensus_vk) in keys {
+            anyhow::ensure!(
+                consensus_keys.insert(consensus_vk),
+                "consensus key {} was provided for more than one validator",
+                hex::encode(consensus_vk.as_bytes())
+            );
+        }
         let mut content = match app_state {
             AppState::Content(c) => c,
             AppState::Checkpoint(_) => anyhow::bail!("checkpointed state is not supported"),
         };
+        anyhow::ensure!(
+            stakes.len() == keyring.len(),
+            "{} stakes were provided for {} validators",
+            stakes.len(),
+            keyring.len()
+        );
 
-        for (consensus_vk, _) in keyring {
+        for ((consensus_vk, _), stake) in keyring.iter().zip(stakes) {
             // Let the seed for the penumbra validator be derived from the verification key,
             // that way tests can operate with no rng.
             let seed = Some(SpendKeyBytes(consensus_vk.to_bytes()));
 
             // Generate a penumbra validator with this consensus key, and a corresponding
             // allocation of delegation tokens.
-            let (validator, allocation) = generate_penumbra_validator(consensus_vk, seed);
+            let (validator, allocation) = generate_penumbra_validator(consensus_vk, seed, *stake);
 
             // Add the validator to the staking component's genesis content.
             trace!(?validator, "adding validator to staking genesis content");
@@ -65,12 +129,98 @@ impl BuilderExt for Builder {
             .map_err(Self::Error::from)
             .map(|s| self.app_state(s))
     }
+
+    fn with_penumbra_app_state_modified(
+        self,
+        base: &AppState,
+        modify: impl FnOnce(&mut Content),
+    ) -> Result<Self, Self::Error> {
+        let mut content = base
+            .content()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("checkpointed state is not supported"))?;
+        modify(&mut content);
+        genesis_parameters(&content)
+            .check_valid()
+            .context("modified app state is not a valid genesis")?;
+        self.with_penumbra_auto_app_state(AppState::Content(content))
+    }
+
+    fn with_penumbra_capped_app_state(
+        self,
+        app_state: AppState,
+        denom: &str,
+        supply_cap: Amount,
+    ) -> Result<Self, Self::Error> {
+        let content = app_state
+            .content()
+            .ok_or_else(|| anyhow::anyhow!("checkpointed state is not supported"))?;
+        let total = genesis_supply(content, denom)?;
+        anyhow::ensure!(
+            total <= supply_cap,
+            "genesis allocations of {denom} total {total}, exceeding the supply cap of {supply_cap}"
+        );
+        self.with_penumbra_auto_app_state(app_state)
+    }
+}
+
+/// Sums the genesis allocations of `denom` in the genesis `content`.
+///
+/// Allocations are matched by the asset they mint, so that different spellings of the
+/// same denomination count towards the same total.
+fn genesis_supply(content: &Content, denom: &str) -> anyhow::Result<Amount> {
+    let parse = |denom: &str| {
+        asset::REGISTRY
+            .parse_denom(denom)
+            .map(|metadata| metadata.id())
+            .ok_or_else(|| anyhow::anyhow!("invalid denomination {denom}"))
+    };
+    let asset_id = parse(denom)?;
+    let mut total = Amount::zero();
+    for allocation in &content.shielded_pool_content.allocations {
+        if parse(&allocation.raw_denom)? == asset_id {
+            total = total
+                .checked_add(&allocation.raw_amount)
+                .ok_or_else(|| anyhow::anyhow!("genesis allocations of {denom} overflow"))?;
+        }
+    }
+    Ok(total)
 }
 
-/// Generates a [`Validator`][PenumbraValidator] given a consensus verification key.
+/// Collects the parameters of each component from the genesis `content`.
+///
+/// An empty chain ID is replaced by the test node's default, as the builder does.
+fn genesis_parameters(content: &Content) -> AppParameters {
+    let chain_id = if content.chain_id.is_empty() {
+        TestNode::<()>::CHAIN_ID.to_string()
+    } else {
+        content.chain_id.clone()
+    };
+    AppParameters {
+        chain_id,
+        auction_params: content.auction_content.auction_params.clone(),
+        community_pool_params: content.community_pool_content.community_pool_params.clone(),
+        distributions_params: content.distributions_content.distributions_params.clone(),
+        dex_params: content.dex_content.dex_params.clone(),
+        fee_params: content.fee_content.fee_params.clone(),
+        funding_params: content.funding_content.funding_params.clone(),
+        governance_params: content.governance_content.governance_params.clone(),
+        ibc_params: content.ibc_content.ibc_params.clone(),
+        sct_params: content.sct_content.sct_params.clone(),
+        shielded_pool_params: content.shielded_pool_content.shielded_pool_params.clone(),
+        stake_params: content.stake_content.stake_params.clone(),
+    }
+}
+
+/// The amount of delegation tokens allocated to each validator, unless weighted stakes are given.
+const DEFAULT_VALIDATOR_STAKE: u128 = 1000;
+
+/// Generates a [`Validator`][PenumbraValidator] given a consensus verification key, and an
+/// allocation of `stake` delegation tokens to it.
 fn generate_penumbra_validator(
     consensus_key: &ed25519_consensus::VerificationKey,
     seed: Option<SpendKeyBytes>,
+    stake: Amount,
 ) -> (PenumbraValidator, Allocation) {
     let seed = seed.unwrap_or(SpendKeyBytes(OsRng.gen()));
     let spend_key = SpendKey::from(seed.clone());
@@ -104,7 +254,7 @@ fn generate_penumbra_validator(
     let delegation_denom = DelegationToken::from(ik).denom();
 
     let allocation = Allocation {
-        raw_amount: 1000u128.into(),
+        raw_amount: stake,
         raw_denom: delegation_denom.to_string(),
         address,
     };
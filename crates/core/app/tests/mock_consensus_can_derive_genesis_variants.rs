@@ -0,0 +1,65 @@
+use {
+    self::common::BuilderExt,
+    cnidarium::TempStorage,
+    common::TempStorageExt as _,
+    penumbra_app::{
+        genesis::{self, AppState},
+        server::consensus::Consensus,
+    },
+    penumbra_mock_consensus::TestNode,
+    penumbra_sct::component::StateReadExt as _,
+};
+
+mod common;
+
+/// Exercises that several test chains can be started from variants of one base app state,
+/// and that a variant with invalid parameters is rejected before its chain starts.
+#[tokio::test]
+async fn mock_consensus_can_derive_genesis_variants() -> anyhow::Result<()> {
+    // Install a test logger, and prepare the shared base app state.
+    let guard = common::set_tracing_subscriber();
+    let base = AppState::Content(
+        genesis::Content::default().with_chain_id(TestNode::<()>::CHAIN_ID.to_string()),
+    );
+
+    // Start one chain for each variant of the base, and check its epoch duration.
+    for epoch_duration in [7, 11] {
+        let storage = TempStorage::new_with_penumbra_prefixes().await?;
+        let consensus = Consensus::new(storage.as_ref().clone());
+        let test_node = TestNode::builder()
+            .single_validator()
+            .with_penumbra_app_state_modified(&base, |content| {
+                content.sct_content.sct_params.epoch_duration = epoch_duration;
+            })?
+            .init_chain(consensus)
+            .await?;
+
+        let params = storage.latest_snapshot().get_sct_params().await?;
+        assert_eq!(params.epoch_duration, epoch_duration);
+
+        drop(test_node);
+        drop(storage);
+    }
+
+    // The base itself is not modified by deriving variants from it.
+    let base_content = base.content().expect("base has content");
+    assert_eq!(
+        base_content.sct_content.sct_params.epoch_duration,
+        genesis::Content::default()
+            .sct_content
+            .sct_params
+            .epoch_duration,
+    );
+
+    // A variant with an invalid epoch duration is rejected by the builder.
+    let invalid = TestNode::builder()
+        .single_validator()
+        .with_penumbra_app_state_modified(&base, |content| {
+            content.sct_content.sct_params.epoch_duration = 0;
+        });
+    assert!(invalid.is_err(), "an invalid genesis should be rejected");
+
+    drop(guard);
+
+    Ok(())
+}
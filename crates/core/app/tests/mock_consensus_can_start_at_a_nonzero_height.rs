@@ -0,0 +1,86 @@
+use {
+    self::common::{BuilderExt, ValidatorDataReadExt as _},
+    cnidarium::TempStorage,
+    common::TempStorageExt as _,
+    penumbra_app::{
+        genesis::{self, AppState},
+        server::consensus::Consensus,
+    },
+    penumbra_mock_consensus::TestNode,
+    penumbra_sct::component::{clock::EpochRead as _, StateReadExt as _},
+    tap::TapFallible,
+};
+
+mod common;
+
+/// Exercises that a test node configured with an initial height jumps straight to that height
+/// after genesis, and that the components initialized at genesis remain usable.
+#[tokio::test]
+async fn mock_consensus_can_start_at_a_nonzero_height() -> anyhow::Result<()> {
+    // Install a test logger, acquire some temporary storage, and start the test node.
+    let guard = common::set_tracing_subscriber();
+    let storage = TempStorage::new_with_penumbra_prefixes().await?;
+    let mut test_node = {
+        let app_state = AppState::Content(
+            genesis::Content::default().with_chain_id(TestNode::<()>::CHAIN_ID.to_string()),
+        );
+        let consensus = Consensus::new(storage.as_ref().clone());
+        TestNode::builder()
+            .single_validator()
+            .with_penumbra_auto_app_state(app_state)?
+            .with_initial_height(1000)
+            .init_chain(consensus)
+            .await
+            .tap_ok(|e| tracing::info!(hash = %e.last_app_hash_hex(), "finished init chain"))?
+    };
+
+    // Genesis is still applied at height 0.
+    let snapshot = storage.latest_snapshot();
+    assert_eq!(
+        snapshot.get_block_height().await?,
+        0,
+        "genesis is at height 0"
+    );
+    assert_eq!(
+        test_node.height().value(),
+        999,
+        "no blocks have been executed"
+    );
+
+    // The first block lands at the initial height, and the chain keeps growing from there.
+    test_node.block().execute().await?;
+    let snapshot = storage.latest_snapshot();
+    assert_eq!(
+        snapshot.get_block_height().await?,
+        1000,
+        "first block is at height 1000"
+    );
+    test_node.fast_forward(2).await?;
+    let snapshot = storage.latest_snapshot();
+    assert_eq!(
+        snapshot.get_block_height().await?,
+        1002,
+        "height should grow"
+    );
+
+    // The state initialized at genesis is still there.
+    let epoch = snapshot.get_current_epoch().await?;
+    assert_eq!(
+        (epoch.index, epoch.start_height),
+        (0, 0),
+        "still in the genesis epoch"
+    );
+    snapshot.get_sct_params().await?;
+    assert_eq!(
+        snapshot.validator_definitions().await?.len(),
+        1,
+        "the genesis validator is defined"
+    );
+
+    // Free our temporary storage.
+    drop(test_node);
+    drop(storage);
+    drop(guard);
+
+    Ok(())
+}
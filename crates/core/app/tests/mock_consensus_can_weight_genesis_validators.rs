@@ -0,0 +1,108 @@
+use {
+    self::common::{BuilderExt, ValidatorDataReadExt},
+    cnidarium::TempStorage,
+    common::TempStorageExt as _,
+    penumbra_app::{
+        genesis::{self, AppState},
+        server::consensus::Consensus,
+    },
+    penumbra_mock_consensus::TestNode,
+    penumbra_num::Amount,
+    penumbra_stake::{DelegationToken, IdentityKey},
+};
+
+mod common;
+
+/// Exercises that the builder can generate a large validator set with weighted stakes, and
+/// that it rejects a list of stakes of the wrong length.
+#[tokio::test]
+async fn mock_consensus_can_weight_genesis_validators() -> anyhow::Result<()> {
+    // Install a test logger.
+    let guard = common::set_tracing_subscriber();
+    let app_state = AppState::Content(
+        genesis::Content::default().with_chain_id(TestNode::<()>::CHAIN_ID.to_string()),
+    );
+
+    // A few large validators and a long tail of small ones.
+    let stakes: Vec<Amount> = (1..=20u64)
+        .map(|rank| Amount::from(1_000_000 / (rank * rank)))
+        .collect();
+    let builder = TestNode::builder()
+        .with_seed([1; 32])
+        .validators(20)
+        .with_penumbra_weighted_app_state(app_state.clone(), &stakes)?;
+
+    // Each validator is allocated its own stake, in the order of the keyring.
+    let content = serde_json::from_slice::<AppState>(
+        builder
+            .app_state
+            .as_ref()
+            .expect("the builder has an app state"),
+    )?
+    .content()
+    .cloned()
+    .expect("the app state has content");
+    let validators = &content.stake_content.validators;
+    assert_eq!(validators.len(), 20);
+    for ((consensus_vk, _), (validator, stake)) in
+        builder.keyring.iter().zip(validators.iter().zip(&stakes))
+    {
+        assert_eq!(validator.consensus_key, consensus_vk.as_bytes().to_vec());
+        let identity_key: IdentityKey = validator
+            .identity_key
+            .clone()
+            .expect("validator has an identity key")
+            .try_into()?;
+        let delegation_denom = DelegationToken::from(identity_key).denom().to_string();
+        let allocated: Vec<Amount> = content
+            .shielded_pool_content
+            .allocations
+            .iter()
+            .filter(|allocation| allocation.raw_denom == delegation_denom)
+            .map(|allocation| allocation.raw_amount)
+            .collect();
+        assert_eq!(allocated, vec![*stake]);
+    }
+    let total = content
+        .shielded_pool_content
+        .allocations
+        .iter()
+        .fold(Amount::zero(), |total, allocation| {
+            total + allocation.raw_amount
+        });
+    assert_eq!(
+        total,
+        stakes
+            .iter()
+            .fold(Amount::zero(), |total, stake| total + *stake)
+    );
+
+    // The chain starts with all twenty validators.
+    let storage = TempStorage::new_with_penumbra_prefixes().await?;
+    let test_node = builder
+        .init_chain(Consensus::new(storage.as_ref().clone()))
+        .await?;
+    assert_eq!(
+        storage
+            .latest_snapshot()
+            .validator_definitions()
+            .await?
+            .len(),
+        20
+    );
+
+    // A list of stakes that doesn't match the number of validators is rejected.
+    let mismatched = TestNode::builder()
+        .validators(20)
+        .with_penumbra_weighted_app_state(app_state, &stakes[..19]);
+    assert!(
+        mismatched.is_err(),
+        "a stake list shorter than the validator set should be rejected"
+    );
+
+    drop(test_node);
+    drop(storage);
+    drop(guard);
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use {
+    self::common::BuilderExt,
+    cnidarium::TempStorage,
+    common::TempStorageExt as _,
+    penumbra_app::{
+        genesis::{self, AppState},
+        server::consensus::Consensus,
+    },
+    penumbra_keys::test_keys,
+    penumbra_mock_consensus::TestNode,
+    penumbra_num::Amount,
+    penumbra_shielded_pool::genesis::Allocation,
+    std::ops::Deref,
+};
+
+mod common;
+
+/// Returns an app state allocating `amounts` of the staking token to the test wallet.
+fn app_state_allocating(amounts: &[u64]) -> AppState {
+    let mut content =
+        genesis::Content::default().with_chain_id(TestNode::<()>::CHAIN_ID.to_string());
+    content.shielded_pool_content.allocations = amounts
+        .iter()
+        .map(|amount| Allocation {
+            raw_amount: (*amount).into(),
+            raw_denom: "upenumbra".to_string(),
+            address: test_keys::ADDRESS_0.deref().clone(),
+        })
+        .collect();
+    AppState::Content(content)
+}
+
+/// Exercises that the builder rejects genesis allocations exceeding a supply cap, and that
+/// allocations adding up to exactly the cap are allowed.
+#[tokio::test]
+async fn mock_consensus_enforces_genesis_supply_cap() -> anyhow::Result<()> {
+    // Install a test logger.
+    let guard = common::set_tracing_subscriber();
+    let supply_cap = Amount::from(1_000u64);
+
+    // Allocations under the cap, and summing exactly to the cap, are accepted.
+    for amounts in [&[300, 600][..], &[400, 600][..]] {
+        let storage = TempStorage::new_with_penumbra_prefixes().await?;
+        let test_node = TestNode::builder()
+            .single_validator()
+            .with_penumbra_capped_app_state(app_state_allocating(amounts), "upenumbra", supply_cap)?
+            .init_chain(Consensus::new(storage.as_ref().clone()))
+            .await?;
+        drop(test_node);
+        drop(storage);
+    }
+
+    // Allocations over the cap are rejected before the chain starts.
+    let over_cap = TestNode::builder()
+        .single_validator()
+        .with_penumbra_capped_app_state(app_state_allocating(&[400, 601]), "upenumbra", supply_cap);
+    assert!(
+        over_cap.is_err(),
+        "allocations exceeding the supply cap should be rejected"
+    );
+
+    drop(guard);
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use {ed25519_consensus::VerificationKey, penumbra_mock_consensus::TestNode};
+
+/// Returns the consensus verification keys generated for two validators from `seed`.
+fn keys_from_seed(seed: [u8; 32]) -> Vec<VerificationKey> {
+    TestNode::builder()
+        .with_seed(seed)
+        .two_validators()
+        .keyring
+        .into_keys()
+        .collect()
+}
+
+/// Exercises that the same seed always produces the same validator keys.
+#[test]
+fn mock_consensus_keyring_is_deterministic_with_a_seed() {
+    let keys = keys_from_seed([1; 32]);
+    assert_eq!(keys.len(), 2, "two distinct keys should be generated");
+    assert_eq!(keys, keys_from_seed([1; 32]));
+}
+
+/// Exercises that different seeds produce different validator keys.
+#[test]
+fn mock_consensus_keyring_differs_across_seeds() {
+    assert_ne!(keys_from_seed([1; 32]), keys_from_seed([2; 32]));
+}
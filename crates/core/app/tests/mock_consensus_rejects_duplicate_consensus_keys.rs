@@ -0,0 +1,43 @@
+use {
+    self::common::BuilderExt,
+    penumbra_app::genesis::{self, AppState},
+    penumbra_mock_consensus::TestNode,
+    rand_core::OsRng,
+};
+
+mod common;
+
+/// Exercises that the builder rejects validators sharing a consensus key, rather than
+/// starting a chain with fewer validators than were configured.
+#[tokio::test]
+async fn mock_consensus_rejects_duplicate_consensus_keys() -> anyhow::Result<()> {
+    // Install a test logger.
+    let guard = common::set_tracing_subscriber();
+    let app_state = AppState::Content(
+        genesis::Content::default().with_chain_id(TestNode::<()>::CHAIN_ID.to_string()),
+    );
+
+    let sk = ed25519_consensus::SigningKey::new(OsRng);
+    let key = (sk.clone(), sk.verification_key());
+
+    // A single validator trivially has a unique consensus key.
+    let single = TestNode::builder()
+        .with_keys(vec![key.clone()])
+        .single_validator()
+        .with_penumbra_auto_app_state(app_state.clone())?;
+    assert_eq!(single.keyring.len(), 1);
+
+    // Two validators provided with the same consensus key are rejected.
+    let duplicated = TestNode::builder()
+        .with_keys(vec![key.clone(), key])
+        .two_validators()
+        .with_penumbra_auto_app_state(app_state);
+    assert!(
+        duplicated.is_err(),
+        "validators sharing a consensus key should be rejected"
+    );
+
+    drop(guard);
+
+    Ok(())
+}
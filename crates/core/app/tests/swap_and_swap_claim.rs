@@ -1,15 +1,18 @@
 mod common;
 
 use self::common::TempStorageExt;
-use cnidarium::{ArcStateDeltaExt, StateDelta, TempStorage};
+use cnidarium::{ArcStateDeltaExt, Snapshot, StateDelta, TempStorage};
 use cnidarium_component::{ActionHandler, Component};
 use decaf377::Fq;
-use penumbra_asset::asset;
+use penumbra_asset::{asset, Value};
 use penumbra_compact_block::component::CompactBlockManager as _;
 use penumbra_dex::{
-    component::{Dex, StateReadExt as _},
+    component::{
+        Dex, StateReadExt as _, StateWriteExt as _, SwapDataRead as _,
+        SWAP_CLAIM_PROOF_RETENTION_BLOCKS,
+    },
     swap::{SwapPlaintext, SwapPlan},
-    swap_claim::SwapClaimPlan,
+    swap_claim::{DuplicateProof, SwapClaim, SwapClaimPlan},
     TradingPair,
 };
 use penumbra_fee::Fee;
@@ -17,10 +20,14 @@ use penumbra_keys::{test_keys, Address};
 use penumbra_mock_client::MockClient;
 use penumbra_num::Amount;
 use penumbra_sct::{
-    component::{clock::EpochManager, source::SourceContext as _, StateReadExt as _},
+    component::{
+        clock::EpochManager, source::SourceContext as _, tree::NullifierAlreadySpent,
+        StateReadExt as _,
+    },
     epoch::Epoch,
 };
 use penumbra_shielded_pool::component::ShieldedPool;
+use penumbra_tct as tct;
 use penumbra_transaction::Transaction;
 use rand_core::SeedableRng;
 use std::{ops::Deref, sync::Arc};
@@ -138,6 +145,295 @@ async fn swap_and_swap_claim() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+/// A swap can't be claimed until the grace period after its batch has elapsed, and can
+/// be claimed in the block that ends it.
+async fn swap_claim_waits_for_grace_period() -> anyhow::Result<()> {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(1312);
+    let (_storage, mut state) = start_chain().await?;
+
+    let height = 1;
+    let grace_blocks = 3;
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    let dex_params = state_tx.get_dex_params().await?;
+    state_tx.put_dex_params(penumbra_dex::DexParameters {
+        swap_claim_grace_blocks: grace_blocks,
+        ..dex_params
+    });
+    state_tx.apply();
+
+    let swaps = execute_swaps(&mut rng, &mut state, height, vec![Fee::default()]).await?;
+    let claim = &swaps[0].claim;
+
+    // One block before the end of the grace period, the claim is rejected...
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_block_height(height + grace_blocks - 1);
+    state_tx.put_mock_source(2u8);
+    assert!(
+        claim.check_and_execute(&mut state_tx).await.is_err(),
+        "a claim before the end of the grace period should be rejected"
+    );
+    drop(state_tx);
+
+    // ...and in the block that ends it, the claim is accepted.
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_block_height(height + grace_blocks);
+    state_tx.put_mock_source(3u8);
+    claim.check_and_execute(&mut state_tx).await?;
+    state_tx.apply();
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Claiming the same swap twice is rejected with an error identifying the nullifier
+/// and the height at which it was first spent.
+async fn swap_claim_replay_reports_first_spend() -> anyhow::Result<()> {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(1312);
+    let (_storage, mut state) = start_chain().await?;
+
+    let height = 1;
+    let swaps = execute_swaps(&mut rng, &mut state, height, vec![Fee::default()]).await?;
+    let claim = &swaps[0].claim;
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(2u8);
+    claim.check_and_execute(&mut state_tx).await?;
+    state_tx.apply();
+
+    // Replay the same SwapClaim action in a later transaction.
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(3u8);
+    let err = claim
+        .check_and_execute(&mut state_tx)
+        .await
+        .expect_err("replaying a swap claim should fail");
+    let replay = err
+        .downcast_ref::<NullifierAlreadySpent>()
+        .expect("replay error should be a NullifierAlreadySpent");
+    assert_eq!(replay.nullifier, claim.body.nullifier);
+    assert_eq!(replay.spend_height, height);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Resubmitting an accepted swap claim is reported as a duplicate of it, until the
+/// record of its proof expires.
+async fn swap_claim_resubmitted_proof_is_a_duplicate() -> anyhow::Result<()> {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(1312);
+    let (_storage, mut state) = start_chain().await?;
+
+    let height = 1;
+    let mut swaps = execute_swaps(&mut rng, &mut state, height, vec![Fee::default()]).await?;
+    let ExecutedSwap {
+        claim_plan,
+        auth_path,
+        claim,
+    } = swaps.remove(0);
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(2u8);
+    claim.check_and_execute(&mut state_tx).await?;
+    state_tx.apply();
+
+    // A second claim of the same swap, with a distinct proof, is a double spend...
+
+    let other_claim = SwapClaimPlan {
+        proof_blinding_r: Fq::rand(&mut rng),
+        proof_blinding_s: Fq::rand(&mut rng),
+        ..claim_plan
+    }
+    .swap_claim(&test_keys::FULL_VIEWING_KEY, &auth_path);
+    assert_ne!(other_claim.proof.hash(), claim.proof.hash());
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(3u8);
+    let err = other_claim
+        .check_and_execute(&mut state_tx)
+        .await
+        .expect_err("claiming a swap twice should fail");
+    assert!(err.downcast_ref::<NullifierAlreadySpent>().is_some());
+    assert!(
+        err.downcast_ref::<DuplicateProof>().is_none(),
+        "a distinct proof should not be reported as a duplicate"
+    );
+    drop(state_tx);
+
+    // ...while resubmitting the exact same claim is flagged as a duplicate...
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(4u8);
+    let err = claim
+        .check_and_execute(&mut state_tx)
+        .await
+        .expect_err("resubmitting a swap claim should fail");
+    let duplicate = err
+        .downcast_ref::<DuplicateProof>()
+        .expect("resubmission error should be a DuplicateProof");
+    assert_eq!(duplicate.proof_hash, claim.proof.hash());
+    assert_eq!(duplicate.height, height);
+    assert!(err.downcast_ref::<NullifierAlreadySpent>().is_some());
+    drop(state_tx);
+
+    // ...until the record of its proof expires, after which it is only a double spend.
+
+    let expiry_height = height + SWAP_CLAIM_PROOF_RETENTION_BLOCKS;
+    begin_block(&mut state, expiry_height);
+    end_block(&mut state, expiry_height).await;
+    assert_eq!(state.swap_claim_proof_height(&claim.proof).await?, None);
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(5u8);
+    let err = claim
+        .check_and_execute(&mut state_tx)
+        .await
+        .expect_err("resubmitting a swap claim should fail");
+    assert!(err.downcast_ref::<NullifierAlreadySpent>().is_some());
+    assert!(err.downcast_ref::<DuplicateProof>().is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Swap claims may only pay fees in the assets the DEX parameters allow, which are
+/// just the staking token if the list is empty.
+async fn swap_claim_fee_must_be_in_allowed_asset() -> anyhow::Result<()> {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(1312);
+    let (_storage, mut state) = start_chain().await?;
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+
+    // Let the default, empty, list of fee assets take effect...
+    begin_block(&mut state, 1);
+    end_block(&mut state, 1).await;
+
+    // ...before the swaps, whose claims pay fees in gm and in the staking token.
+    let gm_fee = Fee(Value {
+        amount: 0u64.into(),
+        asset_id: gm.id(),
+    });
+    let swaps = execute_swaps(&mut rng, &mut state, 2, vec![gm_fee, Fee::default()]).await?;
+    let (gm_claim, staking_claim) = (&swaps[0].claim, &swaps[1].claim);
+
+    // The claim paying its fee in the staking token is accepted, while the one paying
+    // in gm is rejected.
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(3u8);
+    staking_claim.check_and_execute(&mut state_tx).await?;
+    state_tx.apply();
+
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(4u8);
+    let err = gm_claim
+        .check_and_execute(&mut state_tx)
+        .await
+        .expect_err("a claim paying its fee in a disallowed asset should be rejected");
+    assert!(err.to_string().contains("cannot pay fees"));
+
+    Ok(())
+}
+
+#[tokio::test]
+/// A swap made while its claim fee asset was allowed can still be claimed after the
+/// asset is removed from the list, while swaps made after the change can't.
+async fn swap_claim_fee_asset_is_grandfathered_across_parameter_change() -> anyhow::Result<()> {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(1312);
+    let (_storage, mut state) = start_chain().await?;
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gm_fee = Fee(Value {
+        amount: 0u64.into(),
+        asset_id: gm.id(),
+    });
+
+    // Allow claim fees to be paid in gm, and make a swap whose claim pays in gm.
+    begin_block(&mut state, 1);
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    let dex_params = state_tx.get_dex_params().await?;
+    state_tx.put_dex_params(penumbra_dex::DexParameters {
+        swap_claim_fee_assets: vec![gm.id()],
+        ..dex_params.clone()
+    });
+    state_tx.apply();
+    end_block(&mut state, 1).await;
+    let pending = execute_swaps(&mut rng, &mut state, 2, vec![gm_fee]).await?;
+
+    // Before it is claimed, the list is emptied, allowing the staking token only...
+    begin_block(&mut state, 3);
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_dex_params(penumbra_dex::DexParameters {
+        swap_claim_fee_assets: vec![],
+        ..dex_params
+    });
+    state_tx.apply();
+    end_block(&mut state, 3).await;
+
+    // ...and another swap whose claim pays in gm is made.
+    let later = execute_swaps(&mut rng, &mut state, 4, vec![gm_fee]).await?;
+
+    // The pending swap can still be claimed...
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(5u8);
+    pending[0].claim.check_and_execute(&mut state_tx).await?;
+    state_tx.apply();
+
+    // ...but the swap made after the change can't.
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(6u8);
+    let err = later[0]
+        .claim
+        .check_and_execute(&mut state_tx)
+        .await
+        .expect_err("a claim paying its fee in a disallowed asset should be rejected");
+    assert!(err.to_string().contains("cannot pay fees"));
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Changing DEX parameters other than the swap claim fee assets doesn't grandfather
+/// swaps whose claims pay fees in a disallowed asset.
+async fn swap_claim_fee_asset_is_not_grandfathered_by_unrelated_parameter_change(
+) -> anyhow::Result<()> {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(1312);
+    let (_storage, mut state) = start_chain().await?;
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gm_fee = Fee(Value {
+        amount: 0u64.into(),
+        asset_id: gm.id(),
+    });
+
+    // Make a swap whose claim pays in gm, which the default list of fee assets disallows.
+    let pending = execute_swaps(&mut rng, &mut state, 1, vec![gm_fee]).await?;
+
+    // Before it is claimed, a parameter unrelated to the fee assets changes.
+    begin_block(&mut state, 2);
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    let dex_params = state_tx.get_dex_params().await?;
+    state_tx.put_dex_params(penumbra_dex::DexParameters {
+        max_hops: dex_params.max_hops + 1,
+        ..dex_params
+    });
+    state_tx.apply();
+    end_block(&mut state, 2).await;
+    assert_eq!(state.swap_claim_fee_assets_since().await?, None);
+
+    // The swap is still not allowed to pay its claim fee in gm.
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_mock_source(3u8);
+    let err = pending[0]
+        .claim
+        .check_and_execute(&mut state_tx)
+        .await
+        .expect_err("a claim paying its fee in a disallowed asset should be rejected");
+    assert!(err.to_string().contains("cannot pay fees"));
+
+    Ok(())
+}
+
 /*
 #[tokio::test]
 #[should_panic(expected = "was already spent")]
@@ -334,3 +630,122 @@ async fn swap_with_nonzero_fee() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// A swap executed in its batch, with a claim of it that passed its stateless and
+/// historical checks.
+struct ExecutedSwap {
+    claim_plan: SwapClaimPlan,
+    auth_path: tct::Proof,
+    claim: SwapClaim,
+}
+
+/// Starts a chain from the default genesis.
+async fn start_chain() -> anyhow::Result<(TempStorage, Arc<StateDelta<Snapshot>>)> {
+    let storage = TempStorage::new_with_penumbra_prefixes()
+        .await?
+        .apply_default_genesis()
+        .await?;
+    let state = Arc::new(StateDelta::new(storage.latest_snapshot()));
+    Ok((storage, state))
+}
+
+/// Simulates the BeginBlock of the block at `height`, in the first epoch.
+fn begin_block(state: &mut Arc<StateDelta<Snapshot>>, height: u64) {
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.put_epoch_by_height(
+        height,
+        Epoch {
+            index: 0,
+            start_height: 0,
+        },
+    );
+    state_tx.put_block_height(height);
+    state_tx.apply();
+}
+
+/// Simulates the EndBlock of the block at `height`, executing its batch swaps and
+/// writing out the SCT.
+async fn end_block(state: &mut Arc<StateDelta<Snapshot>>, height: u64) {
+    let end_block = abci::request::EndBlock {
+        height: height.try_into().unwrap(),
+    };
+    Dex::end_block(state, &end_block).await;
+    ShieldedPool::end_block(state, &end_block).await;
+    let mut state_tx = state.try_begin_transaction().unwrap();
+    state_tx.finish_block().await.unwrap();
+    state_tx.apply();
+}
+
+/// Executes, in the block at `height`, a swap of gm for gn whose claim pays each of
+/// the given `claim_fees`, and builds a claim of each swap against its batch.
+async fn execute_swaps(
+    rng: &mut rand_chacha::ChaChaRng,
+    state: &mut Arc<StateDelta<Snapshot>>,
+    height: u64,
+    claim_fees: Vec<Fee>,
+) -> anyhow::Result<Vec<ExecutedSwap>> {
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let trading_pair = TradingPair::new(gm.id(), gn.id());
+
+    begin_block(state, height);
+    let mut swaps = Vec::new();
+    for (i, fee) in claim_fees.into_iter().enumerate() {
+        let plaintext = SwapPlaintext::new(
+            rng,
+            trading_pair,
+            100_000u64.into(),
+            0u64.into(),
+            fee,
+            test_keys::ADDRESS_0.deref().clone(),
+        );
+        let swap = SwapPlan::new(rng, plaintext.clone()).swap(&test_keys::FULL_VIEWING_KEY);
+        swap.check_stateless(()).await?;
+        swap.check_historical(state.clone()).await?;
+        let mut state_tx = state.try_begin_transaction().unwrap();
+        state_tx.put_mock_source(i as u8 + 1);
+        swap.check_and_execute(&mut state_tx).await?;
+        state_tx.apply();
+        swaps.push((plaintext, swap));
+    }
+    end_block(state, height).await;
+
+    // Claims need an auth path for their swap, so sync a client's view of the SCT.
+    let epoch_duration = state.get_epoch_duration_parameter().await?;
+    let mut client = MockClient::new(test_keys::SPEND_KEY.clone());
+    client.sync_to(height, state.deref()).await?;
+    let output_data = state.output_data(height, trading_pair).await?.unwrap();
+    // The SwapClaim ActionHandler uses the transaction's anchor to check proofs.
+    let context = Transaction {
+        anchor: client.latest_height_and_sct_root().1,
+        ..Default::default()
+    }
+    .context();
+
+    let mut executed = Vec::new();
+    for (plaintext, swap) in swaps {
+        let commitment = swap.body.payload.commitment;
+        let auth_path = client.witness_commitment(commitment).unwrap();
+        assert_eq!(
+            client.swap_by_commitment(&commitment),
+            Some(plaintext.clone())
+        );
+        let claim_plan = SwapClaimPlan {
+            swap_plaintext: plaintext,
+            position: auth_path.position(),
+            output_data,
+            epoch_duration,
+            proof_blinding_r: Fq::rand(rng),
+            proof_blinding_s: Fq::rand(rng),
+        };
+        let claim = claim_plan.swap_claim(&test_keys::FULL_VIEWING_KEY, &auth_path);
+        claim.check_stateless(context.clone()).await?;
+        claim.check_historical(state.clone()).await?;
+        executed.push(ExecutedSwap {
+            claim_plan,
+            auth_path,
+            claim,
+        });
+    }
+    Ok(executed)
+}
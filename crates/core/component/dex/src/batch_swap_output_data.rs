@@ -11,6 +11,7 @@ use penumbra_proto::{penumbra::core::component::dex::v1 as pb, DomainType};
 use penumbra_tct::Position;
 use serde::{Deserialize, Serialize};
 
+use penumbra_fee::Fee;
 use penumbra_num::fixpoint::{bit_constrain, U128x128, U128x128Var};
 use penumbra_num::{Amount, AmountVar};
 
@@ -81,6 +82,32 @@ impl BatchSwapOutputData {
                 .expect("rounded amount is integral"),
         )
     }
+
+    /// Given a user's inputs `(delta_1_i, delta_2_i)`, compute the all-in rate of their
+    /// swap: the output they claim, net of the `claim_fee`, per unit of input.
+    ///
+    /// The fee is only subtracted when it is paid in the asset the swap bought. A fee
+    /// that exceeds the output gives a rate of zero. The rate is only defined for a swap
+    /// with an input of exactly one asset, and is `None` otherwise.
+    pub fn effective_rate(
+        &self,
+        (delta_1_i, delta_2_i): (Amount, Amount),
+        claim_fee: &Fee,
+    ) -> Option<U128x128> {
+        let (lambda_1_i, lambda_2_i) = self.pro_rata_outputs((delta_1_i, delta_2_i));
+        let (input, output, output_id) =
+            match (delta_1_i == Amount::zero(), delta_2_i == Amount::zero()) {
+                (false, true) => (delta_1_i, lambda_2_i, self.trading_pair.asset_2()),
+                (true, false) => (delta_2_i, lambda_1_i, self.trading_pair.asset_1()),
+                _ => return None,
+            };
+        let net_output = if claim_fee.asset_id() == output_id {
+            output.saturating_sub(&claim_fee.amount())
+        } else {
+            output
+        };
+        (U128x128::from(net_output) / U128x128::from(input)).ok()
+    }
 }
 
 impl ToConstraintField<Fq> for BatchSwapOutputData {
@@ -340,6 +367,196 @@ impl TryFrom<pb::BatchSwapOutputDataResponse> for BatchSwapOutputData {
     }
 }
 
+/// The current version of the [`CanonicalBatchSwapOutputData`] JSON schema.
+pub const BATCH_SWAP_OUTPUT_DATA_SCHEMA_VERSION: u32 = 1;
+
+/// A stable JSON representation of [`BatchSwapOutputData`], for non-Rust consumers.
+///
+/// The `Serialize` impl of [`BatchSwapOutputData`] uses the proto3 JSON mapping,
+/// which follows the protobuf definitions. This schema is fixed instead: a change to
+/// it comes with a new `schema_version`. Amounts, the height, and the position
+/// prefix are encoded as decimal strings, since they may exceed the range of
+/// integers that JSON parsers represent exactly, and asset IDs are encoded in bech32m.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CanonicalBatchSwapOutputData {
+    pub schema_version: u32,
+    pub delta_1: String,
+    pub delta_2: String,
+    pub lambda_1: String,
+    pub lambda_2: String,
+    pub unfilled_1: String,
+    pub unfilled_2: String,
+    pub height: String,
+    pub asset_1: String,
+    pub asset_2: String,
+    pub sct_position_prefix: String,
+}
+
+impl BatchSwapOutputData {
+    /// Serializes the batch output to its [`CanonicalBatchSwapOutputData`] JSON.
+    pub fn to_canonical_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&CanonicalBatchSwapOutputData::from(
+            *self,
+        ))?)
+    }
+
+    /// Parses a batch output from its [`CanonicalBatchSwapOutputData`] JSON.
+    pub fn from_canonical_json(json: &str) -> Result<Self> {
+        serde_json::from_str::<CanonicalBatchSwapOutputData>(json)?.try_into()
+    }
+}
+
+impl From<BatchSwapOutputData> for CanonicalBatchSwapOutputData {
+    fn from(s: BatchSwapOutputData) -> Self {
+        Self {
+            schema_version: BATCH_SWAP_OUTPUT_DATA_SCHEMA_VERSION,
+            delta_1: s.delta_1.value().to_string(),
+            delta_2: s.delta_2.value().to_string(),
+            lambda_1: s.lambda_1.value().to_string(),
+            lambda_2: s.lambda_2.value().to_string(),
+            unfilled_1: s.unfilled_1.value().to_string(),
+            unfilled_2: s.unfilled_2.value().to_string(),
+            height: s.height.to_string(),
+            asset_1: s.trading_pair.asset_1().to_string(),
+            asset_2: s.trading_pair.asset_2().to_string(),
+            sct_position_prefix: u64::from(s.sct_position_prefix).to_string(),
+        }
+    }
+}
+
+impl TryFrom<CanonicalBatchSwapOutputData> for BatchSwapOutputData {
+    type Error = anyhow::Error;
+    fn try_from(s: CanonicalBatchSwapOutputData) -> Result<Self, Self::Error> {
+        anyhow::ensure!(
+            s.schema_version == BATCH_SWAP_OUTPUT_DATA_SCHEMA_VERSION,
+            "unsupported schema version {}, expected {}",
+            s.schema_version,
+            BATCH_SWAP_OUTPUT_DATA_SCHEMA_VERSION
+        );
+        let amount = |field: &str, value: &str| -> Result<Amount> {
+            value
+                .parse::<u128>()
+                .map(Amount::from)
+                .map_err(|e| anyhow!("invalid {field} {value:?}: {e}"))
+        };
+        let trading_pair = TradingPair::new(s.asset_1.parse()?, s.asset_2.parse()?);
+        anyhow::ensure!(
+            trading_pair.asset_1().to_string() == s.asset_1,
+            "asset_1 and asset_2 are not in canonical order"
+        );
+        let sct_position_prefix = Position::from(
+            s.sct_position_prefix
+                .parse::<u64>()
+                .map_err(|e| anyhow!("invalid sct_position_prefix: {e}"))?,
+        );
+        anyhow::ensure!(
+            sct_position_prefix.commitment() == 0,
+            "sct_position_prefix.commitment() != 0"
+        );
+        Ok(Self {
+            delta_1: amount("delta_1", &s.delta_1)?,
+            delta_2: amount("delta_2", &s.delta_2)?,
+            lambda_1: amount("lambda_1", &s.lambda_1)?,
+            lambda_2: amount("lambda_2", &s.lambda_2)?,
+            unfilled_1: amount("unfilled_1", &s.unfilled_1)?,
+            unfilled_2: amount("unfilled_2", &s.unfilled_2)?,
+            height: s
+                .height
+                .parse()
+                .map_err(|e| anyhow!("invalid height: {e}"))?,
+            trading_pair,
+            sct_position_prefix,
+        })
+    }
+}
+
+impl BatchSwapOutputData {
+    /// Encodes the batch output compactly, for storage under a key that already
+    /// identifies its height and trading pair.
+    ///
+    /// The height and trading pair are left out, and the amounts and position prefix
+    /// are encoded as LEB128 varints, so that the small amounts of most batches take a
+    /// few bytes each. [`Self::from_compact_bytes`] recovers the batch output exactly.
+    pub fn to_compact_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for amount in [
+            self.delta_1,
+            self.delta_2,
+            self.lambda_1,
+            self.lambda_2,
+            self.unfilled_1,
+            self.unfilled_2,
+        ] {
+            put_varint(&mut bytes, amount.value());
+        }
+        put_varint(&mut bytes, u64::from(self.sct_position_prefix).into());
+        bytes
+    }
+
+    /// Decodes a batch output encoded by [`Self::to_compact_bytes`], given the height and
+    /// trading pair it was stored under.
+    pub fn from_compact_bytes(
+        height: u64,
+        trading_pair: TradingPair,
+        mut bytes: &[u8],
+    ) -> Result<Self> {
+        let mut amount = || get_varint(&mut bytes).map(Amount::from);
+        let (delta_1, delta_2) = (amount()?, amount()?);
+        let (lambda_1, lambda_2) = (amount()?, amount()?);
+        let (unfilled_1, unfilled_2) = (amount()?, amount()?);
+        let sct_position_prefix = Position::from(u64::try_from(get_varint(&mut bytes)?)?);
+        anyhow::ensure!(bytes.is_empty(), "trailing bytes in compact batch output");
+        anyhow::ensure!(
+            sct_position_prefix.commitment() == 0,
+            "sct_position_prefix.commitment() != 0"
+        );
+        Ok(Self {
+            delta_1,
+            delta_2,
+            lambda_1,
+            lambda_2,
+            unfilled_1,
+            unfilled_2,
+            height,
+            trading_pair,
+            sct_position_prefix,
+        })
+    }
+}
+
+/// Appends `value` to `bytes` as an unsigned LEB128 varint.
+fn put_varint(bytes: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads an unsigned LEB128 varint from the front of `bytes`.
+fn get_varint(bytes: &mut &[u8]) -> Result<u128> {
+    let mut value = 0u128;
+    for shift in (0..u128::BITS).step_by(7) {
+        let (&byte, rest) = bytes
+            .split_first()
+            .ok_or_else(|| anyhow!("truncated compact batch output"))?;
+        *bytes = rest;
+        let bits = u128::from(byte & 0x7f);
+        anyhow::ensure!(
+            bits.checked_shl(shift).map(|b| b >> shift) == Some(bits),
+            "varint overflows u128"
+        );
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            // Reject non-minimal encodings, so that each batch output has one encoding.
+            anyhow::ensure!(byte != 0 || shift == 0, "non-minimal varint");
+            return Ok(value);
+        }
+    }
+    Err(anyhow!("varint overflows u128"))
+}
+
 #[cfg(test)]
 mod tests {
     use ark_groth16::{r1cs_to_qap::LibsnarkReduction, Groth16};
@@ -388,6 +605,156 @@ mod tests {
         assert_eq!(lambda_2_i, Amount::from(28766268u64));
     }
 
+    /// A batch where 100 of asset 1 was traded for 90 of asset 2, and 10 was left unfilled.
+    fn partially_filled_bsod() -> BatchSwapOutputData {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        BatchSwapOutputData {
+            delta_1: Amount::from(110u64),
+            delta_2: Amount::zero(),
+            lambda_1: Amount::zero(),
+            lambda_2: Amount::from(90u64),
+            unfilled_1: Amount::from(10u64),
+            unfilled_2: Amount::zero(),
+            height: 1,
+            trading_pair: TradingPair::new(gm.id(), gn.id()),
+            sct_position_prefix: Position::from((0, 1, 0)),
+        }
+    }
+
+    #[test]
+    fn effective_rate_is_net_of_the_claim_fee() {
+        let bsod = partially_filled_bsod();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let fee_in_output = |amount: u64| {
+            Fee(penumbra_asset::Value {
+                amount: amount.into(),
+                asset_id: gn.id(),
+            })
+        };
+
+        // 55 of asset 1 claims 45 of asset 2, less the fee of 9.
+        let delta_i = (Amount::from(55u64), Amount::zero());
+        assert_eq!(
+            bsod.effective_rate(delta_i, &fee_in_output(9)),
+            (U128x128::from(36u64) / U128x128::from(55u64)).ok()
+        );
+
+        // A fee in another asset does not reduce the output.
+        assert_eq!(
+            bsod.effective_rate(delta_i, &Fee::from_staking_token_amount(9u64.into())),
+            (U128x128::from(45u64) / U128x128::from(55u64)).ok()
+        );
+
+        // A tiny swap whose output is dominated by the fee has a rate of zero.
+        let delta_i = (Amount::from(2u64), Amount::zero());
+        assert_eq!(bsod.pro_rata_outputs(delta_i).1, Amount::from(1u64));
+        assert_eq!(
+            bsod.effective_rate(delta_i, &fee_in_output(9)),
+            Some(U128x128::from(0u64))
+        );
+
+        // A swap without exactly one input has no rate.
+        assert_eq!(
+            bsod.effective_rate((Amount::zero(), Amount::zero()), &fee_in_output(9)),
+            None
+        );
+    }
+
+    fn canonical_json_bsod() -> BatchSwapOutputData {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        BatchSwapOutputData {
+            delta_1: Amount::from(u128::MAX),
+            delta_2: Amount::from(2u64),
+            lambda_1: Amount::from(3u64),
+            lambda_2: Amount::from(u64::MAX as u128 + 1),
+            unfilled_1: Amount::from(5u64),
+            unfilled_2: Amount::zero(),
+            height: 1234,
+            trading_pair: TradingPair::new(gm.id(), gn.id()),
+            sct_position_prefix: Position::from((3, 10, 0)),
+        }
+    }
+
+    #[test]
+    fn canonical_json_round_trip() {
+        let bsod = canonical_json_bsod();
+        let json = bsod.to_canonical_json().unwrap();
+        assert_eq!(
+            BatchSwapOutputData::from_canonical_json(&json).unwrap(),
+            bsod
+        );
+    }
+
+    #[test]
+    fn canonical_json_encodes_large_amounts_as_strings() {
+        let json = canonical_json_bsod().to_canonical_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["schema_version"], serde_json::json!(1));
+        assert_eq!(
+            value["delta_1"],
+            serde_json::json!("340282366920938463463374607431768211455")
+        );
+        assert_eq!(value["lambda_2"], serde_json::json!("18446744073709551616"));
+        assert_eq!(value["unfilled_2"], serde_json::json!("0"));
+        assert_eq!(value["height"], serde_json::json!("1234"));
+    }
+
+    #[test]
+    fn compact_bytes_round_trip() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let trading_pair = TradingPair::new(gm.id(), gn.id());
+        let amounts = [
+            0,
+            1,
+            127,
+            128,
+            300,
+            u64::MAX as u128,
+            u64::MAX as u128 + 1,
+            u128::MAX,
+        ];
+
+        for (i, &amount) in amounts.iter().enumerate() {
+            for (j, &other) in amounts.iter().enumerate() {
+                let bsod = BatchSwapOutputData {
+                    delta_1: amount.into(),
+                    delta_2: other.into(),
+                    lambda_1: other.into(),
+                    lambda_2: amount.into(),
+                    unfilled_1: amounts[(i + j) % amounts.len()].into(),
+                    unfilled_2: amounts[(i * j) % amounts.len()].into(),
+                    height: (i * amounts.len() + j) as u64,
+                    trading_pair,
+                    sct_position_prefix: Position::from((i as u16, j as u16, 0)),
+                };
+                let bytes = bsod.to_compact_bytes();
+                let decoded =
+                    BatchSwapOutputData::from_compact_bytes(bsod.height, trading_pair, &bytes)
+                        .unwrap();
+                assert_eq!(decoded, bsod);
+                assert_eq!(decoded.encode_to_vec(), bsod.encode_to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn compact_bytes_reject_malformed_encodings() {
+        let bsod = canonical_json_bsod();
+        let bytes = bsod.to_compact_bytes();
+        let decode = |bytes: &[u8]| {
+            BatchSwapOutputData::from_compact_bytes(bsod.height, bsod.trading_pair, bytes)
+        };
+
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(&[bytes.as_slice(), &[0]].concat()).is_err());
+        // A zero encoded in two bytes instead of one.
+        assert!(decode(&[&[0x80, 0x00][..], &bytes[1..]].concat()).is_err());
+    }
+
     struct ProRataOutputCircuit {
         delta_1_i: Amount,
         delta_2_i: Amount,
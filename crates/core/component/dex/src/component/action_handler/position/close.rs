@@ -2,20 +2,26 @@ use anyhow::Result;
 use async_trait::async_trait;
 use cnidarium::StateWrite;
 use cnidarium_component::ActionHandler;
-use penumbra_proto::{DomainType as _, StateWriteProto as _};
+use penumbra_proto::DomainType as _;
 
-use crate::{component::PositionManager, event, lp::action::PositionClose};
+use crate::{
+    component::{DexEventRecorder as _, PositionManager},
+    event,
+    lp::action::PositionClose,
+};
 
 #[async_trait]
 /// Debits an opened position NFT and credits a closed position NFT.
 impl ActionHandler for PositionClose {
     type CheckStatelessContext = ();
+    #[tracing::instrument(name = "position_close", level = "debug", skip_all, fields(position_id = %self.position_id), ret(level = "debug"), err(level = "warn"))]
     async fn check_stateless(&self, _context: ()) -> Result<()> {
         // Nothing to do: the only validation is of the state change,
         // and that's done by the value balance mechanism.
         Ok(())
     }
 
+    #[tracing::instrument(name = "position_close", level = "debug", skip_all, fields(position_id = %self.position_id), ret(level = "debug"), err(level = "warn"))]
     async fn check_and_execute<S: StateWrite>(&self, mut state: S) -> Result<()> {
         // We don't want to actually close the position here, because otherwise
         // the economic effects could depend on intra-block ordering, and we'd
@@ -25,7 +31,7 @@ impl ActionHandler for PositionClose {
         state.queue_close_position(self.position_id);
 
         // queue position close you will...
-        state.record_proto(
+        state.record_dex_event(
             event::EventQueuePositionClose {
                 position_id: self.position_id,
             }
@@ -2,16 +2,20 @@ use anyhow::{ensure, Result};
 use async_trait::async_trait;
 use cnidarium::StateWrite;
 use cnidarium_component::ActionHandler;
+use penumbra_num::Amount;
 
 use crate::{
-    component::{PositionManager, StateReadExt},
+    component::{
+        position_manager::counter::PositionCounterRead as _, PositionManager, StateReadExt,
+    },
     lp::{action::PositionOpen, position},
 };
 
 #[async_trait]
-/// Debits the initial reserves and credits an opened position NFT.
+/// Debits the initial reserves and the open fee, and credits an opened position NFT.
 impl ActionHandler for PositionOpen {
     type CheckStatelessContext = ();
+    #[tracing::instrument(name = "position_open", level = "debug", skip_all, fields(position_id = %self.position.id()), ret(level = "debug"), err(level = "warn"))]
     async fn check_stateless(&self, _context: ()) -> Result<()> {
         // Check:
         //  + reserves are at most 80 bits wide,
@@ -27,6 +31,7 @@ impl ActionHandler for PositionOpen {
         Ok(())
     }
 
+    #[tracing::instrument(name = "position_open", level = "debug", skip_all, fields(position_id = %self.position.id()), ret(level = "debug"), err(level = "warn"))]
     async fn check_and_execute<S: StateWrite>(&self, mut state: S) -> Result<()> {
         // Only open the position if the dex is enabled in the dex params.
         let dex_params = state.get_dex_params().await?;
@@ -36,6 +41,39 @@ impl ActionHandler for PositionOpen {
             "Dex MUST be enabled to open positions."
         );
 
+        // Governance can set a fee floor per pair, to protect LPs on volatile pairs.
+        let pair = self.position.phi.pair;
+        let min_fee_bps = dex_params.min_fee_bps_for(&pair);
+        ensure!(
+            self.position.phi.component.fee >= min_fee_bps,
+            "position fee of {}bps is below the minimum of {}bps for pair {:?}",
+            self.position.phi.component.fee,
+            min_fee_bps,
+            pair
+        );
+
+        // The first position on a pair must establish a market for it, with enough
+        // reserves on both sides. Once the pair has open positions, any size goes.
+        let min_reserves = dex_params.pair_bootstrap_min_reserves;
+        if min_reserves > Amount::zero() && state.get_position_count(&pair).await == 0 {
+            let reserves = &self.position.reserves;
+            ensure!(
+                reserves.r1 >= min_reserves && reserves.r2 >= min_reserves,
+                "the first position on pair {:?} must have reserves of at least {} on both sides",
+                pair,
+                min_reserves
+            );
+        }
+
+        // The open fee is debited from the transaction's value balance, and is not
+        // credited anywhere, so it is burned.
+        ensure!(
+            self.open_fee >= dex_params.position_open_fee,
+            "position open fee of {} is below the required {}",
+            self.open_fee,
+            dex_params.position_open_fee
+        );
+
         state.open_position(self.position.clone()).await?;
         Ok(())
     }
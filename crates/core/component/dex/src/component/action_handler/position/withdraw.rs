@@ -11,12 +11,14 @@ use crate::{component::PositionManager, lp::action::PositionWithdraw};
 /// Debits a closed position NFT and credits a withdrawn position NFT and the final reserves.
 impl ActionHandler for PositionWithdraw {
     type CheckStatelessContext = ();
+    #[tracing::instrument(name = "position_withdraw", level = "debug", skip_all, fields(position_id = %self.position_id), ret(level = "debug"), err(level = "warn"))]
     async fn check_stateless(&self, _context: ()) -> Result<()> {
         // Nothing to do: the only validation is of the state change,
         // and that's done by the value balance mechanism.
         Ok(())
     }
 
+    #[tracing::instrument(name = "position_withdraw", level = "debug", skip_all, fields(position_id = %self.position_id), ret(level = "debug"), err(level = "warn"))]
     async fn check_and_execute<S: StateWrite>(&self, mut state: S) -> Result<()> {
         // See comment in check_stateful for why we check the position state here:
         // we need to ensure that we're checking the reserves at the moment we execute
@@ -5,11 +5,13 @@ use async_trait::async_trait;
 use cnidarium::StateWrite;
 use cnidarium_component::ActionHandler;
 use penumbra_proof_params::SWAP_PROOF_VERIFICATION_KEY;
-use penumbra_proto::{DomainType as _, StateWriteProto};
-use penumbra_sct::component::source::SourceContext;
+use penumbra_proto::DomainType as _;
+use penumbra_sct::component::{clock::EpochRead as _, source::SourceContext};
 
 use crate::{
-    component::{InternalDexWrite, StateReadExt, SwapDataWrite, SwapManager},
+    component::{
+        DexEventRecorder as _, InternalDexWrite, StateReadExt, SwapDataWrite, SwapManager,
+    },
     event,
     swap::{proof::SwapProofPublic, Swap},
 };
@@ -17,6 +19,7 @@ use crate::{
 #[async_trait]
 impl ActionHandler for Swap {
     type CheckStatelessContext = ();
+    #[tracing::instrument(name = "swap", level = "debug", skip_all, fields(commitment = ?self.body.payload.commitment), ret(level = "debug"), err(level = "warn"))]
     async fn check_stateless(&self, _context: ()) -> Result<()> {
         // Check that the trading pair is distinct.
         if self.body.trading_pair.asset_1() == self.body.trading_pair.asset_2() {
@@ -35,6 +38,7 @@ impl ActionHandler for Swap {
         Ok(())
     }
 
+    #[tracing::instrument(name = "swap", level = "debug", skip_all, fields(commitment = ?self.body.payload.commitment), ret(level = "debug"), err(level = "warn"))]
     async fn check_and_execute<S: StateWrite>(&self, mut state: S) -> Result<()> {
         // Only execute the swap if the dex is enabled in the dex params.
         let dex_params = state.get_dex_params().await?;
@@ -46,17 +50,31 @@ impl ActionHandler for Swap {
 
         let swap = self;
 
-        // Accumulate the swap's flows, crediting the DEX VCB for the inflows.
-        let flow = (swap.body.delta_1_i, swap.body.delta_2_i);
-        state
-            .accumulate_swap_flow(&swap.body.trading_pair, flow.into())
-            .await?;
+        // Reject swaps exceeding their pair's swap size limit before they enter the batch.
+        dex_params.check_swap_size(
+            &swap.body.trading_pair,
+            swap.body.delta_1_i,
+            swap.body.delta_2_i,
+        )?;
 
-        // Record the swap commitment in the state.
         let source = state.get_current_source().expect("source is set");
-        state
-            .add_swap_payload(self.body.payload.clone(), source)
-            .await;
+        if dex_params.executes_batches_at(state.get_block_height().await?) {
+            // Accumulate the swap's flows, crediting the DEX VCB for the inflows.
+            let flow = (swap.body.delta_1_i, swap.body.delta_2_i);
+            state
+                .accumulate_swap_flow(&swap.body.trading_pair, flow.into())
+                .await?;
+            state.queue_claimable_swap(&swap.body.trading_pair, flow.into());
+
+            // Record the swap commitment in the state.
+            state
+                .add_swap_payload(self.body.payload.clone(), source)
+                .await;
+        } else {
+            // Hold the swap until the next batch execution, crediting the DEX VCB
+            // for the inflows. Its commitment is recorded once it is released.
+            state.defer_swap(&swap.body, source).await?;
+        }
 
         // Mark the assets for the swap's trading pair as accessed during this block.
         let fixed_candidates = Arc::new(dex_params.fixed_candidates.clone());
@@ -66,7 +84,7 @@ impl ActionHandler for Swap {
         );
         state.add_recently_accessed_asset(swap.body.trading_pair.asset_2(), fixed_candidates);
 
-        state.record_proto(event::EventSwap::from(self).to_proto());
+        state.record_dex_event(event::EventSwap::from(self).to_proto());
 
         Ok(())
     }
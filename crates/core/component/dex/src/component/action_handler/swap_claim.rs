@@ -7,8 +7,9 @@ use penumbra_txhash::TransactionContext;
 
 use cnidarium::{StateRead, StateWrite};
 use penumbra_proof_params::SWAPCLAIM_PROOF_VERIFICATION_KEY;
-use penumbra_proto::{DomainType as _, StateWriteProto};
+use penumbra_proto::DomainType as _;
 use penumbra_sct::component::{
+    clock::EpochRead as _,
     source::SourceContext,
     tree::{SctManager, VerificationExt},
     StateReadExt as _,
@@ -16,32 +17,27 @@ use penumbra_sct::component::{
 use penumbra_shielded_pool::component::NoteManager;
 
 use crate::{
-    component::StateReadExt,
+    component::{DexEventRecorder as _, StateReadExt, SwapDataRead as _, SwapDataWrite as _},
     event,
-    swap_claim::{SwapClaim, SwapClaimProofPublic},
+    swap_claim::{DuplicateProof, SwapClaim},
 };
 
 #[async_trait]
 impl ActionHandler for SwapClaim {
     type CheckStatelessContext = TransactionContext;
+    #[tracing::instrument(name = "swap_claim", level = "debug", skip_all, fields(nullifier = ?self.body.nullifier), ret(level = "debug"), err(level = "warn"))]
     async fn check_stateless(&self, context: TransactionContext) -> Result<()> {
         self.proof
             .verify(
                 &SWAPCLAIM_PROOF_VERIFICATION_KEY,
-                SwapClaimProofPublic {
-                    anchor: context.anchor,
-                    nullifier: self.body.nullifier,
-                    claim_fee: self.body.fee.clone(),
-                    output_data: self.body.output_data,
-                    note_commitment_1: self.body.output_1_commitment,
-                    note_commitment_2: self.body.output_2_commitment,
-                },
+                self.proof_public(context.anchor),
             )
             .context("a swap claim proof did not verify")?;
 
         Ok(())
     }
 
+    #[tracing::instrument(name = "swap_claim", level = "debug", skip_all, fields(nullifier = ?self.body.nullifier), ret(level = "debug"), err(level = "warn"))]
     async fn check_historical<S: StateRead + 'static>(&self, state: Arc<S>) -> Result<()> {
         let swap_claim = self;
 
@@ -76,10 +72,50 @@ impl ActionHandler for SwapClaim {
         Ok(())
     }
 
+    #[tracing::instrument(name = "swap_claim", level = "debug", skip_all, fields(nullifier = ?self.body.nullifier), ret(level = "debug"), err(level = "warn"))]
     async fn check_and_execute<S: StateWrite>(&self, mut state: S) -> Result<()> {
         // 3. Check that the nullifier hasn't been spent before.
+        //
+        // If it was spent by a claim with this exact proof, the claim is a resubmission
+        // of that one, which is reported as such on top of the double spend.
         let spent_nullifier = self.body.nullifier;
-        state.check_nullifier_unspent(spent_nullifier).await?;
+        if let Err(e) = state.check_nullifier_unspent(spent_nullifier).await {
+            return Err(match state.swap_claim_proof_height(&self.proof).await? {
+                Some(height) => e.context(DuplicateProof {
+                    proof_hash: self.proof.hash(),
+                    height,
+                }),
+                None => e,
+            });
+        }
+
+        // 4. Check that the swap's grace period has elapsed, so that it can't be claimed
+        // the moment its batch clears.
+        //
+        // This is checked here, rather than in `check_historical`, because it depends
+        // on the height of the block the claim is included in.
+        let output_height = self.body.output_data.height;
+        let height = state.get_block_height().await?;
+        let dex_params = state.get_dex_params().await?;
+        if !dex_params.swap_claimable_at(output_height, height) {
+            anyhow::bail!(
+                "swap from batch at height {output_height} cannot be claimed yet at height {height}"
+            );
+        }
+
+        // 5. Check that the claim pays its fee in an allowed asset.
+        //
+        // The claim fee is fixed when the swap is made, so swaps made before the
+        // allowed assets last changed are grandfathered, rather than left unclaimable.
+        let fee_asset_id = self.body.fee.asset_id();
+        // If the assets never changed, every swap was made under the current ones.
+        let grandfathered = match state.swap_claim_fee_assets_since().await? {
+            Some(since) => dex_params.grandfathers_swap_claim_fee(output_height, since),
+            None => false,
+        };
+        if !grandfathered && !dex_params.allows_swap_claim_fee_asset(fee_asset_id) {
+            anyhow::bail!("swap claims cannot pay fees in {fee_asset_id}");
+        }
 
         // Record the output notes in the state.
         let source = state
@@ -95,7 +131,11 @@ impl ActionHandler for SwapClaim {
 
         state.nullify(self.body.nullifier, source).await;
 
-        state.record_proto(event::EventSwapClaim::from(self).to_proto());
+        // The claimed swap's inputs no longer count as locked in its batch.
+        state.record_swap_claim(&self.body.output_data).await?;
+        state.record_swap_claim_proof(&self.proof, height);
+
+        state.record_dex_event(event::EventSwapClaim::from(self).to_proto());
 
         Ok(())
     }
@@ -5,12 +5,14 @@ use anyhow::Result;
 use async_trait::async_trait;
 use cnidarium::{StateDelta, StateWrite};
 use penumbra_asset::{asset, Value};
-use penumbra_proto::{DomainType as _, StateWriteProto as _};
+use penumbra_proto::DomainType as _;
 use penumbra_sct::component::clock::EpochRead;
 use tracing::instrument;
 
 use crate::{
-    component::{ExecutionCircuitBreaker, InternalDexWrite, ValueCircuitBreaker},
+    component::{
+        DexEventRecorder as _, ExecutionCircuitBreaker, InternalDexWrite, ValueCircuitBreaker,
+    },
     event, SwapExecution,
 };
 
@@ -134,7 +136,7 @@ pub trait Arbitrage: StateWrite + Sized {
             .await?;
 
         // Emit an ABCI event detailing the arb execution.
-        self_mut.record_proto(
+        self_mut.record_dex_event(
             event::EventArbExecution {
                 height,
                 swap_execution: se,
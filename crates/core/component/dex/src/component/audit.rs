@@ -0,0 +1,159 @@
+//! Audits of the value held by the DEX.
+//!
+//! The value circuit breaker tracks the balance of each asset held by the DEX,
+//! crediting and debiting it as value flows in and out. An audit recomputes what
+//! that balance should be from the state itself, so that a value leak shows up as
+//! a discrepancy between the two.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use cnidarium::StateRead;
+use futures::{StreamExt as _, TryStreamExt as _};
+use penumbra_asset::asset;
+use penumbra_num::Amount;
+use penumbra_proto::StateReadProto as _;
+
+use crate::{
+    component::{PositionRead as _, SwapDataRead as _},
+    state_key, swap,
+};
+
+#[async_trait]
+pub trait ValueBalanceAudit: StateRead {
+    /// Compares the value the DEX should hold with the balance recorded by its value
+    /// circuit breaker, returning `(expected, actual)` for each asset.
+    ///
+    /// The DEX should hold the reserves of every position that has not been
+    /// withdrawn, and the inputs of every swap that has not been executed yet: those
+    /// queued in the current block, and those deferred to a later batch. On a healthy
+    /// chain, the two amounts are equal for every asset.
+    async fn audit_value_balance(&self) -> Result<BTreeMap<asset::Id, (Amount, Amount)>> {
+        let mut expected = BTreeMap::<asset::Id, Amount>::new();
+        let mut add = |asset_id: asset::Id, amount: Amount| -> Result<()> {
+            if amount == Amount::zero() {
+                return Ok(());
+            }
+            let total = expected.entry(asset_id).or_default();
+            *total = total
+                .checked_add(&amount)
+                .with_context(|| format!("overflowed expected balance of {asset_id}"))?;
+            Ok(())
+        };
+
+        let mut positions = self.all_positions();
+        while let Some(position) = positions.next().await {
+            let position = position?;
+            for reserves in [position.reserves_1(), position.reserves_2()] {
+                add(reserves.asset_id, reserves.amount)?;
+            }
+        }
+
+        for (pair, flow) in self.swap_flows() {
+            let (delta_1, delta_2) = *flow;
+            add(pair.asset_1(), delta_1)?;
+            add(pair.asset_2(), delta_2)?;
+        }
+
+        let deferred: Vec<(String, swap::Body)> = self
+            .prefix(state_key::deferred_swaps::prefix())
+            .try_collect()
+            .await?;
+        for (_, body) in deferred {
+            add(body.trading_pair.asset_1(), body.delta_1_i)?;
+            add(body.trading_pair.asset_2(), body.delta_2_i)?;
+        }
+
+        let prefix = state_key::value_balance_prefix();
+        let actual: BTreeMap<asset::Id, Amount> = self
+            .prefix::<Amount>(prefix)
+            .map(|entry| {
+                let (key, amount) = entry?;
+                let asset_id = key[prefix.len()..]
+                    .parse()
+                    .with_context(|| format!("malformed value balance key {key}"))?;
+                Ok::<_, anyhow::Error>((asset_id, amount))
+            })
+            .try_collect()
+            .await?;
+
+        let mut audit: BTreeMap<asset::Id, (Amount, Amount)> = expected
+            .into_iter()
+            .map(|(asset_id, amount)| (asset_id, (amount, Amount::zero())))
+            .collect();
+        for (asset_id, amount) in actual {
+            audit.entry(asset_id).or_default().1 = amount;
+        }
+        Ok(audit)
+    }
+}
+
+impl<T: StateRead + ?Sized> ValueBalanceAudit for T {}
+
+#[cfg(test)]
+mod tests {
+    use cnidarium::{StateDelta, TempStorage};
+    use penumbra_proto::StateWriteProto as _;
+
+    use super::*;
+    use crate::{
+        component::{
+            router::{create_buy, create_sell},
+            tests::TempStorageExt as _,
+            PositionManager as _, SwapDataWrite as _,
+        },
+        DirectedUnitPair, TradingPair,
+    };
+
+    #[tokio::test]
+    async fn audit_detects_leaked_value() -> anyhow::Result<()> {
+        let _ = tracing_subscriber::fmt::try_init();
+        let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+        let mut state = StateDelta::new(storage.latest_snapshot());
+
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let pair = DirectedUnitPair::new(gm.clone(), gn.clone());
+
+        // Open positions on both sides of the book, and queue a swap of gm for gn.
+        let buy = create_buy(pair.clone(), 1u64.into(), 2u64.into());
+        let sell = create_sell(pair.clone(), 3u64.into(), 2u64.into());
+        state.open_position(buy.clone()).await?;
+        state.open_position(sell.clone()).await?;
+        let trading_pair = TradingPair::new(gm.id(), gn.id());
+        let swap_input = Amount::from(10u64);
+        let flow = if trading_pair.asset_1() == gm.id() {
+            (swap_input, Amount::zero())
+        } else {
+            (Amount::zero(), swap_input)
+        };
+        state
+            .accumulate_swap_flow(&trading_pair, flow.into())
+            .await?;
+
+        let reserves = |asset_id: asset::Id| {
+            [&buy, &sell]
+                .iter()
+                .filter_map(|position| position.reserves_for(asset_id))
+                .fold(Amount::zero(), |total, amount| total + amount)
+        };
+        let expected_gm = reserves(gm.id()) + swap_input;
+        let expected_gn = reserves(gn.id());
+
+        // A healthy state holds exactly what it should.
+        let audit = state.audit_value_balance().await?;
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[&gm.id()], (expected_gm, expected_gm));
+        assert_eq!(audit[&gn.id()], (expected_gn, expected_gn));
+
+        // Leaking some gn out of the DEX without touching the positions is detected.
+        let leaked = expected_gn - Amount::from(1u64);
+        state.put(state_key::value_balance(&gn.id()), leaked);
+        let audit = state.audit_value_balance().await?;
+        assert_eq!(audit[&gm.id()], (expected_gm, expected_gm));
+        assert_eq!(audit[&gn.id()], (expected_gn, leaked));
+
+        Ok(())
+    }
+}
@@ -8,6 +8,7 @@ use penumbra_proto::{DomainType, StateReadProto, StateWriteProto};
 use penumbra_sct::component::clock::EpochRead as _;
 use tonic::async_trait;
 
+use crate::component::DexEventRecorder as _;
 use crate::event::EventCandlestickData;
 use crate::{lp::position::Position, state_key::candlesticks, DirectedTradingPair, SwapExecution};
 
@@ -211,7 +212,7 @@ pub trait Chandelier: StateWrite {
                 candlesticks::data::by_pair_and_height(&trading_pair, height).into(),
                 candlestick,
             );
-            self.record_proto(
+            self.record_dex_event(
                 EventCandlestickData {
                     pair: *trading_pair,
                     stick: candlestick,
@@ -6,7 +6,7 @@ use penumbra_proto::{DomainType, StateReadProto, StateWriteProto};
 use tonic::async_trait;
 use tracing::instrument;
 
-use crate::{event, state_key};
+use crate::{component::DexEventRecorder as _, event, state_key};
 
 #[async_trait]
 pub trait ValueCircuitBreakerRead: StateRead {
@@ -39,7 +39,7 @@ pub(crate) trait ValueCircuitBreaker: StateWrite {
         tracing::debug!(?prev_balance, ?new_balance, "crediting the dex VCB");
         self.put(state_key::value_balance(&value.asset_id), new_balance);
 
-        self.record_proto(
+        self.record_dex_event(
             event::EventValueCircuitBreakerCredit {
                 asset_id: value.asset_id,
                 previous_balance: prev_balance,
@@ -68,7 +68,7 @@ pub(crate) trait ValueCircuitBreaker: StateWrite {
         tracing::debug!(?prev_balance, ?new_balance, "crediting the dex VCB");
         self.put(state_key::value_balance(&value.asset_id), new_balance);
 
-        self.record_proto(
+        self.record_dex_event(
             event::EventValueCircuitBreakerDebit {
                 asset_id: value.asset_id,
                 previous_balance: prev_balance,
@@ -176,6 +176,7 @@ mod tests {
         // Create the PositionOpen action
         let pos_open = PositionOpen {
             position: buy_1.clone(),
+            open_fee: Amount::zero(),
         };
 
         // Execute the PositionOpen action.
@@ -5,6 +5,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use cnidarium::{StateRead, StateWrite};
 use cnidarium_component::Component;
+use futures::TryStreamExt as _;
 use penumbra_asset::asset;
 use penumbra_asset::{Value, STAKING_TOKEN_ASSET_ID};
 use penumbra_fee::component::StateWriteExt as _;
@@ -14,15 +15,19 @@ use penumbra_proto::{DomainType as _, StateReadProto, StateWriteProto};
 use tendermint::v0_37::abci;
 use tracing::instrument;
 
+use crate::lp::Reserves;
 use crate::state_key::block_scoped;
 use crate::{
-    component::SwapDataRead, component::SwapDataWrite, event, genesis, state_key,
-    BatchSwapOutputData, DexParameters, DirectedTradingPair, SwapExecution, TradingPair,
+    component::DexEventRecorder as _, component::SwapDataRead, component::SwapDataWrite,
+    component::SwapManager, event, genesis, state_key, BatchSwapOutputData, DexParameters,
+    DirectedTradingPair, SwapExecution, TradingPair, MIN_BATCH_OUTPUT_COMPRESSION_BLOCKS,
+    MIN_BATCH_OUTPUT_RETENTION_BLOCKS,
 };
 
 use super::eviction_manager::EvictionManager;
 use super::{
     chandelier::Chandelier,
+    fee_revenue::FeeRevenueTracker as _,
     router::{HandleBatchSwaps, RoutingParams},
     Arbitrage, PositionManager, PositionRead as _, ValueCircuitBreaker,
 };
@@ -39,6 +44,9 @@ impl Component for Dex {
             None => { /* no-op */ }
             Some(app_state) => {
                 state.put_dex_params(app_state.dex_params.clone());
+                state.put_swap_claim_fee_assets(
+                    app_state.dex_params.swap_claim_fee_assets.iter().copied(),
+                );
             }
         }
     }
@@ -55,6 +63,25 @@ impl Component for Dex {
         state: &mut Arc<S>,
         end_block: &abci::request::EndBlock,
     ) {
+        // 0. If batch swaps execute in this block, release the swaps deferred since
+        // the last batch execution, so that they execute along with this block's swaps.
+        let height: u64 = end_block
+            .height
+            .try_into()
+            .expect("height is part of the end block data");
+        let executes_batches = state
+            .get_dex_params()
+            .await
+            .expect("dex params are set")
+            .executes_batches_at(height);
+        if executes_batches {
+            Arc::get_mut(state)
+                .expect("should have unique ref at start of Dex::end_block")
+                .release_deferred_swaps()
+                .await
+                .expect("releasing deferred swaps should not fail");
+        }
+
         // F.0. Add all non-native fee payments as swap flows.
         let base_fees_and_tips = {
             let state_ref =
@@ -210,6 +237,7 @@ impl Component for Dex {
             max_hops: routing_params.max_hops + 2,
             fixed_candidates,
             price_limit: Some(1u64.into()),
+            prefer_via: None,
         };
 
         match state
@@ -248,10 +276,77 @@ impl Component for Dex {
             .finalize_block_candlesticks()
             .await
             .expect("finalizing block candlesticks should not fail");
+
+        // 6. Prune the output data of batches that fell out of the retention window.
+        Arc::get_mut(state)
+            .expect("state should be uniquely referenced after batch swaps complete")
+            .prune_output_data(
+                end_block
+                    .height
+                    .try_into()
+                    .expect("height is part of the end block data"),
+            )
+            .await
+            .expect("pruning batch output data should not fail");
+
+        // 7. Compress the output data of batches that are no longer kept in full.
+        Arc::get_mut(state)
+            .expect("state should be uniquely referenced after batch swaps complete")
+            .compress_output_data(
+                end_block
+                    .height
+                    .try_into()
+                    .expect("height is part of the end block data"),
+            )
+            .await
+            .expect("compressing batch output data should not fail");
+
+        // 8. Forget the swap claim proofs accepted outside the window in which
+        // resubmissions are reported as duplicates.
+        Arc::get_mut(state)
+            .expect("state should be uniquely referenced after batch swaps complete")
+            .prune_swap_claim_proofs(
+                end_block
+                    .height
+                    .try_into()
+                    .expect("height is part of the end block data"),
+            )
+            .await
+            .expect("pruning swap claim proofs should not fail");
+
+        // 9. Record the height from which changed swap claim fee assets apply.
+        Arc::get_mut(state)
+            .expect("state should be uniquely referenced after batch swaps complete")
+            .record_swap_claim_fee_assets_since(
+                end_block
+                    .height
+                    .try_into()
+                    .expect("height is part of the end block data"),
+            )
+            .await
+            .expect("recording the swap claim fee assets should not fail");
     }
 
-    #[instrument(name = "dex", skip(_state))]
-    async fn end_epoch<S: StateWrite + 'static>(mut _state: &mut Arc<S>) -> Result<()> {
+    #[instrument(name = "dex", skip(state))]
+    async fn end_epoch<S: StateWrite + 'static>(state: &mut Arc<S>) -> Result<()> {
+        // Snapshot the reserves of the positions open at the epoch boundary. The
+        // snapshots are only used for analytics, so failing to record them should not
+        // halt the chain.
+        if let Err(e) = Arc::get_mut(state)
+            .expect("state should be uniquely referenced at the end of the epoch")
+            .snapshot_position_reserves()
+            .await
+        {
+            tracing::error!(?e, "failed to snapshot position reserves");
+        }
+        // Likewise, the fee revenue is only kept for reporting.
+        if let Err(e) = Arc::get_mut(state)
+            .expect("state should be uniquely referenced at the end of the epoch")
+            .prune_fee_revenue()
+            .await
+        {
+            tracing::error!(?e, "failed to prune position fee revenue");
+        }
         Ok(())
     }
 }
@@ -271,13 +366,67 @@ pub trait StateReadExt: StateRead {
         self.get_dex_params().await.map(RoutingParams::from)
     }
 
+    /// Gets the height from which the current swap claim fee assets apply, or `None` if
+    /// they haven't changed since genesis.
+    async fn swap_claim_fee_assets_since(&self) -> Result<Option<u64>> {
+        self.get_proto(state_key::swap_claim_fee_assets_since())
+            .await
+    }
+
+    /// Gets the output data of the batch executed on `trading_pair` at `height`, whether
+    /// it is still stored in full or was compressed.
     async fn output_data(
         &self,
         height: u64,
         trading_pair: TradingPair,
     ) -> Result<Option<BatchSwapOutputData>> {
-        self.get(&state_key::output_data(height, trading_pair))
-            .await
+        if let Some(output_data) = self
+            .get(&state_key::output_data(height, trading_pair))
+            .await?
+        {
+            return Ok(Some(output_data));
+        }
+        self.get_raw(&state_key::output_data_compact(height, trading_pair))
+            .await?
+            .map(|bytes| BatchSwapOutputData::from_compact_bytes(height, trading_pair, &bytes))
+            .transpose()
+    }
+
+    /// Gets the value locked in the unclaimed swaps on `pair`, as the inputs of the
+    /// swaps whose batches have executed.
+    ///
+    /// Swaps still waiting for their batch to execute are pending rather than unclaimed,
+    /// and are not counted. Claims don't reveal which swap they claim, so the inputs of
+    /// a batch count in full until all of its swaps have been claimed, making this an
+    /// upper bound for batches that were partially claimed.
+    async fn unclaimed_swap_value(&self, pair: TradingPair) -> Result<(Amount, Amount)> {
+        let prefix = state_key::unclaimed_swaps::inputs_by_pair(&pair);
+        let mut total = (Amount::zero(), Amount::zero());
+        let mut stream = self.prefix::<Reserves>(&prefix);
+        while let Some((_, inputs)) = stream.try_next().await? {
+            total = (total.0 + inputs.r1, total.1 + inputs.r2);
+        }
+        Ok(total)
+    }
+
+    /// Gets the total inputs and the number of the unclaimed swaps of the batch on
+    /// `pair` executed at `height`, if any.
+    async fn unclaimed_swaps_at(
+        &self,
+        pair: &TradingPair,
+        height: u64,
+    ) -> Result<Option<((Amount, Amount), u64)>> {
+        let Some(count) = self
+            .get_proto::<u64>(&state_key::unclaimed_swaps::count(pair, height))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let inputs: Reserves = self
+            .get(&state_key::unclaimed_swaps::inputs(pair, height))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("missing inputs of unclaimed swaps"))?;
+        Ok(Some(((inputs.r1, inputs.r2), count)))
     }
 
     async fn swap_execution(
@@ -295,6 +444,9 @@ pub trait StateReadExt: StateRead {
 
     /// Return a set of [`TradingPair`]s for which liquidity positions were opened
     /// during this block.
+    ///
+    /// The set iterates in the order of [`TradingPair`]'s `Ord`, independent of the
+    /// order in which the pairs were marked active.
     fn get_active_trading_pairs_in_block(&self) -> BTreeSet<TradingPair> {
         self.object_get(block_scoped::active::trading_pairs())
             .unwrap_or_default()
@@ -393,13 +545,16 @@ pub(crate) trait InternalDexWrite: StateWrite {
             self.put_swap_execution_at_height(height, tp_2_for_1, swap_execution);
         }
 
+        // Start counting the batch's user swaps as unclaimed.
+        self.record_unclaimed_swaps(&output_data);
+
         // ... and also add it to the set in the compact block to be pushed out to clients.
         let mut outputs = self.pending_batch_swap_outputs();
         outputs.insert(trading_pair, output_data);
         self.object_put(state_key::pending_outputs(), outputs);
 
         // Also generate an ABCI event for indexing:
-        self.record_proto(
+        self.record_dex_event(
             event::EventBatchSwap {
                 batch_swap_output_data: output_data,
                 swap_execution_1_for_2,
@@ -411,6 +566,142 @@ pub(crate) trait InternalDexWrite: StateWrite {
         Ok(())
     }
 
+    /// Prunes the output data of the batches executed at the height that falls out of
+    /// the retention window at `height`, if the DEX parameters set one.
+    ///
+    /// The window is never shorter than [`MIN_BATCH_OUTPUT_RETENTION_BLOCKS`]. The output
+    /// data of a batch with unclaimed swaps is kept past the window, and pruned once the
+    /// last of them is claimed. Only one height is pruned per block, so shortening the
+    /// window leaves the batches that skipped over in place.
+    async fn prune_output_data(&mut self, height: u64) -> Result<()> {
+        let retention_blocks = self.get_dex_params().await?.batch_output_retention_blocks;
+        if retention_blocks == 0 {
+            return Ok(());
+        }
+        let retention_blocks = retention_blocks.max(MIN_BATCH_OUTPUT_RETENTION_BLOCKS);
+        let Some(expired_height) = height.checked_sub(retention_blocks) else {
+            return Ok(());
+        };
+
+        let mut expired_pairs = BTreeSet::new();
+        for prefix in [
+            state_key::output_data_at_height(expired_height),
+            state_key::output_data_compact_at_height(expired_height),
+        ] {
+            let keys: Vec<String> = self
+                .prefix_raw(&prefix)
+                .map_ok(|(key, _)| key)
+                .try_collect()
+                .await?;
+            for key in keys {
+                let (asset_1, asset_2) = key[prefix.len()..]
+                    .split_once('/')
+                    .ok_or_else(|| anyhow::anyhow!("malformed batch output data key {key}"))?;
+                expired_pairs.insert(TradingPair::new(asset_1.parse()?, asset_2.parse()?));
+            }
+        }
+
+        for pair in expired_pairs {
+            if self
+                .unclaimed_swaps_at(&pair, expired_height)
+                .await?
+                .is_some()
+            {
+                tracing::debug!(
+                    expired_height,
+                    ?pair,
+                    "keeping expired batch output data with unclaimed swaps"
+                );
+                continue;
+            }
+            tracing::debug!(expired_height, ?pair, "pruning expired batch output data");
+            self.delete_output_data(expired_height, pair);
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the output data of the batch on `trading_pair` executed at `height`,
+    /// whether it is kept in full or compacted.
+    fn delete_output_data(&mut self, height: u64, trading_pair: TradingPair) {
+        self.delete(state_key::output_data(height, trading_pair));
+        self.delete(state_key::output_data_compact(height, trading_pair));
+    }
+
+    /// Replaces the output data of the batches executed at the height that falls out of
+    /// the window kept in full at `height` with its compact encoding, if the DEX
+    /// parameters set one.
+    ///
+    /// The window is never shorter than [`MIN_BATCH_OUTPUT_COMPRESSION_BLOCKS`]. Reads
+    /// through [`StateReadExt::output_data`] return the same batch output either way.
+    async fn compress_output_data(&mut self, height: u64) -> Result<()> {
+        let compression_blocks = self.get_dex_params().await?.batch_output_compression_blocks;
+        if compression_blocks == 0 {
+            return Ok(());
+        }
+        let compression_blocks = compression_blocks.max(MIN_BATCH_OUTPUT_COMPRESSION_BLOCKS);
+        let Some(compressed_height) = height.checked_sub(compression_blocks) else {
+            return Ok(());
+        };
+
+        let outputs: Vec<(String, BatchSwapOutputData)> = self
+            .prefix(&state_key::output_data_at_height(compressed_height))
+            .try_collect()
+            .await?;
+        tracing::debug!(
+            compressed_height,
+            count = outputs.len(),
+            "compressing batch output data"
+        );
+        for (key, output_data) in outputs {
+            self.put_raw(
+                state_key::output_data_compact(output_data.height, output_data.trading_pair),
+                output_data.to_compact_bytes(),
+            );
+            self.delete(key);
+        }
+
+        Ok(())
+    }
+
+    /// Records `height` as the height from which the current swap claim fee assets
+    /// apply, if they differ from the ones recorded when they last changed.
+    ///
+    /// Only the set of assets is compared, so changes to other DEX parameters, or to
+    /// the order of the assets, leave the height in place.
+    async fn record_swap_claim_fee_assets_since(&mut self, height: u64) -> Result<()> {
+        let assets: BTreeSet<asset::Id> = self
+            .get_dex_params()
+            .await?
+            .swap_claim_fee_assets
+            .into_iter()
+            .collect();
+        let recorded: Vec<(String, asset::Id)> = self
+            .prefix(state_key::swap_claim_fee_assets_prefix())
+            .try_collect()
+            .await?;
+        let recorded_assets: BTreeSet<asset::Id> =
+            recorded.iter().map(|(_, asset_id)| *asset_id).collect();
+        if recorded_assets == assets {
+            return Ok(());
+        }
+
+        tracing::debug!(height, ?assets, "swap claim fee assets changed");
+        for (key, _) in recorded {
+            self.delete(key);
+        }
+        self.put_swap_claim_fee_assets(assets);
+        self.put_proto(state_key::swap_claim_fee_assets_since().to_string(), height);
+        Ok(())
+    }
+
+    /// Records `assets` as the current swap claim fee assets, to detect later changes.
+    fn put_swap_claim_fee_assets(&mut self, assets: impl IntoIterator<Item = asset::Id>) {
+        for asset_id in assets {
+            self.put(state_key::swap_claim_fee_asset(&asset_id), asset_id);
+        }
+    }
+
     fn set_arb_execution(&mut self, height: u64, execution: SwapExecution) {
         self.put(state_key::arb_execution(height), execution);
     }
@@ -0,0 +1,32 @@
+//! Within-block indexing of DEX events.
+//!
+//! Every DEX event is recorded with an [`EVENT_INDEX_ATTRIBUTE`] counting the DEX
+//! events recorded before it in the same block. The counter lives in the ephemeral
+//! object store, so it resets with each block, and it is rolled back along with
+//! the events of a transaction that fails to execute.
+
+use cnidarium::StateWrite;
+use penumbra_proto::event::ProtoEvent;
+use tendermint::abci;
+
+use crate::{event::EVENT_INDEX_ATTRIBUTE, state_key};
+
+pub(crate) trait DexEventRecorder: StateWrite {
+    /// Records a DEX event, tagged with its index among the DEX events of the block.
+    fn record_dex_event<E: ProtoEvent>(&mut self, proto_event: E) {
+        let index: u64 = self
+            .object_get(state_key::event_index::next())
+            .unwrap_or_default();
+        self.object_put(state_key::event_index::next(), index + 1);
+
+        let mut event = proto_event.into_event();
+        event.attributes.push(abci::EventAttribute {
+            key: EVENT_INDEX_ATTRIBUTE.to_string(),
+            value: index.to_string(),
+            index: true,
+        });
+        self.record(event);
+    }
+}
+
+impl<T: StateWrite + ?Sized> DexEventRecorder for T {}
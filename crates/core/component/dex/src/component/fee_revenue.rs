@@ -0,0 +1,233 @@
+//! Per-epoch accounting of the fees earned by liquidity positions.
+//!
+//! The dex takes no protocol cut of trading fees: the whole fee charged by a
+//! position is kept in its reserves, and accrues to its owner. The revenue
+//! tracked here is therefore the fees accrued by liquidity providers, not
+//! revenue collected by the protocol.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use cnidarium::{StateRead, StateWrite};
+use futures::TryStreamExt as _;
+use penumbra_asset::asset;
+use penumbra_num::Amount;
+use penumbra_sct::component::clock::EpochRead as _;
+use std::collections::BTreeMap;
+
+use crate::{lp::position::Position, state_key};
+
+/// The number of epochs for which the fees accrued by liquidity positions are kept.
+pub const FEE_REVENUE_RETENTION_EPOCHS: u64 = 30;
+
+#[async_trait]
+pub trait FeeRevenueRead: StateRead {
+    /// Gets the fees accrued by liquidity positions during `epoch`, by asset.
+    ///
+    /// Assets in which no fees were accrued are omitted.
+    async fn epoch_fee_revenue(&self, epoch: u64) -> Result<BTreeMap<asset::Id, Amount>> {
+        let prefix = state_key::fee_revenue::by_epoch(epoch);
+        self.nonverifiable_prefix_raw(prefix.as_bytes())
+            .map_err(anyhow::Error::from)
+            .and_then(|(key, value)| {
+                let prefix = &prefix;
+                async move {
+                    let asset_id = std::str::from_utf8(&key)?
+                        .strip_prefix(prefix.as_str())
+                        .ok_or_else(|| anyhow!("fee revenue key outside of its prefix"))?
+                        .parse()?;
+                    let amount = Amount::from_be_bytes(
+                        value
+                            .as_slice()
+                            .try_into()
+                            .map_err(|_| anyhow!("malformed fee revenue for {asset_id}"))?,
+                    );
+                    Ok((asset_id, amount))
+                }
+            })
+            .try_collect()
+            .await
+    }
+
+    /// Gets the fees accrued by liquidity positions in `asset_id` during `epoch`.
+    async fn epoch_fee_revenue_for(&self, epoch: u64, asset_id: &asset::Id) -> Result<Amount> {
+        let key = state_key::fee_revenue::by_epoch_and_asset(epoch, asset_id);
+        self.nonverifiable_get_raw(key.as_bytes())
+            .await?
+            .map(|bytes| {
+                Ok(Amount::from_be_bytes(bytes.as_slice().try_into().map_err(
+                    |_| anyhow!("malformed fee revenue for {asset_id}"),
+                )?))
+            })
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+}
+
+impl<T: StateRead + ?Sized> FeeRevenueRead for T {}
+
+#[async_trait]
+pub(crate) trait FeeRevenueTracker: StateWrite {
+    /// Accumulates the fee earned by a position in an execution taking it from
+    /// `prev_state` to `new_state` into the revenue of the current epoch.
+    ///
+    /// The fee is charged on the input to the position, which is the asset whose
+    /// reserves increased: `fee` basis points of the input are kept by the position
+    /// on top of the amount that was priced into the trade.
+    async fn record_position_fees(
+        &mut self,
+        prev_state: &Position,
+        new_state: &Position,
+    ) -> Result<()> {
+        let pair = prev_state.phi.pair;
+        let input = [pair.asset_1(), pair.asset_2()]
+            .into_iter()
+            .find_map(|asset_id| {
+                let prev = prev_state.reserves_for(asset_id)?;
+                let new = new_state.reserves_for(asset_id)?;
+                (new > prev).then(|| (asset_id, new - prev))
+            });
+        let Some((asset_id, input)) = input else {
+            return Ok(());
+        };
+
+        let fee = input
+            .value()
+            .checked_mul(prev_state.phi.component.fee.into())
+            .ok_or_else(|| anyhow!("fee overflow for position {}", prev_state.id()))?
+            / 10_000;
+        if fee == 0 {
+            return Ok(());
+        }
+
+        let epoch = self.get_current_epoch().await?.index;
+        let revenue = self
+            .epoch_fee_revenue_for(epoch, &asset_id)
+            .await?
+            .checked_add(&Amount::from(fee))
+            .ok_or_else(|| anyhow!("fee revenue overflow for {asset_id}"))?;
+        self.nonverifiable_put_raw(
+            state_key::fee_revenue::by_epoch_and_asset(epoch, &asset_id).into(),
+            revenue.to_be_bytes().to_vec(),
+        );
+        Ok(())
+    }
+
+    /// Prunes the fee revenue of the epoch that falls out of the
+    /// [`FEE_REVENUE_RETENTION_EPOCHS`] window at the end of the current epoch.
+    async fn prune_fee_revenue(&mut self) -> Result<()> {
+        let epoch = self.get_current_epoch().await?.index;
+        let Some(expired_epoch) = epoch.checked_sub(FEE_REVENUE_RETENTION_EPOCHS) else {
+            return Ok(());
+        };
+        let prefix = state_key::fee_revenue::by_epoch(expired_epoch);
+        let expired: Vec<Vec<u8>> = self
+            .nonverifiable_prefix_raw(prefix.as_bytes())
+            .map_ok(|(key, _)| key)
+            .try_collect()
+            .await?;
+        tracing::debug!(
+            expired_epoch,
+            count = expired.len(),
+            "pruning position fee revenue"
+        );
+        for key in expired {
+            self.nonverifiable_delete(key);
+        }
+        Ok(())
+    }
+}
+
+impl<T: StateWrite + ?Sized> FeeRevenueTracker for T {}
+
+#[cfg(test)]
+mod tests {
+    use cnidarium::{StateDelta, TempStorage};
+    use penumbra_sct::{component::clock::EpochManager as _, epoch::Epoch};
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::{lp::Reserves, DirectedTradingPair};
+
+    /// Puts the chain at `height`, in `epoch`.
+    fn enter_epoch(state: &mut impl StateWrite, height: u64, epoch: u64) {
+        state.put_block_height(height);
+        state.put_epoch_by_height(
+            height,
+            Epoch {
+                index: epoch,
+                start_height: height,
+            },
+        );
+    }
+
+    #[tokio::test]
+    async fn fees_accumulate_per_epoch() -> anyhow::Result<()> {
+        let storage = TempStorage::new().await?;
+        let mut state = StateDelta::new(storage.latest_snapshot());
+
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let pair = DirectedTradingPair::new(gm.id(), gn.id());
+        // A 30bps position, holding 1_000_000 of both assets.
+        let position = Position::new(
+            OsRng,
+            pair,
+            30,
+            1u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: 1_000_000u64.into(),
+                r2: 1_000_000u64.into(),
+            },
+        );
+        // The position after `input` of `asset_id` was traded into it.
+        let filled = |asset_id: asset::Id, input: u64| {
+            let mut filled = position.clone();
+            if filled.phi.pair.asset_1() == asset_id {
+                filled.reserves.r1 += Amount::from(input);
+            } else {
+                filled.reserves.r2 += Amount::from(input);
+            }
+            filled
+        };
+
+        enter_epoch(&mut state, 1, 0);
+        state
+            .record_position_fees(&position, &filled(gm.id(), 10_000))
+            .await?;
+        state
+            .record_position_fees(&position, &filled(gm.id(), 20_000))
+            .await?;
+        // Rounds down to nothing.
+        state
+            .record_position_fees(&position, &filled(gm.id(), 300))
+            .await?;
+
+        enter_epoch(&mut state, 2, 1);
+        state
+            .record_position_fees(&position, &filled(gn.id(), 100_000))
+            .await?;
+
+        assert_eq!(
+            state.epoch_fee_revenue(0).await?,
+            BTreeMap::from([(gm.id(), Amount::from(90u64))])
+        );
+        assert_eq!(
+            state.epoch_fee_revenue(1).await?,
+            BTreeMap::from([(gn.id(), Amount::from(300u64))])
+        );
+        assert_eq!(state.epoch_fee_revenue(2).await?, BTreeMap::new());
+
+        // Ending the epoch at which the first epoch falls out of the window prunes
+        // only its revenue.
+        enter_epoch(&mut state, 3, FEE_REVENUE_RETENTION_EPOCHS);
+        state.prune_fee_revenue().await?;
+        assert_eq!(state.epoch_fee_revenue(0).await?, BTreeMap::new());
+        assert_eq!(
+            state.epoch_fee_revenue(1).await?,
+            BTreeMap::from([(gn.id(), Amount::from(300u64))])
+        );
+
+        Ok(())
+    }
+}
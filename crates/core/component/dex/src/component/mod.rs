@@ -8,28 +8,45 @@ pub mod router;
 
 mod action_handler;
 mod arb;
+mod audit;
 mod chandelier;
 pub(crate) mod circuit_breaker;
 mod dex;
+mod event_index;
 mod eviction_manager;
+mod fee_revenue;
 mod flow;
 mod position_manager;
+mod preview;
+mod snapshot;
 mod swap_manager;
+mod utilization;
+mod volume;
 
+pub use audit::ValueBalanceAudit;
 pub use dex::{Dex, StateReadExt, StateWriteExt};
-pub use position_manager::PositionManager;
+pub use fee_revenue::{FeeRevenueRead, FEE_REVENUE_RETENTION_EPOCHS};
+pub use position_manager::{PositionManager, POSITION_RESERVES_RETENTION_EPOCHS};
 
 // Read data from the Dex component;
 pub use position_manager::PositionRead;
-pub use swap_manager::SwapDataRead;
+pub use preview::{SwapPreview, SwapPreviewRead, TradePrices};
+pub use snapshot::{DexSnapshot, DexSnapshotRead, SNAPSHOT_BATCH_OUTPUT_BLOCKS};
+pub use swap_manager::{SwapDataRead, SWAP_CLAIM_PROOF_RETENTION_BLOCKS};
+pub use utilization::PositionUtilizationRead;
+pub use volume::{AssetVolumeRead, MAX_VOLUME_WINDOW_BLOCKS, VOLUME_BUCKETS, VOLUME_BUCKET_BLOCKS};
 
 pub(crate) use arb::Arbitrage;
 pub(crate) use circuit_breaker::ExecutionCircuitBreaker;
 pub(crate) use circuit_breaker::ValueCircuitBreaker;
 pub use circuit_breaker::ValueCircuitBreakerRead;
 pub(crate) use dex::InternalDexWrite;
+pub(crate) use event_index::DexEventRecorder;
+pub(crate) use fee_revenue::FeeRevenueTracker;
 pub(crate) use swap_manager::SwapDataWrite;
 pub(crate) use swap_manager::SwapManager;
+pub(crate) use utilization::PositionUtilizationTracker;
+pub(crate) use volume::AssetVolumeTracker;
 
 #[cfg(test)]
 pub(crate) mod tests;
@@ -1,40 +1,50 @@
+use std::collections::BTreeSet;
 use std::future;
 use std::{pin::Pin, sync::Arc};
 
-use anyhow::{bail, ensure, Result};
+use anyhow::{ensure, Result};
 use async_stream::try_stream;
 use async_trait::async_trait;
 use cnidarium::{EscapedByteSlice, StateRead, StateWrite};
 use futures::Stream;
 use futures::StreamExt;
+use futures::TryStreamExt;
 use penumbra_asset::{asset, Balance};
+use penumbra_num::{fixpoint::U128x128, Amount};
 use penumbra_proto::DomainType;
 use penumbra_proto::{StateReadProto, StateWriteProto};
+use penumbra_sct::component::clock::EpochRead as _;
 use tap::Tap;
 use tracing::instrument;
 
 use crate::component::{
     dex::InternalDexWrite,
     dex::StateReadExt as _,
+    event_index::DexEventRecorder as _,
     position_manager::{
         base_liquidity_index::AssetByLiquidityIndex, inventory_index::PositionByInventoryIndex,
         price_index::PositionByPriceIndex,
     },
 };
-use crate::lp::Reserves;
+use crate::lp::{LpNft, Reserves};
 use crate::{
     component::position_manager::counter::PositionCounter,
     component::ValueCircuitBreaker,
     lp::position::{self, Position},
-    state_key::engine,
-    DirectedTradingPair,
+    state_key::{engine, eviction_queue},
+    DirectedTradingPair, TradingPair,
 };
 use crate::{event, state_key};
 
 use super::chandelier::Chandelier;
+use super::fee_revenue::FeeRevenueTracker;
+use super::utilization::PositionUtilizationTracker;
 
 const DYNAMIC_ASSET_LIMIT: usize = 10;
 
+/// The number of epochs for which the end-of-epoch reserves of open positions are kept.
+pub const POSITION_RESERVES_RETENTION_EPOCHS: u64 = 30;
+
 mod base_liquidity_index;
 pub(crate) mod counter;
 pub(crate) mod inventory_index;
@@ -43,6 +53,9 @@ pub(crate) mod price_index;
 #[async_trait]
 pub trait PositionRead: StateRead {
     /// Return a stream of all [`position::Metadata`] available.
+    ///
+    /// Positions are streamed in the order of their state keys, that is, by position ID,
+    /// so the same state always yields them in the same order.
     fn all_positions(
         &self,
     ) -> Pin<Box<dyn Stream<Item = Result<position::Position>> + Send + 'static>> {
@@ -77,10 +90,128 @@ pub trait PositionRead: StateRead {
             .boxed()
     }
 
+    /// Returns the trading pairs with at least one open position.
+    ///
+    /// The pairs are sorted in canonical byte order, by the encoding of their first
+    /// asset and then of their second, so the same state always yields the same list,
+    /// whatever order the positions were opened in.
+    async fn active_trading_pairs(&self) -> Result<Vec<TradingPair>> {
+        let mut pairs: Vec<TradingPair> = self
+            .all_positions()
+            .try_filter(|position| future::ready(position.state == position::State::Opened))
+            .map_ok(|position| position.phi.pair)
+            .try_collect()
+            .await?;
+        pairs.sort_unstable_by_key(|pair| pair.to_bytes());
+        pairs.dedup();
+        Ok(pairs)
+    }
+
+    /// Returns the open positions on `pair` whose effective price lies within `[low, high]`.
+    ///
+    /// The effective price is the price of the pair's second asset in terms of its
+    /// first, inclusive of fees. Unlike [`Self::positions_by_price`], this includes
+    /// positions that are depleted on one side.
+    async fn positions_in_band(
+        &self,
+        pair: TradingPair,
+        low: Amount,
+        high: Amount,
+    ) -> Result<Vec<(position::Id, position::Position)>> {
+        let (low, high) = (U128x128::from(low), U128x128::from(high));
+        self.all_positions()
+            .try_filter(|position| {
+                let price = position.phi.component.effective_price();
+                future::ready(
+                    position.phi.pair == pair
+                        && position.state == position::State::Opened
+                        && low <= price
+                        && price <= high,
+                )
+            })
+            .map_ok(|position| (position.id(), position))
+            .try_collect()
+            .await
+    }
+
     async fn position_by_id(&self, id: &position::Id) -> Result<Option<position::Position>> {
         self.get(&state_key::position_by_id(id)).await
     }
 
+    /// Fetch the reserves that a position held at the end of `epoch`.
+    ///
+    /// Returns `None` if the position was not open at the end of that epoch.
+    async fn position_reserves_at_epoch(
+        &self,
+        id: &position::Id,
+        epoch: u64,
+    ) -> Result<Option<Reserves>> {
+        self.nonverifiable_get(state_key::position_reserves_at_epoch(id, epoch).as_bytes())
+            .await
+    }
+
+    /// Fetch the heights bounding the period during which a position was open.
+    ///
+    /// Returns `None` if the position has never been opened.
+    async fn position_lifespan(&self, id: &position::Id) -> Result<Option<position::Lifespan>> {
+        let Some(open_height) = self
+            .get_proto::<u64>(&state_key::position_open_height(id))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let close_height = self
+            .get_proto::<u64>(&state_key::position_close_height(id))
+            .await?;
+        Ok(Some(position::Lifespan {
+            open_height,
+            close_height,
+        }))
+    }
+
+    /// Fetch the heights at which a position was opened, closed, and withdrawn from.
+    ///
+    /// Fails if the position has never been opened.
+    async fn position_timeline(&self, id: &position::Id) -> Result<position::PositionTimeline> {
+        let position::Lifespan {
+            open_height,
+            close_height,
+        } = self
+            .position_lifespan(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("position {id} has never been opened"))?;
+        let withdraw_height = self
+            .get_proto::<u64>(&state_key::position_withdraw_height(id))
+            .await?;
+        Ok(position::PositionTimeline {
+            open_height,
+            close_height,
+            withdraw_height,
+        })
+    }
+
+    /// Resolve the asset ID of an LPNFT to the position it was minted for, along with
+    /// the current state of that position.
+    ///
+    /// Each state transition of a position mints a new LPNFT, so an NFT for a state other
+    /// than the returned one, e.g. an opened position's NFT after it was withdrawn from, is
+    /// no longer live. Returns `None` if no position has ever had an NFT with this asset ID.
+    async fn position_by_nft(
+        &self,
+        nft_asset_id: &asset::Id,
+    ) -> Result<Option<(position::Id, position::State)>> {
+        let Some(id) = self
+            .get::<position::Id>(&state_key::position_by_nft(nft_asset_id))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let position = self.position_by_id(&id).await?.ok_or_else(|| {
+            anyhow::anyhow!("LPNFT {nft_asset_id} refers to unknown position {id}")
+        })?;
+        Ok(Some((id, position.state)))
+    }
+
     async fn check_position_by_id(&self, id: &position::Id) -> bool {
         self.get_raw(&state_key::position_by_id(id))
             .await
@@ -205,7 +336,7 @@ pub trait PositionManager: StateWrite + PositionRead {
 
         self.update_position(id, Some(prev_state), new_state)
             .await?;
-        self.record_proto(event::EventPositionClose { position_id: *id }.to_proto());
+        self.record_dex_event(event::EventPositionClose { position_id: *id }.to_proto());
 
         Ok(())
     }
@@ -229,13 +360,90 @@ pub trait PositionManager: StateWrite + PositionRead {
         Ok(())
     }
 
+    /// Closes every opened position whose total reserves are strictly below
+    /// `threshold`, returning the number of positions closed.
+    ///
+    /// This is meant to be invoked on behalf of governance, to clear positions with
+    /// negligible reserves out of the state. Swept positions go through the normal
+    /// close path, so their owners can still withdraw their reserves.
+    #[instrument(skip(self))]
+    async fn sweep_dust_positions(&mut self, threshold: Amount) -> Result<usize> {
+        let dust: Vec<position::Id> = self
+            .all_positions()
+            .try_filter(|position| {
+                let total = position.reserves.r1.saturating_add(&position.reserves.r2);
+                future::ready(position.state == position::State::Opened && total < threshold)
+            })
+            .map_ok(|position| position.id())
+            .try_collect()
+            .await?;
+
+        for id in &dust {
+            tracing::debug!(?id, "sweeping dust position");
+            self.close_position_by_id(id).await?;
+        }
+        Ok(dust.len())
+    }
+
+    /// Records the reserves of every open position at the end of the current epoch,
+    /// and prunes the snapshots of the epoch that falls out of the
+    /// [`POSITION_RESERVES_RETENTION_EPOCHS`] window.
+    #[instrument(skip(self))]
+    async fn snapshot_position_reserves(&mut self) -> Result<()> {
+        let epoch = self.get_current_epoch().await?.index;
+
+        // The inventory index has an entry for each direction of every open position.
+        let open: BTreeSet<position::Id> = self
+            .nonverifiable_prefix_raw(eviction_queue::inventory_index::prefix())
+            .map(|entry| {
+                let (key, _) = entry?;
+                let raw_id = eviction_queue::inventory_index::parse_id_from_key(key)?;
+                Ok::<_, anyhow::Error>(position::Id(raw_id))
+            })
+            .try_collect()
+            .await?;
+
+        tracing::debug!(epoch, count = open.len(), "snapshotting position reserves");
+        for id in open {
+            let position = self
+                .position_by_id(&id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("indexed position {id} does not exist"))?;
+            self.nonverifiable_put(
+                state_key::position_reserves_at_epoch(&id, epoch)
+                    .as_bytes()
+                    .to_vec(),
+                position.reserves,
+            );
+        }
+
+        if let Some(expired_epoch) = epoch.checked_sub(POSITION_RESERVES_RETENTION_EPOCHS) {
+            let prefix = state_key::position_reserves_at_epoch_prefix(expired_epoch);
+            let expired: Vec<Vec<u8>> = self
+                .nonverifiable_prefix_raw(prefix.as_bytes())
+                .map_ok(|(key, _)| key)
+                .try_collect()
+                .await?;
+            tracing::debug!(
+                expired_epoch,
+                count = expired.len(),
+                "pruning position reserve snapshots"
+            );
+            for key in expired {
+                self.nonverifiable_delete(key);
+            }
+        }
+        Ok(())
+    }
+
     /// Opens a new position, updating all necessary indexes and checking for
     /// its nonexistence prior to being opened.
     ///
     /// # Errors
     /// This method returns an error if the position is malformed
     /// e.g. it is set to a state other than `Opened`
-    ///  or, it specifies a position identifier already used by another position.
+    ///  or, it specifies a position identifier already used by another position, in which
+    ///  case the error can be downcast to a [`position::PositionIdCollision`].
     ///
     /// An error can also occur if a DEX engine invariant is breached
     /// e.g. overflowing the position counter (`u16::MAX`)
@@ -254,9 +462,11 @@ pub trait PositionManager: StateWrite + PositionRead {
 
         // Validate that the position ID doesn't collide
         if let Some(existing_lp) = self.position_by_id(&id).await? {
-            anyhow::bail!(
-                "attempted to open a position with ID {id:?}, which already exists with state {existing_lp:?}",
-            );
+            return Err(position::PositionIdCollision {
+                id,
+                state: existing_lp.state,
+            }
+            .into());
         }
 
         // Credit the DEX for the inflows from this position.
@@ -279,7 +489,7 @@ pub trait PositionManager: StateWrite + PositionRead {
         self.mark_trading_pair_as_active(position.phi.pair);
 
         // Finally, record the new position state.
-        self.record_proto(event::EventPositionOpen::from(position.clone()).to_proto());
+        self.record_dex_event(event::EventPositionOpen::from(position.clone()).to_proto());
         self.update_position(&id, None, position).await?;
 
         Ok(())
@@ -349,7 +559,7 @@ pub trait PositionManager: StateWrite + PositionRead {
 
         // We have already short-circuited no-op execution updates, so we can emit an execution
         // event and not worry about duplicates.
-        self.record_proto(
+        self.record_dex_event(
             event::EventPositionExecution::in_context(&prev_state, &new_state, context).to_proto(),
         );
 
@@ -365,7 +575,7 @@ pub trait PositionManager: StateWrite + PositionRead {
                 );
 
                 new_state.state = position::State::Closed;
-                self.record_proto(event::EventPositionClose { position_id }.to_proto());
+                self.record_dex_event(event::EventPositionClose { position_id }.to_proto());
             }
         }
 
@@ -376,6 +586,19 @@ pub trait PositionManager: StateWrite + PositionRead {
             .map_err(|e| tracing::warn!(?e, "failed to record position execution"))
             .ok();
 
+        // Accumulate the fees earned by the position into the epoch's revenue.
+        // As above, an error recording them must not halt the chain.
+        self.record_position_fees(&prev_state, &new_state)
+            .await
+            .map_err(|e| tracing::warn!(?e, "failed to record position fees"))
+            .ok();
+
+        // Track how much of its reserves the position turned over, for its utilization.
+        self.record_position_turnover(&prev_state, &new_state)
+            .await
+            .map_err(|e| tracing::warn!(?e, "failed to record position turnover"))
+            .ok();
+
         self.update_position(&position_id, Some(prev_state), new_state)
             .await
     }
@@ -433,7 +656,7 @@ pub trait PositionManager: StateWrite + PositionRead {
 
         // Record an event prior to updating the position state, so we have access to
         // the current reserves.
-        self.record_proto(
+        self.record_dex_event(
             event::EventPositionWithdraw::in_context(position_id, &prev_state).to_proto(),
         );
 
@@ -490,11 +713,61 @@ trait Inner: StateWrite {
         self.update_trading_pair_position_counter(&prev_state, &new_state)
             .await?;
         self.update_position_by_price_index(&id, &prev_state, &new_state)?;
+        self.update_position_lifespan(&id, &prev_state, &new_state)
+            .await?;
+
+        self.update_position_by_nft(&id, &prev_state, &new_state);
 
         self.put(state_key::position_by_id(&id), new_state.clone());
         Ok(new_state)
     }
 
+    /// Records the height at which a position is opened, the height at which it is closed,
+    /// and the height at which it is first withdrawn from.
+    async fn update_position_lifespan(
+        &mut self,
+        id: &position::Id,
+        prev_state: &Option<Position>,
+        new_state: &Position,
+    ) -> Result<()> {
+        use position::State::*;
+
+        let prev_state = prev_state.as_ref().map(|p| p.state);
+        match (prev_state, new_state.state) {
+            (None, Opened) => {
+                let height = self.get_block_height().await?;
+                self.put_proto(state_key::position_open_height(id), height);
+            }
+            (Some(Opened), Closed) => {
+                let height = self.get_block_height().await?;
+                self.put_proto(state_key::position_close_height(id), height);
+            }
+            (Some(Closed), Withdrawn { .. }) => {
+                let height = self.get_block_height().await?;
+                self.put_proto(state_key::position_withdraw_height(id), height);
+                // A withdrawn position is never traded against again, so its
+                // utilization is no longer needed.
+                self.clear_position_turnover(id);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Records which position the LPNFT for the position's new state belongs to.
+    fn update_position_by_nft(
+        &mut self,
+        id: &position::Id,
+        prev_state: &Option<Position>,
+        new_state: &Position,
+    ) {
+        if prev_state.as_ref().map(|p| p.state) != Some(new_state.state) {
+            let nft = LpNft::new(*id, new_state.state);
+            self.put(state_key::position_by_nft(&nft.asset_id()), *id);
+        }
+    }
+
     fn guard_invalid_transitions(
         prev_state: &Option<Position>,
         new_state: &Position,
@@ -504,29 +777,12 @@ trait Inner: StateWrite {
 
         if let Some(prev_lp) = prev_state {
             tracing::debug!(?id, prev = ?prev_lp.state, new = ?new_state.state, "evaluating state transition");
-            match (prev_lp.state, new_state.state) {
-                (Opened, Opened) => {}
-                (Opened, Closed) => {}
-                (Closed, Closed) => { /* no-op but allowed */ }
-                (Closed, Withdrawn { sequence }) => {
-                    ensure!(
-                        sequence == 0,
-                        "withdrawn positions must have their sequence start at zero (found: {})",
-                        sequence
-                    );
-                }
-                (Withdrawn { sequence: old_seq }, Withdrawn { sequence: new_seq }) => {
-                    let expected_seq = old_seq.saturating_add(1);
-                    ensure!(
-                        new_seq == expected_seq,
-                        "withdrawn must increase 1-by-1 (old: {}, new: {}, expected: {})",
-                        old_seq,
-                        new_seq,
-                        expected_seq
-                    );
-                }
-                _ => bail!("invalid transition"),
-            }
+            ensure!(
+                prev_lp.state.can_transition_to(new_state.state),
+                "invalid transition from {} to {}",
+                prev_lp.state,
+                new_state.state
+            );
         } else {
             ensure!(
                 matches!(new_state.state, Opened),
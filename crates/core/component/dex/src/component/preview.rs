@@ -0,0 +1,254 @@
+//! Previews of swap execution that account for the rest of the batch.
+//!
+//! A swap does not execute on its own: every swap on a trading pair in a block is
+//! batched together and shares the same clearing price. A preview that includes
+//! the swaps already queued in the block gives a better estimate of the output of
+//! a new swap while the pair is busy.
+//!
+//! Queued swaps only live in the state of the block being executed, so a preview
+//! against a committed snapshot sees none of them.
+//!
+//! Previews and simulations are pure reads: they execute against a fork of the
+//! state, which is discarded. A quoting service can pin a single snapshot to get
+//! consistent quotes across a burst of requests, while the chain moves on.
+//!
+//! A position can also be backtested, by replaying the batch swaps the chain still
+//! has output data for against it, as if it had existed.
+
+use std::sync::Arc;
+
+use anyhow::{ensure, Result};
+use async_trait::async_trait;
+use cnidarium::{StateDelta, StateRead};
+use penumbra_asset::{asset, Value};
+use penumbra_num::{fixpoint::U128x128, Amount};
+use penumbra_sct::component::clock::EpochRead as _;
+
+use crate::{
+    component::{
+        flow::SwapFlow,
+        router::{HandleBatchSwaps as _, RouteAndFill as _, RoutingParams},
+        ExecutionCircuitBreaker, StateReadExt as _, SwapDataRead as _,
+    },
+    lp::{position::Position, Reserves},
+    SwapExecution, TradingPair, MIN_BATCH_OUTPUT_RETENTION_BLOCKS,
+};
+
+/// The expected outcome of a swap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapPreview {
+    /// The amount of the output asset the swap is expected to receive.
+    pub output: Value,
+    /// The amount of the input asset the swap is expected to get back unfilled.
+    pub unfilled: Value,
+}
+
+/// The prices at which a simulated trade executes, in units of input per unit of
+/// output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradePrices {
+    /// The price of the first units of output, at the best available liquidity.
+    pub marginal: U128x128,
+    /// The price of the whole trade, blended across every fill.
+    pub average: U128x128,
+}
+
+#[async_trait]
+pub trait SwapPreviewRead: StateRead + Clone + 'static {
+    /// Simulates the execution of a trade of `input` into the asset `output_id` on
+    /// its own, routed with the given `routing_params`.
+    ///
+    /// If no liquidity is found, the execution is empty, with zero input and output.
+    /// The trade executes against a fork of the state, which is discarded, so repeated
+    /// simulations against the same snapshot give the same result.
+    async fn simulate_trade(
+        &self,
+        input: Value,
+        output_id: asset::Id,
+        routing_params: RoutingParams,
+    ) -> Result<SwapExecution> {
+        let execution_budget = self.get_dex_params().await?.max_execution_budget;
+        let mut fork = Arc::new(StateDelta::new(self.clone()));
+        let execution = fork
+            .route_and_fill(
+                input.asset_id,
+                output_id,
+                input.amount,
+                routing_params,
+                ExecutionCircuitBreaker::new(execution_budget),
+            )
+            .await?;
+        Ok(execution.unwrap_or_else(|| SwapExecution {
+            traces: vec![],
+            input: Value {
+                amount: Amount::zero(),
+                asset_id: input.asset_id,
+            },
+            output: Value {
+                amount: Amount::zero(),
+                asset_id: output_id,
+            },
+        }))
+    }
+
+    /// Simulates a trade of `input` into the asset `output_id`, as
+    /// [`simulate_trade`](Self::simulate_trade) does, and reports both its marginal and
+    /// its average price.
+    ///
+    /// Returns `None` if the trade finds no liquidity. For a buy, the marginal price is
+    /// never above the average price.
+    async fn simulate_trade_prices(
+        &self,
+        input: Value,
+        output_id: asset::Id,
+        routing_params: RoutingParams,
+    ) -> Result<Option<TradePrices>> {
+        let execution = self
+            .simulate_trade(input, output_id, routing_params)
+            .await?;
+        Ok(execution
+            .marginal_price()
+            .zip(execution.average_price())
+            .map(|(marginal, average)| TradePrices { marginal, average }))
+    }
+
+    /// Replays the batch swaps of the last `over_blocks` blocks on the pair of
+    /// `position` against it, as if it had existed, and returns the reserves it would
+    /// have accrued.
+    ///
+    /// In each batch, the flow in each direction trades against the position only if
+    /// the position was priced at or better than the price that flow actually cleared
+    /// at, in which case the position fills as much of the flow as it can. The replay
+    /// is bounded by the batch output data the chain retains, and only reads the state.
+    async fn simulate_position_fills(
+        &self,
+        position: &Position,
+        over_blocks: u64,
+    ) -> Result<Reserves> {
+        let height = self.get_block_height().await?;
+        let over_blocks = match self.get_dex_params().await?.batch_output_retention_blocks {
+            0 => over_blocks,
+            retention_blocks => {
+                over_blocks.min(retention_blocks.max(MIN_BATCH_OUTPUT_RETENTION_BLOCKS))
+            }
+        };
+        let first_height = (height + 1).saturating_sub(over_blocks);
+
+        let phi = &position.phi;
+        let pair = phi.pair;
+        // The price of each side of the position, in units of input per unit of output.
+        let price_1_for_2 = phi.component.effective_price();
+        let price_2_for_1 = phi.component.flip().effective_price();
+
+        let mut reserves = position.reserves.clone();
+        for output_height in first_height..=height {
+            let Some(output_data) = self.output_data(output_height, pair).await? else {
+                continue;
+            };
+            let flows = [
+                (
+                    output_data.delta_1 - output_data.unfilled_1,
+                    output_data.lambda_2,
+                    pair.asset_1(),
+                    price_1_for_2,
+                ),
+                (
+                    output_data.delta_2 - output_data.unfilled_2,
+                    output_data.lambda_1,
+                    pair.asset_2(),
+                    price_2_for_1,
+                ),
+            ];
+            for (filled, output, asset_id, position_price) in flows {
+                if filled == Amount::zero() || output == Amount::zero() {
+                    continue;
+                }
+                if position_price > U128x128::ratio(filled, output)? {
+                    continue;
+                }
+                let input = Value {
+                    amount: filled,
+                    asset_id,
+                };
+                let (_unfilled, new_reserves, _output) = phi.fill(input, &reserves)?;
+                reserves = new_reserves;
+            }
+        }
+
+        Ok(reserves)
+    }
+
+    /// Previews the execution of a swap of `input` into the asset `output_id`.
+    ///
+    /// With `include_queued`, the swap is batched with the swaps already queued on
+    /// the pair in the current block, and receives its pro rata share of the batch
+    /// output. Otherwise, or if no swaps are queued on the pair, the swap is routed
+    /// on its own, exactly as a single-swap simulation would.
+    ///
+    /// The preview executes against a fork of the state, which is discarded.
+    async fn preview_swap(
+        &self,
+        input: Value,
+        output_id: asset::Id,
+        include_queued: bool,
+    ) -> Result<SwapPreview> {
+        ensure!(
+            input.asset_id != output_id,
+            "cannot preview a swap of {} into itself",
+            output_id
+        );
+        let pair = TradingPair::new(input.asset_id, output_id);
+        let (queued_1, queued_2) = if include_queued {
+            *self.swap_flow(&pair)
+        } else {
+            (Amount::zero(), Amount::zero())
+        };
+
+        let routing_params = self.routing_params().await?;
+
+        if queued_1 == Amount::zero() && queued_2 == Amount::zero() {
+            let execution = self
+                .simulate_trade(input, output_id, routing_params)
+                .await?;
+            return Ok(SwapPreview {
+                output: execution.output,
+                unfilled: Value {
+                    amount: input.amount - execution.input.amount,
+                    asset_id: input.asset_id,
+                },
+            });
+        }
+
+        let delta_i = if input.asset_id == pair.asset_1() {
+            (input.amount, Amount::zero())
+        } else {
+            (Amount::zero(), input.amount)
+        };
+        let flow: SwapFlow = (queued_1 + delta_i.0, queued_2 + delta_i.1).into();
+        let execution_budget = self.get_dex_params().await?.max_execution_budget;
+        let height = self.get_block_height().await?;
+        let mut fork = Arc::new(StateDelta::new(self.clone()));
+        let output_data = fork
+            .handle_batch_swaps(pair, flow, height, routing_params, execution_budget)
+            .await?;
+
+        let (lambda_1_i, lambda_2_i) = output_data.pro_rata_outputs(delta_i);
+        let (output, unfilled) = if input.asset_id == pair.asset_1() {
+            (lambda_2_i, lambda_1_i)
+        } else {
+            (lambda_1_i, lambda_2_i)
+        };
+        Ok(SwapPreview {
+            output: Value {
+                amount: output,
+                asset_id: output_id,
+            },
+            unfilled: Value {
+                amount: unfilled,
+                asset_id: input.asset_id,
+            },
+        })
+    }
+}
+
+impl<T: StateRead + Clone + 'static> SwapPreviewRead for T {}
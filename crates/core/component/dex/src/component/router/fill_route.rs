@@ -68,7 +68,14 @@ pub trait FillRoute: StateWrite + Sized {
     ///
     /// # Panics
     /// At the moment, `fill_route` will panic on I/O failures (e.g., if the state is corrupted, or storage fails).
-    #[instrument(skip(self, input, hops, spill_price))]
+    #[instrument(
+        skip(self, input, hops, spill_price),
+        fields(
+            start = %input.asset_id,
+            end = ?hops.last(),
+            positions = tracing::field::Empty,
+        )
+    )]
     async fn fill_route(
         &mut self,
         input: Value,
@@ -214,6 +221,9 @@ async fn fill_route_inner<S: StateWrite + Sized>(
         }
     }
 
+    // Record how many positions the route touched, to attribute filling time.
+    tracing::Span::current().record("positions", frontier.position_ids.len());
+
     // We need to save these positions, because we mutated their state, even
     // if we didn't fully consume their reserves.
     frontier
@@ -568,8 +578,7 @@ impl<S: StateRead + StateWrite> Frontier<S> {
             }
 
             let (unfilled, new_reserves, output) = position
-                .phi
-                .fill(current_input, &position.reserves)
+                .fill(current_input)
                 .map_err(|_| FillError::ExecutionOverflow(position.id()))?;
 
             if unfilled.amount > Amount::zero() {
@@ -670,8 +679,7 @@ impl<S: StateRead + StateWrite> Frontier<S> {
 
         for i in start_index..self.positions.len() {
             let (unfilled, new_reserves, output) = self.positions[i]
-                .phi
-                .fill(current_value, &self.positions[i].reserves)
+                .fill(current_value)
                 .expect("forward fill should not fail");
 
             assert_eq!(
@@ -695,8 +703,7 @@ impl<S: StateRead + StateWrite> Frontier<S> {
             tx.trace[i + 1] = Some(current_value.amount);
 
             let (new_reserves, prev_input) = self.positions[i]
-                .phi
-                .fill_output(&self.positions[i].reserves, current_value)
+                .fill_output(current_value)
                 .expect("backward fill should not fail")
                 .expect(
                     "working backwards from most-constraining position should not exceed reserves",
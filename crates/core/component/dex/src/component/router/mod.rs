@@ -9,7 +9,7 @@ use path::Path;
 use path_cache::{PathCache, PathEntry, SharedPathCache};
 
 pub use fill_route::FillRoute;
-pub use params::RoutingParams;
+pub use params::{RoutingParams, MAX_ROUTE_HOPS, PREFER_VIA_TOLERANCE_BPS};
 pub use path_search::PathSearch;
 pub use route_and_fill::{HandleBatchSwaps, RouteAndFill};
 
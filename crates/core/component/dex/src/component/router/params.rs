@@ -5,11 +5,27 @@ use penumbra_num::fixpoint::U128x128;
 
 use crate::DexParameters;
 
+/// How much worse than the best path, in basis points, a path through the
+/// [`RoutingParams::prefer_via`] asset can be priced and still be preferred.
+pub const PREFER_VIA_TOLERANCE_BPS: u64 = 100;
+
+/// The maximum number of hops in any route, regardless of [`RoutingParams::max_hops`].
+///
+/// This bounds the cost of a path search on every node, however deep the liquidity
+/// graph is; routes that would need more hops are not found.
+pub const MAX_ROUTE_HOPS: usize = 8;
+
 #[derive(Debug, Clone)]
 pub struct RoutingParams {
     pub price_limit: Option<U128x128>,
     pub fixed_candidates: Arc<Vec<asset::Id>>,
+    /// The maximum number of hops in a route, up to [`MAX_ROUTE_HOPS`].
     pub max_hops: usize,
+    /// An intermediate asset that routes should pass through, when such a route
+    /// is priced competitively with the best route.
+    ///
+    /// If there is no competitive route through this asset, the best route is used.
+    pub prefer_via: Option<asset::Id>,
 }
 
 impl RoutingParams {
@@ -23,6 +39,14 @@ impl RoutingParams {
         }
     }
 
+    /// Prefer routes passing through the `via` asset, if they are competitive.
+    pub fn with_prefer_via(self, via: asset::Id) -> Self {
+        Self {
+            prefer_via: Some(via),
+            ..self
+        }
+    }
+
     /// Clamps the spill price to the price limit and returns whether or not it was clamped.
     pub fn clamp_to_limit(&self, spill_price: Option<U128x128>) -> (Option<U128x128>, bool) {
         match (spill_price, self.price_limit) {
@@ -52,6 +76,7 @@ impl From<DexParameters> for RoutingParams {
             fixed_candidates: Arc::new(fixed_candidates),
             max_hops: max_hops as usize,
             price_limit: None,
+            prefer_via: None,
         }
     }
 }
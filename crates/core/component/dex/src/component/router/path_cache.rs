@@ -69,15 +69,14 @@ pub(super) type SharedPathCache<S> = Arc<Mutex<PathCache<S>>>;
 impl<S: StateRead + 'static> PathCache<S> {
     /// Initializes a new PathCache with the identity path for the start asset.
     pub fn begin(start: asset::Id, state: StateDelta<S>) -> SharedPathCache<S> {
+        Self::begin_with(Path::begin(start, state))
+    }
+
+    /// Initializes a new PathCache with an existing path, so that every path
+    /// found from it is an extension of that path.
+    pub fn begin_with(path: Path<S>) -> SharedPathCache<S> {
         let mut cache = BTreeMap::new();
-        cache.insert(
-            start,
-            PathEntry {
-                path: Path::begin(start, state),
-                active: true,
-                spill: None,
-            },
-        );
+        cache.insert(*path.end(), path.into());
         Arc::new(Mutex::new(Self(cache)))
     }
 
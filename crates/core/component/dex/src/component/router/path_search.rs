@@ -12,13 +12,21 @@ use tracing::{instrument, Instrument};
 
 use crate::component::PositionRead as _;
 
-use super::{Path, PathCache, PathEntry, RoutingParams, SharedPathCache};
+use super::{
+    Path, PathCache, PathEntry, RoutingParams, SharedPathCache, MAX_ROUTE_HOPS,
+    PREFER_VIA_TOLERANCE_BPS,
+};
 
 #[async_trait]
 pub trait PathSearch: StateRead + Clone + 'static {
     /// Find the best route from `src` to `dst` with estimated price strictly less
     /// than `params.price_limit`, also returning the spill price for the next-best
     /// route, if one exists.
+    ///
+    /// If `params.prefer_via` is set, a route through that asset is returned instead
+    /// of the best route when it is priced within [`PREFER_VIA_TOLERANCE_BPS`] of it.
+    ///
+    /// Routes are limited to `params.max_hops` hops, and never exceed [`MAX_ROUTE_HOPS`].
     #[instrument(skip(self, params), fields(max_hops = params.max_hops), level = "debug", ret)]
     async fn path_search(
         &self,
@@ -30,8 +38,18 @@ pub trait PathSearch: StateRead + Clone + 'static {
             max_hops,
             fixed_candidates,
             price_limit,
+            prefer_via,
         } = params;
 
+        if max_hops > MAX_ROUTE_HOPS {
+            tracing::debug!(
+                max_hops,
+                cap = MAX_ROUTE_HOPS,
+                "capping the route length at the maximum"
+            );
+        }
+        let max_hops = max_hops.min(MAX_ROUTE_HOPS);
+
         // Initialize some metrics for calculating time spent on path searching
         // vs route filling. We use vecs so we can count across iterations of the loop.
         tracing::debug!(?src, ?dst, ?max_hops, "searching for path");
@@ -58,8 +76,24 @@ pub trait PathSearch: StateRead + Clone + 'static {
             return Ok((None, None));
         };
 
+        let (path, spill_price) = match prefer_via {
+            Some(via) if via != src && via != dst && !path.nodes.contains(&via) => {
+                let via_path = cache.lock().0.get_mut(&via).map(|entry| entry.path.fork());
+                let preferred = match via_path {
+                    Some(via_path) => {
+                        search_via(via_path, dst, max_hops, fixed_candidates.clone()).await?
+                    }
+                    None => None,
+                };
+                match preferred {
+                    Some(preferred) => prefer_if_competitive(path, spill, preferred),
+                    None => (path, spill.map(|p| p.price)),
+                }
+            }
+            _ => (path, spill.map(|p| p.price)),
+        };
+
         let nodes = path.nodes;
-        let spill_price = spill.map(|p| p.price);
         tracing::debug!(price = %path.price, spill_price = %spill_price.unwrap_or_else(|| 0u64.into()), ?src, ?nodes, "found path");
         record_duration();
 
@@ -82,6 +116,53 @@ pub trait PathSearch: StateRead + Clone + 'static {
 
 impl<S> PathSearch for S where S: StateRead + Clone + 'static {}
 
+/// Find the best extension of `via_path` to `dst`, within the remaining hops.
+async fn search_via<S: StateRead + 'static>(
+    via_path: Path<S>,
+    dst: asset::Id,
+    max_hops: usize,
+    fixed_candidates: Arc<Vec<asset::Id>>,
+) -> Result<Option<PathEntry<S>>> {
+    let remaining_hops = max_hops.saturating_sub(via_path.nodes.len());
+    let cache = PathCache::begin_with(via_path);
+    for i in 0..remaining_hops {
+        relax_active_paths(cache.clone(), fixed_candidates.clone()).await?;
+        tracing::trace!(
+            i,
+            "finished relaxing all active paths through preferred asset"
+        );
+    }
+    let entry = cache.lock().0.remove(&dst);
+    Ok(entry)
+}
+
+/// Choose the `preferred` path over the `best` one if it is priced within
+/// [`PREFER_VIA_TOLERANCE_BPS`] of it, returning the chosen path and its spill price.
+///
+/// The preferred path is only filled while it stays competitive with the best path.
+fn prefer_if_competitive<S: StateRead + 'static>(
+    best: Path<S>,
+    best_spill: Option<Path<S>>,
+    preferred: PathEntry<S>,
+) -> (Path<S>, Option<U128x128>) {
+    let threshold = U128x128::ratio(10_000 + PREFER_VIA_TOLERANCE_BPS, 10_000)
+        .and_then(|tolerance| best.price.checked_mul(&tolerance));
+    match threshold {
+        Ok(threshold) if preferred.path.price <= threshold => {
+            tracing::debug!(preferred_price = %preferred.path.price, best_price = %best.price, "using competitive path through preferred asset");
+            let spill_price = match preferred.spill {
+                Some(spill) if spill.price < threshold => spill.price,
+                _ => threshold,
+            };
+            (preferred.path, Some(spill_price))
+        }
+        _ => {
+            tracing::debug!(preferred_price = %preferred.path.price, best_price = %best.price, "path through preferred asset is not competitive");
+            (best, best_spill.map(|p| p.price))
+        }
+    }
+}
+
 #[instrument(skip_all)]
 async fn relax_active_paths<S: StateRead + 'static>(
     cache: SharedPathCache<S>,
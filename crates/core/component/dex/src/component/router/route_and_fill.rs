@@ -13,7 +13,7 @@ use crate::{
         chandelier::Chandelier,
         flow::SwapFlow,
         router::{FillRoute, PathSearch, RoutingParams},
-        ExecutionCircuitBreaker, InternalDexWrite, PositionManager,
+        AssetVolumeTracker, ExecutionCircuitBreaker, InternalDexWrite, PositionManager,
     },
     lp::position::MAX_RESERVE_AMOUNT,
     BatchSwapOutputData, SwapExecution, TradingPair,
@@ -25,7 +25,11 @@ use super::fill_route::FillError;
 /// a block's batch swap flows.
 #[async_trait]
 pub trait HandleBatchSwaps: StateWrite + Sized {
-    #[instrument(skip(self, trading_pair, batch_data, block_height, params))]
+    #[instrument(
+        name = "batch_swap",
+        skip(self, trading_pair, batch_data, block_height, params),
+        fields(%trading_pair)
+    )]
     async fn handle_batch_swaps(
         self: &mut Arc<Self>,
         trading_pair: TradingPair,
@@ -121,6 +125,19 @@ pub trait HandleBatchSwaps: StateWrite + Sized {
                 .await;
         }
 
+        // Accumulate the traded amounts of each asset into its rolling volume.
+        for se in [&swap_execution_1_for_2, &swap_execution_2_for_1]
+            .into_iter()
+            .flatten()
+        {
+            let state = Arc::get_mut(self).expect("expected state to have no other refs");
+            for value in [se.input, se.output] {
+                if let Err(e) = state.record_asset_volume(block_height, value).await {
+                    tracing::warn!(?e, ?value, "failed to record asset volume");
+                }
+            }
+        }
+
         // Fetch the swap execution object that should have been modified during the routing and filling.
         Arc::get_mut(self)
             .expect("expected state to have no other refs")
@@ -136,7 +153,10 @@ impl<T: PositionManager> HandleBatchSwaps for T {}
 /// Lower-level trait that ties together the routing and filling logic.
 #[async_trait]
 pub trait RouteAndFill: StateWrite + Sized {
-    #[instrument(skip(self, asset_1, asset_2, input, params, execution_circuit_breaker))]
+    #[instrument(
+        skip(self, asset_1, asset_2, input, params, execution_circuit_breaker),
+        fields(start = %asset_1, end = %asset_2)
+    )]
     async fn route_and_fill(
         self: &mut Arc<Self>,
         asset_1: asset::Id,
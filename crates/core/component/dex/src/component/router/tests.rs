@@ -166,6 +166,10 @@ async fn path_extension_basic() {
 }
 
 async fn create_test_positions_basic<S: StateWrite>(s: &mut S, misprice: bool) {
+    use penumbra_sct::component::clock::EpochManager as _;
+    // Opening a position records the current block height.
+    s.put_block_height(0);
+
     let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
 
     let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
@@ -2175,3 +2179,137 @@ async fn path_compare_node_ids() -> anyhow::Result<()> {
     assert!(path2 < path1);
     Ok(())
 }
+
+#[tokio::test]
+/// Checks that path search prefers a competitive route through the `prefer_via` asset,
+/// and falls back to the best route when there is none.
+async fn path_search_prefer_via() -> anyhow::Result<()> {
+    let _ = tracing_subscriber::fmt::try_init();
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let unit = |denom: &str| asset::Cache::with_known_assets().get_unit(denom).unwrap();
+    let (gm, gn, penumbra) = (unit("gm"), unit("gn"), unit("penumbra"));
+    let (pusd, btc, atom) = (unit("test_usd"), unit("test_btc"), unit("test_atom"));
+
+    // The best route is gm => gn => penumbra, at a price of 1.
+    // The route gm => test_usd => penumbra is 0.5% worse, which is competitive.
+    // The route gm => test_btc => penumbra is twice as expensive, which is not.
+    for order in [
+        "1000gn@1gm",
+        "1000penumbra@1gn",
+        "1000test_usd@1gm",
+        "1000penumbra@1.005test_usd",
+        "1000test_btc@1gm",
+        "1000penumbra@2test_btc",
+    ] {
+        let position = SellOrder::parse_str(order)?.into_position(OsRng);
+        state.open_position(position).await?;
+    }
+    let state = Arc::new(state);
+    let routing_params = state.routing_params().await?;
+
+    let best_route = vec![gn.id(), penumbra.id()];
+    let (path, _spill) = state
+        .path_search(gm.id(), penumbra.id(), routing_params.clone())
+        .await?;
+    assert_eq!(
+        path,
+        Some(best_route.clone()),
+        "best route without preference"
+    );
+
+    let (path, spill) = state
+        .path_search(
+            gm.id(),
+            penumbra.id(),
+            routing_params.clone().with_prefer_via(pusd.id()),
+        )
+        .await?;
+    assert_eq!(path, Some(vec![pusd.id(), penumbra.id()]));
+    assert!(
+        spill.is_some(),
+        "preferred route is only filled while competitive"
+    );
+
+    let (path, _spill) = state
+        .path_search(
+            gm.id(),
+            penumbra.id(),
+            routing_params.clone().with_prefer_via(btc.id()),
+        )
+        .await?;
+    assert_eq!(
+        path,
+        Some(best_route.clone()),
+        "uncompetitive preferred route should be ignored"
+    );
+
+    let (path, _spill) = state
+        .path_search(
+            gm.id(),
+            penumbra.id(),
+            routing_params.with_prefer_via(atom.id()),
+        )
+        .await?;
+    assert_eq!(
+        path,
+        Some(best_route),
+        "missing preferred route should fall back to the best route"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Routes longer than [`MAX_ROUTE_HOPS`] are not found, even when the caller allows
+/// more hops.
+async fn path_search_stops_at_max_route_hops() -> anyhow::Result<()> {
+    use super::MAX_ROUTE_HOPS;
+    use decaf377::Fq;
+    use penumbra_sct::component::clock::EpochManager as _;
+
+    let _ = tracing_subscriber::fmt::try_init();
+    let mut state = StateDelta::new(());
+    state.put_dex_params(DexParameters::default());
+    // Opening a position records the current block height.
+    state.put_block_height(0);
+
+    // A chain of pairs a_0 -> a_1 -> ... -> a_{MAX_ROUTE_HOPS + 1}.
+    let assets: Vec<_> = (0..=MAX_ROUTE_HOPS as u64 + 1)
+        .map(|i| asset::Id(Fq::from(1_000 + i)))
+        .collect();
+    for pair in assets.windows(2) {
+        let position = Position::new(
+            OsRng,
+            DirectedTradingPair::new(pair[0], pair[1]),
+            0u32,
+            1u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: 1_000u64.into(),
+                r2: 1_000u64.into(),
+            },
+        );
+        state.open_position(position).await?;
+    }
+    let state = Arc::new(state);
+
+    let mut routing_params = state.routing_params().await?;
+    routing_params.max_hops = 2 * MAX_ROUTE_HOPS;
+
+    // A route of exactly the maximum length is found.
+    let (path, _spill) = state
+        .path_search(assets[0], assets[MAX_ROUTE_HOPS], routing_params.clone())
+        .await?;
+    assert_eq!(path, Some(assets[1..=MAX_ROUTE_HOPS].to_vec()));
+
+    // One more hop is beyond the cap.
+    let (path, spill) = state
+        .path_search(assets[0], assets[MAX_ROUTE_HOPS + 1], routing_params)
+        .await?;
+    assert_eq!(path, None);
+    assert_eq!(spill, None);
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use std::{pin::Pin, sync::Arc};
+use std::pin::Pin;
 
 use anyhow::Result;
 use async_stream::try_stream;
@@ -7,7 +7,7 @@ use tokio::sync::mpsc;
 use tonic::Status;
 use tracing::instrument;
 
-use cnidarium::{StateDelta, Storage};
+use cnidarium::Storage;
 use penumbra_asset::{asset, Value};
 use penumbra_proto::{
     core::component::dex::v1::{
@@ -29,14 +29,13 @@ use penumbra_proto::{
     DomainType, StateReadProto,
 };
 
-use super::ExecutionCircuitBreaker;
 use crate::{
     component::metrics,
     lp::position::{self, Position},
     state_key, CandlestickData, DirectedTradingPair, SwapExecution, TradingPair,
 };
 
-use super::{chandelier::CandlestickRead, router::RouteAndFill, PositionRead, StateReadExt};
+use super::{chandelier::CandlestickRead, PositionRead, StateReadExt, SwapPreviewRead};
 
 pub mod stub;
 
@@ -637,39 +636,10 @@ impl SimulationService for Server {
             }
         }
 
-        let execution_budget = state
-            .get_dex_params()
-            .await
-            .expect("dex parameters are set")
-            .max_execution_budget;
-
-        let mut state_tx = Arc::new(StateDelta::new(state));
-        let execution_circuit_breaker = ExecutionCircuitBreaker::new(execution_budget);
-
-        let swap_execution = match state_tx
-            .route_and_fill(
-                input.asset_id,
-                output_id,
-                input.amount,
-                routing_params,
-                execution_circuit_breaker,
-            )
+        let swap_execution = state
+            .simulate_trade(input, output_id, routing_params)
             .await
-            .map_err(|e| tonic::Status::internal(format!("error simulating trade: {:#}", e)))?
-        {
-            Some(swap_execution) => swap_execution,
-            None => SwapExecution {
-                traces: vec![],
-                input: Value {
-                    amount: 0u64.into(),
-                    asset_id: input.asset_id,
-                },
-                output: Value {
-                    amount: 0u64.into(),
-                    asset_id: output_id,
-                },
-            },
-        };
+            .map_err(|e| tonic::Status::internal(format!("error simulating trade: {:#}", e)))?;
 
         let unfilled = Value {
             amount: input
@@ -0,0 +1,177 @@
+//! Snapshots of the DEX state for light clients.
+//!
+//! A snapshot holds the open positions and the output data of the recent batches,
+//! in a canonical order, so that every node exports the same snapshot from the same
+//! state. Each entry is stored in the JMT under its own key, so a client can check
+//! any of them against a state root, and the snapshot's hash lets it check that it
+//! holds exactly the snapshot another node exported.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use cnidarium::StateRead;
+use futures::TryStreamExt as _;
+use penumbra_proto::{DomainType as _, StateReadProto as _};
+use penumbra_sct::component::clock::EpochRead as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::PositionRead as _,
+    lp::position::{self, Position},
+    state_key, BatchSwapOutputData,
+};
+
+/// The number of blocks, up to and including the current one, whose batch output
+/// data is included in a snapshot.
+pub const SNAPSHOT_BATCH_OUTPUT_BLOCKS: u64 = 100;
+
+/// The open positions and recent batch outputs of the DEX at a given height.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DexSnapshot {
+    /// The height of the state the snapshot was exported from.
+    pub height: u64,
+    /// The open positions, ordered by ID.
+    pub positions: Vec<Position>,
+    /// The output data of the batches executed in the last
+    /// [`SNAPSHOT_BATCH_OUTPUT_BLOCKS`] blocks, ordered by height and trading pair.
+    pub batch_outputs: Vec<BatchSwapOutputData>,
+}
+
+impl DexSnapshot {
+    /// Computes the hash of this snapshot over the encoding of its entries.
+    pub fn root_hash(&self) -> [u8; 32] {
+        let mut state = blake2b_simd::Params::new()
+            .personal(b"Penumbra_DexSnap")
+            .hash_length(32)
+            .to_state();
+        state.update(&self.height.to_le_bytes());
+        state.update(&(self.positions.len() as u64).to_le_bytes());
+        for position in &self.positions {
+            let bytes = position.encode_to_vec();
+            state.update(&(bytes.len() as u64).to_le_bytes());
+            state.update(&bytes);
+        }
+        state.update(&(self.batch_outputs.len() as u64).to_le_bytes());
+        for output_data in &self.batch_outputs {
+            let bytes = output_data.encode_to_vec();
+            state.update(&(bytes.len() as u64).to_le_bytes());
+            state.update(&bytes);
+        }
+        state
+            .finalize()
+            .as_bytes()
+            .try_into()
+            .expect("hash is 32 bytes")
+    }
+}
+
+#[async_trait]
+pub trait DexSnapshotRead: StateRead {
+    /// Exports the open positions and recent batch outputs of the DEX.
+    ///
+    /// The entries are sorted after they are read, so the snapshot does not depend on
+    /// the order in which the state iterates over them.
+    async fn export_snapshot(&self) -> Result<DexSnapshot> {
+        let height = self.get_block_height().await?;
+
+        let mut positions: Vec<Position> = self
+            .all_positions()
+            .try_filter(|position| {
+                futures::future::ready(position.state == position::State::Opened)
+            })
+            .try_collect()
+            .await?;
+        positions.sort_by_key(Position::id);
+
+        let mut batch_outputs = Vec::new();
+        let first_height = height.saturating_sub(SNAPSHOT_BATCH_OUTPUT_BLOCKS - 1);
+        for output_height in first_height..=height {
+            let outputs: Vec<(String, BatchSwapOutputData)> = self
+                .prefix(&state_key::output_data_at_height(output_height))
+                .try_collect()
+                .await?;
+            batch_outputs.extend(outputs.into_iter().map(|(_, output_data)| output_data));
+        }
+        batch_outputs.sort_by_key(|output_data| (output_data.height, output_data.trading_pair));
+
+        Ok(DexSnapshot {
+            height,
+            positions,
+            batch_outputs,
+        })
+    }
+}
+
+impl<T: StateRead + ?Sized> DexSnapshotRead for T {}
+
+#[cfg(test)]
+mod tests {
+    use cnidarium::{StateDelta, TempStorage};
+    use penumbra_asset::asset;
+    use penumbra_proto::StateWriteProto as _;
+    use penumbra_sct::component::clock::EpochManager as _;
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::{
+        component::{router::create_buy, tests::TempStorageExt as _, PositionManager as _},
+        lp::SellOrder,
+        DirectedUnitPair, TradingPair,
+    };
+
+    #[tokio::test]
+    async fn snapshots_of_equivalent_states_have_the_same_hash() -> anyhow::Result<()> {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let pair = TradingPair::new(gm.id(), gn.id());
+        let positions = vec![
+            SellOrder::parse_str("10gm@1gn")?.into_position(OsRng),
+            SellOrder::parse_str("20gm@2gn")?.into_position(OsRng),
+            create_buy(
+                DirectedUnitPair::new(gm.clone(), gn.clone()),
+                1u64.into(),
+                2u64.into(),
+            ),
+        ];
+        let output_data = |height| BatchSwapOutputData {
+            delta_1: height.into(),
+            delta_2: 0u64.into(),
+            lambda_1: 0u64.into(),
+            lambda_2: height.into(),
+            unfilled_1: 0u64.into(),
+            unfilled_2: 0u64.into(),
+            height,
+            trading_pair: pair,
+            sct_position_prefix: Default::default(),
+        };
+
+        // Seed two states with the same positions and batch outputs, written in
+        // opposite orders.
+        let mut hashes = Vec::new();
+        for reversed in [false, true] {
+            let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+            let mut state = StateDelta::new(storage.latest_snapshot());
+            state.put_block_height(3);
+
+            let mut positions = positions.clone();
+            let mut heights = vec![1, 2, 3];
+            if reversed {
+                positions.reverse();
+                heights.reverse();
+            }
+            for position in positions {
+                state.open_position(position).await?;
+            }
+            for height in heights {
+                state.put(state_key::output_data(height, pair), output_data(height));
+            }
+
+            let snapshot = state.export_snapshot().await?;
+            assert_eq!(snapshot.positions.len(), 3);
+            assert_eq!(snapshot.batch_outputs.len(), 3);
+            hashes.push(snapshot.root_hash());
+        }
+        assert_eq!(hashes[0], hashes[1]);
+
+        Ok(())
+    }
+}
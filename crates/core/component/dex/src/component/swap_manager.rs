@@ -1,18 +1,36 @@
 use async_trait::async_trait;
 use cnidarium::{StateRead, StateWrite};
+use futures::TryStreamExt as _;
 use penumbra_asset::Value;
-use penumbra_sct::{component::tree::SctManager, CommitmentSource};
+use penumbra_num::Amount;
+use penumbra_sct::{
+    component::{clock::EpochRead as _, tree::SctManager},
+    CommitmentSource,
+};
 use penumbra_tct as tct;
 use tracing::instrument;
 
 use crate::component::circuit_breaker::value::ValueCircuitBreaker;
+use crate::component::{InternalDexWrite as _, StateReadExt as _};
+use crate::lp::Reserves;
 use crate::BatchSwapOutputData;
 use crate::SwapExecution;
 use crate::{
-    component::flow::SwapFlow, state_key, swap::SwapPayload, DirectedTradingPair, TradingPair,
+    component::flow::SwapFlow,
+    state_key,
+    swap::{self, SwapPayload},
+    swap_claim::SwapClaimProof,
+    DirectedTradingPair, TradingPair,
 };
-use anyhow::Result;
-use penumbra_proto::StateWriteProto;
+use anyhow::{Context as _, Result};
+use penumbra_proto::{StateReadProto, StateWriteProto};
+
+/// The number of blocks for which accepted swap claim proofs are remembered, so that
+/// a resubmitted claim can be reported as a duplicate of an earlier one.
+///
+/// Resubmissions usually arrive soon after the original claim, and older claims are
+/// still rejected as double spends, so the records don't need to be kept forever.
+pub const SWAP_CLAIM_PROOF_RETENTION_BLOCKS: u64 = 1_000;
 
 /// Manages the addition of new notes to the chain state.
 #[async_trait]
@@ -32,6 +50,34 @@ pub(crate) trait SwapManager: StateWrite {
         payloads.push_back((position, swap, source));
         self.object_put(state_key::pending_payloads(), payloads);
     }
+
+    /// Queues the swaps deferred since the last batch execution into the current
+    /// block's batches, so that they execute together with the block's own swaps.
+    ///
+    /// The commitments of deferred swaps are only added to the SCT now, so that
+    /// they are claimed against the output data of the batch they execute in.
+    #[instrument(skip(self))]
+    async fn release_deferred_swaps(&mut self) -> Result<()> {
+        let prefix = state_key::deferred_swaps::prefix();
+        let deferred: Vec<(String, swap::Body)> = self.prefix(prefix).try_collect().await?;
+
+        for (key, body) in deferred {
+            let source_key = state_key::deferred_swaps::source(&key[prefix.len()..]);
+            let source: CommitmentSource = self
+                .get(&source_key)
+                .await?
+                .context("missing source of deferred swap")?;
+            self.delete(key);
+            self.delete(source_key);
+
+            tracing::trace!(commitment = ?body.payload.commitment, "releasing deferred swap");
+            let flow = (body.delta_1_i, body.delta_2_i);
+            self.queue_swap_flow(&body.trading_pair, flow.into());
+            self.queue_claimable_swap(&body.trading_pair, flow.into());
+            self.add_swap_payload(body.payload, source).await;
+        }
+        Ok(())
+    }
 }
 
 impl<T: StateWrite + ?Sized> SwapManager for T {}
@@ -52,10 +98,32 @@ pub trait SwapDataRead: StateRead {
             .unwrap_or_default()
     }
 
+    /// Get the user swaps queued for each trading pair in this block so far, as
+    /// their total inputs and their number.
+    ///
+    /// Unlike the swap flows, these exclude the chain-submitted swaps of fees.
+    fn claimable_swaps(&self) -> im::OrdMap<TradingPair, (SwapFlow, u64)> {
+        self.object_get(state_key::claimable_swaps())
+            .unwrap_or_default()
+    }
+
     fn pending_batch_swap_outputs(&self) -> im::OrdMap<TradingPair, BatchSwapOutputData> {
         self.object_get(state_key::pending_outputs())
             .unwrap_or_default()
     }
+
+    /// Returns the height at which a swap claim with the exact same `proof` was accepted,
+    /// if any was in the last [`SWAP_CLAIM_PROOF_RETENTION_BLOCKS`] blocks.
+    async fn swap_claim_proof_height(&self, proof: &SwapClaimProof) -> Result<Option<u64>> {
+        let key = state_key::swap_claim_proof(&proof.hash());
+        let Some(bytes) = self.nonverifiable_get_raw(key.as_bytes()).await? else {
+            return Ok(None);
+        };
+        let bytes: [u8; 8] = bytes
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("malformed swap claim proof record"))?;
+        Ok(Some(u64::from_be_bytes(bytes)))
+    }
 }
 
 impl<T: StateRead + ?Sized> SwapDataRead for T {}
@@ -82,6 +150,40 @@ pub(crate) trait SwapDataWrite: StateWrite {
         })
         .await?;
 
+        self.queue_swap_flow(trading_pair, swap_flow);
+        Ok(())
+    }
+
+    /// Defers a swap to the next block that executes batch swaps.
+    ///
+    /// The DEX is credited for the swap's inflows right away, as for any other swap.
+    async fn defer_swap(&mut self, body: &swap::Body, source: CommitmentSource) -> Result<()> {
+        self.dex_vcb_credit(Value {
+            amount: body.delta_1_i,
+            asset_id: body.trading_pair.asset_1,
+        })
+        .await?;
+        self.dex_vcb_credit(Value {
+            amount: body.delta_2_i,
+            asset_id: body.trading_pair.asset_2,
+        })
+        .await?;
+
+        let height = self.get_block_height().await?;
+        let index: u64 = self
+            .object_get(state_key::deferred_swaps::block_count())
+            .unwrap_or_default();
+        self.object_put(state_key::deferred_swaps::block_count(), index + 1);
+
+        let suffix = state_key::deferred_swaps::suffix(height, index);
+        self.put(state_key::deferred_swaps::body(&suffix), body.clone());
+        self.put(state_key::deferred_swaps::source(&suffix), source);
+        Ok(())
+    }
+
+    /// Accumulates a swap flow, whose inflows were already credited to the DEX,
+    /// into the current block's batches.
+    fn queue_swap_flow(&mut self, trading_pair: &TradingPair, swap_flow: SwapFlow) {
         // Accumulate the new swap flow into the map.
         let old = self.swap_flows();
         let new = old.alter(
@@ -92,10 +194,115 @@ pub(crate) trait SwapDataWrite: StateWrite {
             *trading_pair,
         );
         self.object_put(state_key::swap_flows(), new);
+    }
+
+    /// Accounts for a user swap queued into the current block's batches, so that its
+    /// inputs count as unclaimed once its batch executes.
+    fn queue_claimable_swap(&mut self, trading_pair: &TradingPair, swap_flow: SwapFlow) {
+        let swaps = self.claimable_swaps().alter(
+            |maybe_swaps| match maybe_swaps {
+                Some((flow, count)) => Some((
+                    (flow.0 + swap_flow.0, flow.1 + swap_flow.1).into(),
+                    count + 1,
+                )),
+                None => Some((swap_flow, 1)),
+            },
+            *trading_pair,
+        );
+        self.object_put(state_key::claimable_swaps(), swaps);
+    }
+
+    /// Records the unclaimed swaps of the batch described by `output_data`, which
+    /// just executed.
+    fn record_unclaimed_swaps(&mut self, output_data: &BatchSwapOutputData) {
+        let pair = output_data.trading_pair;
+        if let Some((flow, count)) = self.claimable_swaps().get(&pair) {
+            self.put_unclaimed_swaps(&pair, output_data.height, (flow.0, flow.1), *count);
+        }
+    }
+
+    /// Records that a swap claim with `proof` was accepted at `height`.
+    ///
+    /// The record is kept for [`SWAP_CLAIM_PROOF_RETENTION_BLOCKS`] blocks.
+    fn record_swap_claim_proof(&mut self, proof: &SwapClaimProof, height: u64) {
+        let proof_hash = proof.hash();
+        self.nonverifiable_put_raw(
+            state_key::swap_claim_proof(&proof_hash).into(),
+            height.to_be_bytes().to_vec(),
+        );
+        self.nonverifiable_put_raw(
+            state_key::swap_claim_proof_at_height(height, &proof_hash).into(),
+            vec![],
+        );
+    }
+
+    /// Deletes the records of the swap claim proofs accepted at the height that falls
+    /// out of the retention window at `height`.
+    async fn prune_swap_claim_proofs(&mut self, height: u64) -> Result<()> {
+        let Some(expired_height) = height.checked_sub(SWAP_CLAIM_PROOF_RETENTION_BLOCKS) else {
+            return Ok(());
+        };
+        let prefix = state_key::swap_claim_proofs_at_height(expired_height);
+        let keys: Vec<Vec<u8>> = self
+            .nonverifiable_prefix_raw(prefix.as_bytes())
+            .map_ok(|(key, _)| key)
+            .try_collect()
+            .await?;
+        for key in keys {
+            let proof_hash: [u8; 32] = hex::decode(&key[prefix.len()..])?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("malformed swap claim proof index"))?;
+            self.nonverifiable_delete(state_key::swap_claim_proof(&proof_hash).into());
+            self.nonverifiable_delete(key);
+        }
+        Ok(())
+    }
+
+    /// Records the claim of a swap from the batch described by `output_data`.
+    ///
+    /// Claims don't reveal which swap they claim, so a batch's inputs are counted
+    /// as unclaimed until all of its swaps have been claimed. If the batch's output
+    /// data was kept past its retention window for the last of them, it is pruned.
+    async fn record_swap_claim(&mut self, output_data: &BatchSwapOutputData) -> Result<()> {
+        let (pair, height) = (output_data.trading_pair, output_data.height);
+        let Some((inputs, count)) = self.unclaimed_swaps_at(&pair, height).await? else {
+            return Ok(());
+        };
+        if count > 1 {
+            self.put_unclaimed_swaps(&pair, height, inputs, count - 1);
+            return Ok(());
+        }
 
+        self.delete(state_key::unclaimed_swaps::count(&pair, height));
+        self.delete(state_key::unclaimed_swaps::inputs(&pair, height));
+        let current_height = self.get_block_height().await?;
+        if self
+            .get_dex_params()
+            .await?
+            .batch_output_expired_at(height, current_height)
+        {
+            self.delete_output_data(height, pair);
+        }
         Ok(())
     }
 
+    fn put_unclaimed_swaps(
+        &mut self,
+        pair: &TradingPair,
+        height: u64,
+        inputs: (Amount, Amount),
+        count: u64,
+    ) {
+        self.put_proto(state_key::unclaimed_swaps::count(pair, height), count);
+        self.put(
+            state_key::unclaimed_swaps::inputs(pair, height),
+            Reserves {
+                r1: inputs.0,
+                r2: inputs.1,
+            },
+        );
+    }
+
     fn put_swap_execution_at_height(
         &mut self,
         height: u64,
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use anyhow::Ok;
@@ -30,6 +31,7 @@ pub trait TempStorageExt: Sized {
 #[async_trait]
 impl TempStorageExt for TempStorage {
     async fn apply_minimal_genesis(self) -> anyhow::Result<Self> {
+        use penumbra_fee::component::StateWriteExt as _;
         use penumbra_sct::component::clock::EpochManager as _;
         let mut state = StateDelta::new(self.latest_snapshot());
 
@@ -42,6 +44,7 @@ impl TempStorageExt for TempStorage {
             },
         );
         state.put_dex_params(DexParameters::default());
+        state.put_fee_params(penumbra_fee::FeeParameters::default());
 
         self.commit(state).await?;
 
@@ -516,6 +519,7 @@ async fn empty_order_fails() -> anyhow::Result<()> {
 
     let position_action = PositionOpen {
         position: position_1,
+        open_fee: Amount::zero(),
     };
 
     assert!(position_action.check_stateless(()).await.is_err());
@@ -845,6 +849,7 @@ async fn basic_cycle_arb() -> anyhow::Result<()> {
         max_hops: 4 + 2,
         price_limit: Some(1u64.into()),
         fixed_candidates: Arc::new(vec![penumbra.id(), gm.id(), gn.id()]),
+        prefer_via: None,
     };
     state.arbitrage(penumbra.id(), routing_params).await?;
 
@@ -944,6 +949,7 @@ async fn reproduce_arbitrage_loop_testnet_53() -> anyhow::Result<()> {
         max_hops: 4 + 2,
         price_limit: Some(1u64.into()),
         fixed_candidates: Arc::new(vec![penumbra.id(), test_usd.id()]),
+        prefer_via: None,
     };
 
     let arb_profit = tokio::time::timeout(
@@ -1144,3 +1150,1659 @@ async fn check_routable_asset_ordering() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+/// A position's lifespan records when it was opened, and stops aging once it is closed.
+async fn position_lifespan_tracks_open_and_close_heights() -> anyhow::Result<()> {
+    use penumbra_sct::component::clock::EpochManager as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let position = SellOrder::parse_str("100gm@1gn")?.into_position(OsRng);
+    let id = position.id();
+    assert_eq!(state.position_lifespan(&id).await?, None);
+
+    state.put_block_height(10);
+    state.open_position(position).await?;
+
+    let lifespan = state.position_lifespan(&id).await?.unwrap();
+    assert_eq!(lifespan.open_height(), 10);
+    assert_eq!(lifespan.close_height(), None);
+    assert_eq!(lifespan.age(10), 0);
+    assert_eq!(lifespan.age(17), 7);
+
+    state.put_block_height(25);
+    state.close_position_by_id(&id).await?;
+
+    let lifespan = state.position_lifespan(&id).await?.unwrap();
+    assert_eq!(lifespan.open_height(), 10);
+    assert_eq!(lifespan.close_height(), Some(25));
+    // The position was active for 15 blocks, however long after closing it is queried.
+    assert_eq!(lifespan.age(25), 15);
+    assert_eq!(lifespan.age(1_000), 15);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// A position's timeline fills in each stage of its lifecycle as the position reaches it.
+async fn position_timeline_tracks_lifecycle_stages() -> anyhow::Result<()> {
+    use crate::lp::position::PositionTimeline;
+    use penumbra_sct::component::clock::EpochManager as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let position = SellOrder::parse_str("100gm@1gn")?.into_position(OsRng);
+    let id = position.id();
+    assert!(state.position_timeline(&id).await.is_err());
+
+    state.put_block_height(10);
+    state.open_position(position).await?;
+    assert_eq!(
+        state.position_timeline(&id).await?,
+        PositionTimeline {
+            open_height: 10,
+            close_height: None,
+            withdraw_height: None,
+        }
+    );
+
+    state.put_block_height(25);
+    state.close_position_by_id(&id).await?;
+    state.put_block_height(30);
+    state.withdraw_position(id, 0).await?;
+    // Later withdrawals, once the position is empty, don't move the withdrawal height.
+    state.put_block_height(40);
+    state.withdraw_position(id, 1).await?;
+    assert_eq!(
+        state.position_timeline(&id).await?,
+        PositionTimeline {
+            open_height: 10,
+            close_height: Some(25),
+            withdraw_height: Some(30),
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+/// The turnover recorded for a position is deleted once the position is withdrawn.
+async fn position_turnover_is_deleted_on_withdrawal() -> anyhow::Result<()> {
+    use crate::component::{
+        PositionUtilizationRead as _, PositionUtilizationTracker as _, VOLUME_BUCKETS,
+    };
+    use penumbra_sct::component::clock::EpochManager as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let position = SellOrder::parse_str("100gm@1gn")?.into_position(OsRng);
+    let id = position.id();
+    state.put_block_height(10);
+    state.open_position(position.clone()).await?;
+
+    // The position's reserves are traded out in full.
+    let mut filled = position.clone();
+    filled.reserves = Reserves {
+        r1: position.reserves.r2,
+        r2: position.reserves.r1,
+    };
+    state.record_position_turnover(&position, &filled).await?;
+    assert!(state.position_utilization(&id, 1).await? > 0.0);
+
+    state.close_position_by_id(&id).await?;
+    state.withdraw_position(id, 0).await?;
+
+    assert_eq!(state.position_utilization(&id, 1).await?, 0.0);
+    for bucket in 0..VOLUME_BUCKETS {
+        assert_eq!(state.turnover_bucket(&id, bucket).await?, None);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+/// An LPNFT resolves to its position, which reflects that the NFT is stale once withdrawn.
+async fn position_by_nft_tracks_state_transitions() -> anyhow::Result<()> {
+    use crate::lp::LpNft;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let position = SellOrder::parse_str("100gm@1gn")?.into_position(OsRng);
+    let id = position.id();
+    let opened_nft = LpNft::new(id, position::State::Opened).asset_id();
+    assert_eq!(state.position_by_nft(&opened_nft).await?, None);
+
+    state.open_position(position).await?;
+    assert_eq!(
+        state.position_by_nft(&opened_nft).await?,
+        Some((id, position::State::Opened))
+    );
+
+    state.close_position_by_id(&id).await?;
+    state.withdraw_position(id, 0).await?;
+
+    // The opened position's NFT still resolves, but to a withdrawn position ...
+    assert_eq!(
+        state.position_by_nft(&opened_nft).await?,
+        Some((id, position::State::Withdrawn { sequence: 0 }))
+    );
+    // ... while the NFT minted by the withdrawal is the live one.
+    let withdrawn_nft = LpNft::new(id, position::State::Withdrawn { sequence: 0 }).asset_id();
+    assert_eq!(
+        state.position_by_nft(&withdrawn_nft).await?,
+        Some((id, position::State::Withdrawn { sequence: 0 }))
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+/// The price index returns positions cheapest-first, and tracks their reserves
+/// as positions are partially and then fully filled.
+async fn price_index_stays_ordered_across_fills() -> anyhow::Result<()> {
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    // Buying gn with gm.
+    let pair = DirectedTradingPair::new(gm.id(), gn.id());
+
+    let mut positions = Vec::new();
+    for order in [
+        "10gn@1.5gm",
+        "10gn@3gm",
+        "10gn@1gm",
+        "10gn@2gm",
+        "10gn@2.5gm",
+    ] {
+        let position = SellOrder::parse_str(order)?.into_position(OsRng);
+        state.open_position(position.clone()).await?;
+        positions.push(position);
+    }
+    let by_price = |indices: &[usize]| -> Vec<position::Id> {
+        indices.iter().map(|&i| positions[i].id()).collect()
+    };
+
+    async fn indexed_ids(
+        state: &impl cnidarium::StateRead,
+        pair: &DirectedTradingPair,
+    ) -> anyhow::Result<Vec<position::Id>> {
+        use futures::TryStreamExt as _;
+        state
+            .positions_by_price(pair)
+            .map_ok(|(id, _)| id)
+            .try_collect()
+            .await
+    }
+
+    assert_eq!(
+        indexed_ids(&state, &pair).await?,
+        by_price(&[2, 0, 3, 4, 1])
+    );
+
+    // Partially fill the cheapest position: it stays first, with updated reserves.
+    let cheapest = positions[2].clone();
+    let (unfilled, reserves, _) = cheapest
+        .phi
+        .fill(gm.value(4u64.into()), &cheapest.reserves)?;
+    assert_eq!(unfilled.amount, 0u64.into());
+    state
+        .position_execution(
+            Position {
+                reserves,
+                ..cheapest.clone()
+            },
+            pair,
+        )
+        .await?;
+    assert_eq!(
+        indexed_ids(&state, &pair).await?,
+        by_price(&[2, 0, 3, 4, 1])
+    );
+    let (_, best) = state.best_position(&pair).await?.unwrap();
+    assert_eq!(best.reserves, reserves);
+
+    // Exhaust the cheapest position: it no longer has any gn to sell.
+    let partially_filled = state.position_by_id(&cheapest.id()).await?.unwrap();
+    let (_, reserves, _) = partially_filled
+        .phi
+        .fill(gm.value(100u64.into()), &partially_filled.reserves)?;
+    let exhausted = Position {
+        reserves,
+        ..partially_filled
+    };
+    assert_eq!(exhausted.reserves_for(gn.id()), Some(0u64.into()));
+    state.position_execution(exhausted, pair).await?;
+    assert_eq!(indexed_ids(&state, &pair).await?, by_price(&[0, 3, 4, 1]));
+
+    Ok(())
+}
+
+/// A tracing layer recording the name and fields of every span, once closed.
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    open: Arc<std::sync::Mutex<BTreeMap<u64, RecordedSpan>>>,
+    closed: Arc<std::sync::Mutex<Vec<RecordedSpan>>>,
+}
+
+#[derive(Debug, Default)]
+struct RecordedSpan {
+    name: &'static str,
+    fields: BTreeMap<&'static str, String>,
+}
+
+impl tracing::field::Visit for RecordedSpan {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.fields.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanRecorder {
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        _: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let mut span = RecordedSpan {
+            name: attrs.metadata().name(),
+            ..Default::default()
+        };
+        attrs.record(&mut span);
+        self.open.lock().unwrap().insert(id.into_u64(), span);
+    }
+
+    fn on_record(
+        &self,
+        id: &tracing::span::Id,
+        values: &tracing::span::Record<'_>,
+        _: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if let Some(span) = self.open.lock().unwrap().get_mut(&id.into_u64()) {
+            values.record(span);
+        }
+    }
+
+    fn on_close(&self, id: tracing::span::Id, _: tracing_subscriber::layer::Context<'_, S>) {
+        if let Some(span) = self.open.lock().unwrap().remove(&id.into_u64()) {
+            self.closed.lock().unwrap().push(span);
+        }
+    }
+}
+
+#[tokio::test]
+/// Test that batch execution emits spans attributing time to the pair and positions it touched.
+async fn batch_execution_emits_tracing_spans() -> anyhow::Result<()> {
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    let recorder = SpanRecorder::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = Arc::new(StateDelta::new(storage.latest_snapshot()));
+    let mut state_tx = state.try_begin_transaction().unwrap();
+
+    let penumbra = asset::Cache::with_known_assets()
+        .get_unit("penumbra")
+        .unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let pair_gn_penumbra = DirectedUnitPair::new(gn.clone(), penumbra.clone());
+
+    // Two positions selling penumbra for gn, both of which are needed to fill the swap.
+    for _ in 0..2 {
+        let buy = create_buy(pair_gn_penumbra.clone(), 1u64.into(), 1u64.into());
+        state_tx.open_position(buy).await.unwrap();
+    }
+    state_tx.apply();
+
+    let trading_pair = pair_gn_penumbra.into_directed_trading_pair().into();
+    let swap_flow = if trading_pair.asset_1() == gn.id() {
+        (gn.value(2u32.into()).amount, 0u32.into())
+    } else {
+        (0u32.into(), gn.value(2u32.into()).amount)
+    };
+    let swap_flow: crate::component::flow::SwapFlow = swap_flow.into();
+    Arc::get_mut(&mut state)
+        .unwrap()
+        .accumulate_swap_flow(&trading_pair, swap_flow.clone())
+        .await
+        .unwrap();
+    let routing_params = state.routing_params().await.unwrap();
+    state
+        .handle_batch_swaps(trading_pair, swap_flow, 0, routing_params, 64)
+        .await
+        .expect("unable to process batch swaps");
+
+    let spans = recorder.closed.lock().unwrap();
+    let span = |name: &str| {
+        spans
+            .iter()
+            .find(|span| span.name == name)
+            .unwrap_or_else(|| panic!("expected a {name} span, got: {spans:?}"))
+    };
+
+    let batch_swap = span("batch_swap");
+    assert_eq!(batch_swap.fields["trading_pair"], trading_pair.to_string());
+
+    // Filling the swap takes both positions, whichever routes they were filled along.
+    let fill_routes: Vec<_> = spans
+        .iter()
+        .filter(|span| span.name == "fill_route")
+        .collect();
+    assert!(!fill_routes.is_empty(), "expected a fill_route span");
+    let mut positions = 0;
+    for fill_route in fill_routes {
+        assert_eq!(fill_route.fields["start"], gn.id().to_string());
+        positions += fill_route.fields["positions"].parse::<usize>()?;
+    }
+    assert!(positions >= 2, "expected both positions to be touched");
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Batch output data is pruned once it falls out of the retention window, but
+/// not while swaps from that batch are still claimable.
+async fn batch_output_data_is_pruned_after_retention_window() -> anyhow::Result<()> {
+    use crate::component::InternalDexWrite as _;
+    use crate::{state_key, TradingPair, MIN_BATCH_OUTPUT_RETENTION_BLOCKS};
+    use penumbra_proto::StateWriteProto as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_dex_params(DexParameters {
+        batch_output_retention_blocks: MIN_BATCH_OUTPUT_RETENTION_BLOCKS,
+        ..Default::default()
+    });
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let trading_pair = TradingPair::new(gm.id(), gn.id());
+    let output_data = |height| BatchSwapOutputData {
+        delta_1: 1u64.into(),
+        delta_2: 0u64.into(),
+        lambda_1: 0u64.into(),
+        lambda_2: 1u64.into(),
+        unfilled_1: 0u64.into(),
+        unfilled_2: 0u64.into(),
+        height,
+        trading_pair,
+        sct_position_prefix: Default::default(),
+    };
+    for height in [1, 2] {
+        state.put(
+            state_key::output_data(height, trading_pair),
+            output_data(height),
+        );
+    }
+
+    // At the end of the window of the first batch, its output data is pruned, while the
+    // second batch's swaps can still be claimed.
+    state
+        .prune_output_data(1 + MIN_BATCH_OUTPUT_RETENTION_BLOCKS)
+        .await?;
+    assert_eq!(state.output_data(1, trading_pair).await?, None);
+    assert_eq!(
+        state.output_data(2, trading_pair).await?,
+        Some(output_data(2))
+    );
+
+    // A shorter window than the minimum does not prune claimable output data.
+    state.put_dex_params(DexParameters {
+        batch_output_retention_blocks: 1,
+        ..Default::default()
+    });
+    state.prune_output_data(3).await?;
+    assert_eq!(
+        state.output_data(2, trading_pair).await?,
+        Some(output_data(2))
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+/// The output data of a batch with unclaimed swaps outlives the retention window, and
+/// is pruned once the last of its swaps is claimed.
+async fn batch_output_data_with_unclaimed_swaps_is_kept() -> anyhow::Result<()> {
+    use crate::{state_key, TradingPair, MIN_BATCH_OUTPUT_RETENTION_BLOCKS};
+    use penumbra_proto::StateWriteProto as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_dex_params(DexParameters {
+        batch_output_retention_blocks: MIN_BATCH_OUTPUT_RETENTION_BLOCKS,
+        ..Default::default()
+    });
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let trading_pair = TradingPair::new(gm.id(), gn.id());
+    let output_data = BatchSwapOutputData {
+        delta_1: 30u64.into(),
+        delta_2: 0u64.into(),
+        lambda_1: 0u64.into(),
+        lambda_2: 30u64.into(),
+        unfilled_1: 0u64.into(),
+        unfilled_2: 0u64.into(),
+        height: 1,
+        trading_pair,
+        sct_position_prefix: Default::default(),
+    };
+    state.put(state_key::output_data(1, trading_pair), output_data.clone());
+    state.put_unclaimed_swaps(&trading_pair, 1, (30u64.into(), 0u64.into()), 2);
+
+    // Past the window, the batch still has two unclaimed swaps.
+    let expired = 1 + MIN_BATCH_OUTPUT_RETENTION_BLOCKS;
+    state.put_block_height(expired);
+    state.prune_output_data(expired).await?;
+    assert_eq!(
+        state.output_data(1, trading_pair).await?,
+        Some(output_data.clone())
+    );
+
+    // The first claim keeps it for the second one, which prunes it.
+    state.record_swap_claim(&output_data).await?;
+    assert_eq!(
+        state.output_data(1, trading_pair).await?,
+        Some(output_data.clone())
+    );
+    state.record_swap_claim(&output_data).await?;
+    assert_eq!(state.output_data(1, trading_pair).await?, None);
+    assert_eq!(state.unclaimed_swaps_at(&trading_pair, 1).await?, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Batch output data is compressed once it falls out of the window kept in full, and
+/// reads back exactly as it was stored.
+async fn batch_output_data_is_compressed_after_window() -> anyhow::Result<()> {
+    use crate::component::InternalDexWrite as _;
+    use crate::{state_key, TradingPair, MIN_BATCH_OUTPUT_COMPRESSION_BLOCKS};
+    use cnidarium::StateRead as _;
+    use penumbra_proto::{DomainType as _, StateWriteProto as _};
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_dex_params(DexParameters {
+        batch_output_compression_blocks: MIN_BATCH_OUTPUT_COMPRESSION_BLOCKS,
+        ..Default::default()
+    });
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let penumbra = asset::Cache::with_known_assets()
+        .get_unit("penumbra")
+        .unwrap();
+    let pairs = [
+        TradingPair::new(gm.id(), gn.id()),
+        TradingPair::new(gm.id(), penumbra.id()),
+        TradingPair::new(gn.id(), penumbra.id()),
+    ];
+    let output_data = |height: u64, trading_pair| BatchSwapOutputData {
+        delta_1: (u128::from(height) * 1_000_003).into(),
+        delta_2: (u128::MAX - u128::from(height)).into(),
+        lambda_1: height.into(),
+        lambda_2: (u128::from(height) << 64).into(),
+        unfilled_1: 0u64.into(),
+        unfilled_2: (height % 7).into(),
+        height,
+        trading_pair,
+        sct_position_prefix: (height as u16, 1, 0).into(),
+    };
+    let heights = 1..=50u64;
+    for height in heights.clone() {
+        for trading_pair in pairs {
+            state.put(
+                state_key::output_data(height, trading_pair),
+                output_data(height, trading_pair),
+            );
+        }
+    }
+
+    // Each block compresses the batches that fell out of the window at its height.
+    for height in heights.clone() {
+        state
+            .compress_output_data(height + MIN_BATCH_OUTPUT_COMPRESSION_BLOCKS)
+            .await?;
+    }
+
+    for height in heights {
+        for trading_pair in pairs {
+            assert_eq!(
+                state
+                    .get_raw(&state_key::output_data(height, trading_pair))
+                    .await?,
+                None,
+                "the full output data should be replaced"
+            );
+            let stored = output_data(height, trading_pair);
+            let recovered = state.output_data(height, trading_pair).await?.unwrap();
+            assert_eq!(recovered, stored);
+            assert_eq!(recovered.encode_to_vec(), stored.encode_to_vec());
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Filling a position with `lp::fill` agrees exactly with the fills the chain makes.
+async fn lp_fill_matches_routed_fills() -> anyhow::Result<()> {
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    // An awkward price and a fee, so that the fills have to round.
+    let position = SellOrder::parse_str("100gn@1.7gm/33bps")?.into_position(OsRng);
+    let id = position.id();
+    state.open_position(position).await?;
+
+    for amount in [1u64, 7, 999_999, 1_234_567, 3_000_001] {
+        let input = Value {
+            amount: amount.into(),
+            asset_id: gm.id(),
+        };
+        let before = state.position_by_id(&id).await?.unwrap();
+        let (output, expected) = crate::lp::fill(&before, input)?;
+
+        let execution = state.fill_route(input, &[gn.id()], None).await?;
+        assert_eq!(execution.input, input, "the fill should be unconstrained");
+        assert_eq!(execution.output, output);
+        assert_eq!(state.position_by_id(&id).await?.unwrap(), expected);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Reopening a position reports the collision with the existing one, while an identical
+/// position with a fresh nonce opens alongside it.
+async fn identical_positions_need_distinct_nonces() -> anyhow::Result<()> {
+    use crate::lp::position::PositionIdCollision;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let order = SellOrder::parse_str("100gm@1gn/10bps")?;
+    let position = order.into_position(OsRng);
+    state.open_position(position.clone()).await?;
+
+    let err = state
+        .open_position(position.clone())
+        .await
+        .expect_err("reopening a position should fail");
+    assert_eq!(
+        err.downcast_ref::<PositionIdCollision>(),
+        Some(&PositionIdCollision {
+            id: position.id(),
+            state: position::State::Opened,
+        })
+    );
+
+    let twin = order.into_position(OsRng);
+    assert_eq!(twin.phi, position.phi);
+    assert_eq!(twin.reserves, position.reserves);
+    assert_ne!(twin.id(), position.id());
+    state.open_position(twin.clone()).await?;
+    assert!(state.position_by_id(&position.id()).await?.is_some());
+    assert!(state.position_by_id(&twin.id()).await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Positions opened below the fee floor of their pair are rejected, while pairs
+/// without a floor of their own fall back to the global minimum fee.
+async fn position_open_enforces_pair_fee_floors() -> anyhow::Result<()> {
+    use crate::{PairFeeFloor, TradingPair};
+    use cnidarium_component::ActionHandler as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let penumbra = asset::Cache::with_known_assets()
+        .get_unit("penumbra")
+        .unwrap();
+    state.put_dex_params(DexParameters {
+        min_fee_bps: 10,
+        pair_fee_floors: vec![PairFeeFloor {
+            pair: TradingPair::new(gm.id(), gn.id()),
+            min_fee_bps: 100,
+        }],
+        ..Default::default()
+    });
+    let open = |order: &str| -> anyhow::Result<PositionOpen> {
+        Ok(PositionOpen {
+            position: SellOrder::parse_str(order)?.into_position(OsRng),
+            open_fee: Amount::zero(),
+        })
+    };
+
+    // The floored pair rejects fees below its own floor, even above the global minimum.
+    let err = open("10gn@1gm/50bps")?
+        .check_and_execute(&mut state)
+        .await
+        .expect_err("a position below the pair's fee floor should be rejected");
+    assert!(err.to_string().contains("below the minimum of 100bps"));
+    open("10gn@1gm/100bps")?
+        .check_and_execute(&mut state)
+        .await?;
+
+    // The unfloored pair only has to meet the global minimum.
+    open("10penumbra@1gm/50bps")?
+        .check_and_execute(&mut state)
+        .await?;
+    let err = open("10penumbra@1gm/5bps")?
+        .check_and_execute(&mut state)
+        .await
+        .expect_err("a position below the global minimum fee should be rejected");
+    assert!(err.to_string().contains("below the minimum of 10bps"));
+    assert_eq!(
+        state
+            .get_dex_params()
+            .await?
+            .min_fee_bps_for(&TradingPair::new(penumbra.id(), gm.id())),
+        10
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Swaps above the swap size limit of their pair are rejected, while swaps at the
+/// limit, and swaps on pairs without a limit, are accepted.
+async fn swap_enforces_pair_swap_limits() -> anyhow::Result<()> {
+    use crate::{
+        swap::{SwapPlaintext, SwapPlan},
+        PairSwapLimit, SwapTooLarge, TradingPair,
+    };
+    use cnidarium_component::ActionHandler as _;
+    use penumbra_fee::Fee;
+    use penumbra_keys::test_keys;
+    use penumbra_sct::component::source::SourceContext as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_mock_source(1u8);
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let penumbra = asset::Cache::with_known_assets()
+        .get_unit("penumbra")
+        .unwrap();
+    let limited_pair = TradingPair::new(gm.id(), gn.id());
+    state.put_dex_params(DexParameters {
+        pair_swap_limits: vec![PairSwapLimit {
+            pair: limited_pair,
+            max_delta_1: 1_000u64.into(),
+            max_delta_2: 0u64.into(),
+        }],
+        ..Default::default()
+    });
+    let swap = |pair: TradingPair, delta_1: u64, delta_2: u64| {
+        let swap_plaintext = SwapPlaintext::new(
+            &mut OsRng,
+            pair,
+            delta_1.into(),
+            delta_2.into(),
+            Fee::default(),
+            *test_keys::ADDRESS_0,
+        );
+        SwapPlan::new(&mut OsRng, swap_plaintext).swap(&test_keys::FULL_VIEWING_KEY)
+    };
+
+    // A swap just under the limit, or at it, is accepted.
+    swap(limited_pair, 999, 0)
+        .check_and_execute(&mut state)
+        .await?;
+    swap(limited_pair, 1_000, 0)
+        .check_and_execute(&mut state)
+        .await?;
+
+    // A swap just over the limit is rejected with the limit it exceeds.
+    let err = swap(limited_pair, 1_001, 0)
+        .check_and_execute(&mut state)
+        .await
+        .expect_err("a swap over the pair's swap size limit should be rejected");
+    assert_eq!(
+        err.downcast_ref::<SwapTooLarge>(),
+        Some(&SwapTooLarge {
+            asset_id: limited_pair.asset_1(),
+            input: 1_001u64.into(),
+            max: 1_000u64.into(),
+        })
+    );
+
+    // A zero maximum leaves that side of the pair uncapped, as does having no limit at all.
+    swap(limited_pair, 0, 1_000_000)
+        .check_and_execute(&mut state)
+        .await?;
+    swap(TradingPair::new(penumbra.id(), gm.id()), 1_000_000, 0)
+        .check_and_execute(&mut state)
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+/// DEX events are indexed in the order they are recorded across the transactions of
+/// a block, and re-executing the block yields the same indices.
+async fn dex_event_indices_are_stable_across_reexecution() -> anyhow::Result<()> {
+    use crate::event::EventCursor;
+
+    /// Opens `positions` in one transaction, then closes the first in another,
+    /// returning the cursors of the events of both transactions.
+    async fn execute_block(
+        storage: &TempStorage,
+        positions: &[Position],
+    ) -> anyhow::Result<Vec<EventCursor>> {
+        let mut block = StateDelta::new(storage.latest_snapshot());
+        let mut events = Vec::new();
+
+        let mut tx = StateDelta::new(&mut block);
+        for position in positions {
+            tx.open_position(position.clone()).await?;
+        }
+        events.extend(tx.apply().1);
+
+        let mut tx = StateDelta::new(&mut block);
+        tx.close_position_by_id(&positions[0].id()).await?;
+        events.extend(tx.apply().1);
+
+        Ok(events
+            .iter()
+            .filter_map(|event| EventCursor::from_event(1, event))
+            .collect())
+    }
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let positions = ["10gn@1gm", "20gn@1gm", "10penumbra@1gm"]
+        .into_iter()
+        .map(|order| Ok(SellOrder::parse_str(order)?.into_position(OsRng)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let first = execute_block(&storage, &positions).await?;
+    let second = execute_block(&storage, &positions).await?;
+
+    // Every event is indexed, and the indices continue across transactions.
+    assert!(first.len() > positions.len());
+    assert_eq!(
+        first.iter().map(|cursor| cursor.index).collect::<Vec<_>>(),
+        (0..first.len() as u64).collect::<Vec<_>>()
+    );
+    assert_eq!(first, second);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Sweeping closes the positions with total reserves strictly below the threshold,
+/// and leaves the swept positions withdrawable.
+async fn sweep_dust_positions_closes_only_dust() -> anyhow::Result<()> {
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let pair = DirectedTradingPair::new(gm.id(), gn.id());
+    let with_reserves = |r1: u64, r2: u64| {
+        Position::new(
+            OsRng,
+            pair,
+            0u32,
+            1u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: r1.into(),
+                r2: r2.into(),
+            },
+        )
+    };
+    let dust = with_reserves(60, 39);
+    let at_threshold = with_reserves(60, 40);
+    let normal = with_reserves(1_000, 0);
+    for position in [&dust, &at_threshold, &normal] {
+        state.open_position(position.clone()).await?;
+    }
+
+    assert_eq!(state.sweep_dust_positions(100u64.into()).await?, 1);
+
+    for (position, expected) in [
+        (&dust, position::State::Closed),
+        (&at_threshold, position::State::Opened),
+        (&normal, position::State::Opened),
+    ] {
+        let actual = state
+            .position_by_id(&position.id())
+            .await?
+            .expect("position exists")
+            .state;
+        assert_eq!(actual, expected);
+    }
+
+    // The owner of the swept position can still withdraw its reserves.
+    let reserves = state.withdraw_position(dust.id(), 0).await?;
+    assert_eq!(reserves, dust.reserves.balance(&dust.phi.pair));
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Querying a price band returns the open positions priced within it, edges included.
+async fn positions_in_band_filters_by_price() -> anyhow::Result<()> {
+    use crate::TradingPair;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let pair = TradingPair::new(gm.id(), gn.id());
+    let directed = DirectedTradingPair::new(pair.asset_1(), pair.asset_2());
+    let priced_at = |price: u64| {
+        Position::new(
+            OsRng,
+            directed,
+            0u32,
+            1u64.into(),
+            price.into(),
+            Reserves {
+                r1: 1_000u64.into(),
+                r2: 1_000u64.into(),
+            },
+        )
+    };
+    let positions: Vec<Position> = [1u64, 2, 3, 4, 5].into_iter().map(priced_at).collect();
+    for position in &positions {
+        state.open_position(position.clone()).await?;
+    }
+    // A closed position within the band is not returned.
+    let closed = priced_at(3);
+    state.open_position(closed.clone()).await?;
+    state.close_position_by_id(&closed.id()).await?;
+
+    let mut in_band: Vec<position::Id> = state
+        .positions_in_band(pair, 2u64.into(), 4u64.into())
+        .await?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    in_band.sort();
+    let mut expected: Vec<position::Id> = positions[1..4].iter().map(Position::id).collect();
+    expected.sort();
+    assert_eq!(in_band, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// With a batch interval of three blocks, swaps submitted in the first two blocks
+/// are deferred, and execute in a single batch with the swaps of the third block.
+async fn swaps_accumulate_across_batch_interval() -> anyhow::Result<()> {
+    use crate::{
+        component::Dex,
+        swap::{SwapPlaintext, SwapPlan},
+        TradingPair,
+    };
+    use cnidarium_component::{ActionHandler as _, Component as _};
+    use penumbra_fee::Fee;
+    use penumbra_keys::test_keys;
+    use penumbra_sct::component::{clock::EpochManager as _, source::SourceContext as _};
+    use tendermint::v0_37::abci::request::EndBlock;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let pair = TradingPair::new(gm.id(), gn.id());
+
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_dex_params(DexParameters {
+        batch_interval_blocks: 3,
+        ..Default::default()
+    });
+    storage.commit(state).await?;
+
+    for height in 1..=3u64 {
+        let mut state = StateDelta::new(storage.latest_snapshot());
+        state.put_block_height(height);
+        state.put_mock_source(height as u8);
+
+        let swap_plaintext = SwapPlaintext::new(
+            &mut OsRng,
+            pair,
+            100u64.into(),
+            0u64.into(),
+            Fee::default(),
+            *test_keys::ADDRESS_0,
+        );
+        SwapPlan::new(&mut OsRng, swap_plaintext)
+            .swap(&test_keys::FULL_VIEWING_KEY)
+            .check_and_execute(&mut state)
+            .await?;
+
+        let mut state = Arc::new(state);
+        Dex::end_block(
+            &mut state,
+            &EndBlock {
+                height: height as i64,
+            },
+        )
+        .await;
+        let state = Arc::try_unwrap(state).expect("state should be uniquely referenced");
+        // The commitments of all three swaps are recorded in the block they execute in.
+        let recorded_swaps = if height == 3 { 3 } else { 0 };
+        assert_eq!(state.pending_swap_payloads().len(), recorded_swaps);
+        storage.commit(state).await?;
+    }
+
+    let snapshot = storage.latest_snapshot();
+    assert!(snapshot.output_data(1, pair).await?.is_none());
+    assert!(snapshot.output_data(2, pair).await?.is_none());
+    let output_data = snapshot
+        .output_data(3, pair)
+        .await?
+        .expect("the deferred swaps execute at the interval boundary");
+    assert_eq!(output_data.delta_1, 300u64.into());
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Only the swaps of executed batches count as unclaimed, and a batch's whole inputs
+/// count until all of its swaps have been claimed.
+async fn unclaimed_swap_value_excludes_pending_and_claimed_swaps() -> anyhow::Result<()> {
+    use crate::{
+        component::{Dex, SwapDataWrite as _},
+        swap::{SwapPlaintext, SwapPlan},
+        TradingPair,
+    };
+    use cnidarium_component::{ActionHandler as _, Component as _};
+    use penumbra_fee::Fee;
+    use penumbra_keys::test_keys;
+    use penumbra_sct::component::{clock::EpochManager as _, source::SourceContext as _};
+    use tendermint::v0_37::abci::request::EndBlock;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let pair = TradingPair::new(gm.id(), gn.id());
+
+    // One swap executes at height 1, two at height 2, and one is still pending at height 3.
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    for (height, inputs) in [(1u64, vec![100u64]), (2, vec![20, 30]), (3, vec![7])] {
+        state.put_block_height(height);
+        state.put_mock_source(height as u8);
+        for input in inputs {
+            let swap_plaintext = SwapPlaintext::new(
+                &mut OsRng,
+                pair,
+                input.into(),
+                0u64.into(),
+                Fee::default(),
+                *test_keys::ADDRESS_0,
+            );
+            SwapPlan::new(&mut OsRng, swap_plaintext)
+                .swap(&test_keys::FULL_VIEWING_KEY)
+                .check_and_execute(&mut state)
+                .await?;
+        }
+        if height == 3 {
+            break;
+        }
+
+        let mut arc_state = Arc::new(state);
+        Dex::end_block(
+            &mut arc_state,
+            &EndBlock {
+                height: height as i64,
+            },
+        )
+        .await;
+        state = Arc::try_unwrap(arc_state).expect("state should be uniquely referenced");
+        storage.commit(state).await?;
+        state = StateDelta::new(storage.latest_snapshot());
+    }
+
+    assert_eq!(
+        state.unclaimed_swap_value(pair).await?,
+        (150u64.into(), 0u64.into())
+    );
+
+    // Claiming the only swap of the first batch removes its inputs.
+    let first_batch = state.output_data(1, pair).await?.expect("batch executed");
+    state.record_swap_claim(&first_batch).await?;
+    assert_eq!(
+        state.unclaimed_swap_value(pair).await?,
+        (50u64.into(), 0u64.into())
+    );
+
+    // The second batch counts until both of its swaps have been claimed.
+    let second_batch = state.output_data(2, pair).await?.expect("batch executed");
+    state.record_swap_claim(&second_batch).await?;
+    assert_eq!(
+        state.unclaimed_swap_value(pair).await?,
+        (50u64.into(), 0u64.into())
+    );
+    state.record_swap_claim(&second_batch).await?;
+    assert_eq!(
+        state.unclaimed_swap_value(pair).await?,
+        (0u64.into(), 0u64.into())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+/// A rejected swap is logged at warn level, along with the reason it was rejected.
+async fn rejected_swap_is_logged_with_its_reason() -> anyhow::Result<()> {
+    use crate::{
+        swap::{SwapPlaintext, SwapPlan},
+        TradingPair,
+    };
+    use cnidarium_component::ActionHandler as _;
+    use penumbra_fee::Fee;
+    use penumbra_keys::test_keys;
+    use std::sync::Mutex;
+
+    /// Collects the formatted logs into a shared buffer.
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            std::io::Result::Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            std::io::Result::Ok(())
+        }
+    }
+
+    let logs = Logs::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_ansi(false)
+        .with_writer({
+            let logs = logs.clone();
+            move || logs.clone()
+        })
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_dex_params(DexParameters {
+        is_enabled: false,
+        ..Default::default()
+    });
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let swap_plaintext = SwapPlaintext::new(
+        &mut OsRng,
+        TradingPair::new(gm.id(), gn.id()),
+        100u64.into(),
+        0u64.into(),
+        Fee::default(),
+        *test_keys::ADDRESS_0,
+    );
+    let swap = SwapPlan::new(&mut OsRng, swap_plaintext).swap(&test_keys::FULL_VIEWING_KEY);
+    assert!(swap.check_and_execute(&mut state).await.is_err());
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone())?;
+    assert!(logs.contains("WARN"), "rejection is logged at warn: {logs}");
+    assert!(
+        logs.contains(&format!("{:?}", swap.body.payload.commitment)),
+        "rejection identifies the swap: {logs}"
+    );
+    assert!(
+        logs.contains("Dex MUST be enabled to process swap actions."),
+        "rejection includes its reason: {logs}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Position reserves are snapshotted at each epoch boundary while the position is open,
+/// and pruned once they fall out of the retention window.
+async fn position_reserves_are_snapshotted_at_epoch_end() -> anyhow::Result<()> {
+    use crate::component::{Dex, POSITION_RESERVES_RETENTION_EPOCHS};
+    use cnidarium_component::Component as _;
+    use penumbra_sct::{component::clock::EpochManager as _, epoch::Epoch};
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let pair = DirectedTradingPair::new(gm.id(), gn.id());
+    let with_reserves = |r1: u64, r2: u64| {
+        Position::new(
+            OsRng,
+            pair,
+            0u32,
+            1u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: r1.into(),
+                r2: r2.into(),
+            },
+        )
+    };
+
+    // The first position is open at the end of epoch 0.
+    let first = with_reserves(100, 200);
+    state.open_position(first.clone()).await?;
+    let mut state = Arc::new(state);
+    Dex::end_epoch(&mut state).await?;
+    let mut state = Arc::try_unwrap(state).expect("state should be uniquely referenced");
+
+    // In epoch 1, the second position is opened and the first one is closed.
+    state.put_block_height(10);
+    state.put_epoch_by_height(
+        10,
+        Epoch {
+            index: 1,
+            start_height: 10,
+        },
+    );
+    let second = with_reserves(300, 0);
+    state.open_position(second.clone()).await?;
+    state.close_position_by_id(&first.id()).await?;
+    let mut state = Arc::new(state);
+    Dex::end_epoch(&mut state).await?;
+    let mut state = Arc::try_unwrap(state).expect("state should be uniquely referenced");
+
+    assert_eq!(
+        state.position_reserves_at_epoch(&first.id(), 0).await?,
+        Some(first.reserves.clone())
+    );
+    assert_eq!(
+        state.position_reserves_at_epoch(&first.id(), 1).await?,
+        None
+    );
+    assert_eq!(
+        state.position_reserves_at_epoch(&second.id(), 0).await?,
+        None
+    );
+    assert_eq!(
+        state.position_reserves_at_epoch(&second.id(), 1).await?,
+        Some(second.reserves.clone())
+    );
+
+    // Snapshots are pruned once they fall out of the retention window.
+    let expiring_epoch = 1 + POSITION_RESERVES_RETENTION_EPOCHS;
+    state.put_block_height(20);
+    state.put_epoch_by_height(
+        20,
+        Epoch {
+            index: expiring_epoch,
+            start_height: 20,
+        },
+    );
+    let mut state = Arc::new(state);
+    Dex::end_epoch(&mut state).await?;
+    let state = Arc::try_unwrap(state).expect("state should be uniquely referenced");
+
+    assert_eq!(
+        state.position_reserves_at_epoch(&second.id(), 1).await?,
+        None
+    );
+    assert_eq!(
+        state
+            .position_reserves_at_epoch(&second.id(), expiring_epoch)
+            .await?,
+        Some(second.reserves.clone())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+/// A preview that includes the swaps queued in the block matches the execution of
+/// the batch, and falls back to the single-swap quote when nothing is queued.
+async fn swap_preview_accounts_for_queued_swaps() -> anyhow::Result<()> {
+    use crate::{
+        component::{Dex, SwapPreview, SwapPreviewRead as _},
+        swap::{SwapPlaintext, SwapPlan},
+        TradingPair,
+    };
+    use cnidarium_component::{ActionHandler as _, Component as _};
+    use penumbra_fee::Fee;
+    use penumbra_keys::test_keys;
+    use penumbra_sct::component::{clock::EpochManager as _, source::SourceContext as _};
+    use tendermint::v0_37::abci::request::EndBlock;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let pair = TradingPair::new(gm.id(), gn.id());
+    // Swaps of gn into gm, as a delta in the order of the pair.
+    let gn_delta = |amount: Amount| {
+        if pair.asset_1() == gn.id() {
+            (amount, Amount::zero())
+        } else {
+            (Amount::zero(), amount)
+        }
+    };
+
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_block_height(1);
+    state.put_mock_source(1);
+    state
+        .open_position(SellOrder::parse_str("10gm@1gn")?.into_position(OsRng))
+        .await?;
+
+    let input = gn.value(gn.unit_amount() * 5u64.into());
+    let alone = SwapPreview {
+        output: gm.value(gm.unit_amount() * 5u64.into()),
+        unfilled: gn.value(0u64.into()),
+    };
+
+    // With nothing queued, including the queued swaps does not change the quote.
+    let mut state = Arc::new(state);
+    assert_eq!(state.preview_swap(input, gm.id(), false).await?, alone);
+    assert_eq!(state.preview_swap(input, gm.id(), true).await?, alone);
+
+    // Another swap for 8gn is queued, which leaves only 2gm of liquidity.
+    let other = gn_delta(gn.unit_amount() * 8u64.into());
+    SwapPlan::new(
+        &mut OsRng,
+        SwapPlaintext::new(
+            &mut OsRng,
+            pair,
+            other.0,
+            other.1,
+            Fee::default(),
+            *test_keys::ADDRESS_0,
+        ),
+    )
+    .swap(&test_keys::FULL_VIEWING_KEY)
+    .check_and_execute(Arc::get_mut(&mut state).expect("state is uniquely referenced"))
+    .await?;
+
+    assert_eq!(state.preview_swap(input, gm.id(), false).await?, alone);
+    let batched = state.preview_swap(input, gm.id(), true).await?;
+    assert!(batched.output.amount < alone.output.amount);
+
+    // Executing the swap in the batch gives exactly the batched preview.
+    let mine = gn_delta(input.amount);
+    SwapPlan::new(
+        &mut OsRng,
+        SwapPlaintext::new(
+            &mut OsRng,
+            pair,
+            mine.0,
+            mine.1,
+            Fee::default(),
+            *test_keys::ADDRESS_0,
+        ),
+    )
+    .swap(&test_keys::FULL_VIEWING_KEY)
+    .check_and_execute(Arc::get_mut(&mut state).expect("state is uniquely referenced"))
+    .await?;
+    Dex::end_block(&mut state, &EndBlock { height: 1 }).await;
+
+    let output_data = state.output_data(1, pair).await?.expect("batch executed");
+    let (lambda_1, lambda_2) = output_data.pro_rata_outputs(mine);
+    let (output, unfilled) = if pair.asset_1() == gn.id() {
+        (lambda_2, lambda_1)
+    } else {
+        (lambda_1, lambda_2)
+    };
+    assert_eq!(
+        batched,
+        SwapPreview {
+            output: gm.value(output),
+            unfilled: gn.value(unfilled),
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Quotes against a pinned snapshot stay the same while the chain moves on.
+async fn quotes_against_a_pinned_snapshot_are_stable() -> anyhow::Result<()> {
+    use crate::component::SwapPreviewRead as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state
+        .open_position(SellOrder::parse_str("10gm@1gn")?.into_position(OsRng))
+        .await?;
+    storage.commit(state).await?;
+
+    let input = gn.value(gn.unit_amount() * 4u64.into());
+    let pinned = storage.latest_snapshot();
+    let routing_params = pinned.routing_params().await?;
+    let quote = pinned
+        .simulate_trade(input, gm.id(), routing_params.clone())
+        .await?;
+    assert_eq!(quote.output, gm.value(gm.unit_amount() * 4u64.into()));
+
+    // The tip moves on, with better liquidity.
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state
+        .open_position(SellOrder::parse_str("10gm@0.5gn")?.into_position(OsRng))
+        .await?;
+    storage.commit(state).await?;
+
+    // Quoting repeatedly against the pinned snapshot gives the same result...
+    for _ in 0..3 {
+        assert_eq!(
+            pinned
+                .simulate_trade(input, gm.id(), routing_params.clone())
+                .await?,
+            quote
+        );
+        assert_eq!(
+            pinned.preview_swap(input, gm.id(), true).await?.output,
+            quote.output
+        );
+    }
+
+    // ...while quoting against the tip sees the new liquidity.
+    let tip_quote = storage
+        .latest_snapshot()
+        .simulate_trade(input, gm.id(), routing_params)
+        .await?;
+    assert!(tip_quote.output.amount > quote.output.amount);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// The marginal price of a trade is its best fill, and its average price degrades
+/// toward the worst fill as it consumes more of the book.
+async fn trade_prices_split_marginal_and_average() -> anyhow::Result<()> {
+    use crate::component::SwapPreviewRead as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    for order in ["1gm@1gn", "1gm@2gn", "1gm@3gn"] {
+        state
+            .open_position(SellOrder::parse_str(order)?.into_position(OsRng))
+            .await?;
+    }
+    storage.commit(state).await?;
+
+    let snapshot = storage.latest_snapshot();
+    let routing_params = snapshot.routing_params().await?;
+    let prices = |amount: u64| {
+        let snapshot = snapshot.clone();
+        let routing_params = routing_params.clone();
+        let input = gn.value(gn.unit_amount() * amount.into());
+        let output_id = gm.id();
+        async move {
+            snapshot
+                .simulate_trade_prices(input, output_id, routing_params)
+                .await
+        }
+    };
+
+    // A trade filled by the best position alone gets the same price throughout...
+    let small = prices(1).await?.expect("the book has liquidity");
+    assert_eq!(small.marginal, small.average);
+
+    // ...while one that exhausts the book starts at the same marginal price, and
+    // averages over the worse fills.
+    let large = prices(6).await?.expect("the book has liquidity");
+    assert_eq!(large.marginal, small.marginal);
+    assert!(large.marginal <= large.average);
+    assert!(large.average > small.average);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// Under a nonzero position open fee, a position must pay the fee to be opened, and it
+/// is debited exactly once. Under a zero fee, positions are opened for free.
+async fn position_open_charges_the_open_fee() -> anyhow::Result<()> {
+    use cnidarium_component::ActionHandler as _;
+    use penumbra_asset::STAKING_TOKEN_ASSET_ID;
+    use penumbra_proto::DomainType as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+
+    let fee = Amount::from(1_000u64);
+    let open = |open_fee: Amount| -> anyhow::Result<PositionOpen> {
+        Ok(PositionOpen {
+            position: SellOrder::parse_str("10gn@1gm")?.into_position(OsRng),
+            open_fee,
+        })
+    };
+    let staking_token_required = |open: &PositionOpen| -> Vec<Value> {
+        open.balance()
+            .required()
+            .filter(|value| value.asset_id == *STAKING_TOKEN_ASSET_ID)
+            .collect()
+    };
+
+    // With a nonzero fee, an open that doesn't pay it is rejected...
+    state.put_dex_params(DexParameters {
+        position_open_fee: fee,
+        ..Default::default()
+    });
+    let err = open(Amount::zero())?
+        .check_and_execute(&mut state)
+        .await
+        .expect_err("a position open without the open fee should be rejected");
+    assert!(err.to_string().contains("below the required"));
+
+    // ...while one that pays it is accepted, and debited the fee once.
+    let charged = open(fee)?;
+    charged.check_and_execute(&mut state).await?;
+    assert_eq!(
+        staking_token_required(&charged),
+        vec![Value {
+            amount: fee,
+            asset_id: *STAKING_TOKEN_ASSET_ID,
+        }]
+    );
+    assert_eq!(
+        PositionOpen::decode(charged.encode_to_vec().as_slice())?.open_fee,
+        fee
+    );
+
+    // With a zero fee, an open is free, and encodes exactly as it did before the fee.
+    state.put_dex_params(DexParameters::default());
+    let free = open(Amount::zero())?;
+    free.check_and_execute(&mut state).await?;
+    assert!(staking_token_required(&free).is_empty());
+    assert!(free.to_proto().open_fee.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+/// The active trading pairs of two states holding the same positions are listed in
+/// the same order, whatever order the positions were opened in.
+async fn active_trading_pairs_are_in_canonical_order() -> anyhow::Result<()> {
+    let orders = ["10gn@1gm", "10penumbra@1gm", "10penumbra@1gn", "20gn@2gm"];
+    let positions = orders
+        .iter()
+        .map(|order| Ok(SellOrder::parse_str(order)?.into_position(OsRng)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut listings = Vec::new();
+    for reversed in [false, true] {
+        let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+        let mut state = StateDelta::new(storage.latest_snapshot());
+
+        let mut positions = positions.clone();
+        if reversed {
+            positions.reverse();
+        }
+        for position in positions {
+            state.open_position(position).await?;
+        }
+        listings.push(state.active_trading_pairs().await?);
+    }
+
+    assert_eq!(listings[0], listings[1]);
+    let pairs = &listings[0];
+    assert_eq!(pairs.len(), 3, "each pair is listed once");
+    assert!(pairs
+        .windows(2)
+        .all(|pair| pair[0].to_bytes() < pair[1].to_bytes()));
+
+    Ok(())
+}
+
+#[tokio::test]
+/// A hypothetical position captures the historical flow that cleared at a price no
+/// better than its own, and the replay is bounded to the requested blocks.
+async fn simulate_position_fills_replays_batch_history() -> anyhow::Result<()> {
+    use crate::{component::SwapPreviewRead as _, state_key, TradingPair};
+    use penumbra_proto::StateWriteProto as _;
+    use penumbra_sct::component::clock::EpochManager as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let pair = TradingPair::new(gm.id(), gn.id());
+
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_block_height(3);
+    let output_data =
+        |height, (delta_1, unfilled_1, lambda_2), (delta_2, lambda_1)| BatchSwapOutputData {
+            delta_1: Amount::from(delta_1),
+            delta_2: Amount::from(delta_2),
+            lambda_1: Amount::from(lambda_1),
+            lambda_2: Amount::from(lambda_2),
+            unfilled_1: Amount::from(unfilled_1),
+            unfilled_2: Amount::zero(),
+            height,
+            trading_pair: pair,
+            sct_position_prefix: Default::default(),
+        };
+    // At height 1, 20 of asset 1 clear for 10 of asset 2, a price of 2.
+    state.put(
+        state_key::output_data(1, pair),
+        output_data(1, (25u64, 5u64, 10u64), (0u64, 0u64)),
+    );
+    // At height 2, 10 of asset 1 clear for 20 of asset 2, a price of 0.5.
+    state.put(
+        state_key::output_data(2, pair),
+        output_data(2, (10, 0, 20), (0, 0)),
+    );
+    // At height 3, 10 of asset 2 clear for 5 of asset 1, a price of 2.
+    state.put(
+        state_key::output_data(3, pair),
+        output_data(3, (0, 0, 0), (10, 5)),
+    );
+
+    // A position trading the two assets one for one, seeded with 100 of asset 2.
+    let position = Position::new(
+        OsRng,
+        DirectedTradingPair::new(pair.asset_1(), pair.asset_2()),
+        0,
+        1u64.into(),
+        1u64.into(),
+        Reserves {
+            r1: Amount::zero(),
+            r2: 100u64.into(),
+        },
+    );
+
+    // Over the whole history, it fills the flow at height 1, skips the cheaper flow
+    // at height 2, and sells back half of what it bought at height 3.
+    let reserves = state.simulate_position_fills(&position, 3).await?;
+    assert_eq!(
+        reserves,
+        Reserves {
+            r1: 10u64.into(),
+            r2: 90u64.into(),
+        }
+    );
+    // Over more blocks than there is history, the result is the same.
+    assert_eq!(
+        state.simulate_position_fills(&position, u64::MAX).await?,
+        reserves
+    );
+    // Over the last block only, it has no asset 1 to sell, so nothing fills.
+    assert_eq!(
+        state.simulate_position_fills(&position, 1).await?,
+        position.reserves
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+/// The first position on a pair must meet the bootstrap minimum on both sides, while
+/// later positions on the pair can be of any size.
+async fn first_position_on_pair_requires_bootstrap_reserves() -> anyhow::Result<()> {
+    use crate::TradingPair;
+    use cnidarium_component::ActionHandler as _;
+
+    let storage = TempStorage::new().await?.apply_minimal_genesis().await?;
+    let mut state = StateDelta::new(storage.latest_snapshot());
+    state.put_dex_params(DexParameters {
+        pair_bootstrap_min_reserves: 10u64.into(),
+        ..Default::default()
+    });
+
+    let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+    let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+    let pair = TradingPair::new(gm.id(), gn.id());
+    let open = |position: Position| PositionOpen {
+        position,
+        open_fee: Amount::zero(),
+    };
+    let two_sided = |reserves: u64| {
+        open(Position::new(
+            OsRng,
+            DirectedTradingPair::new(pair.asset_1(), pair.asset_2()),
+            0,
+            1u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: reserves.into(),
+                r2: reserves.into(),
+            },
+        ))
+    };
+
+    // A first position below the minimum doesn't establish the pair...
+    let err = two_sided(5)
+        .check_and_execute(&mut state)
+        .await
+        .expect_err("a first position below the bootstrap minimum should be rejected");
+    assert!(err.to_string().contains("at least 10 on both sides"));
+    assert!(state.active_trading_pairs().await?.is_empty());
+
+    // ...while one at the minimum does.
+    two_sided(10).check_and_execute(&mut state).await?;
+    assert_eq!(state.active_trading_pairs().await?, vec![pair]);
+
+    // Once the pair exists, smaller and one-sided positions are accepted.
+    two_sided(1).check_and_execute(&mut state).await?;
+    open(SellOrder::parse_str("1gn@1gm")?.into_position(OsRng))
+        .check_and_execute(&mut state)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,221 @@
+//! Rolling utilization of liquidity positions.
+//!
+//! Each execution against a position turns over some fraction of the reserves it
+//! gives out: filling half of a position's reserves of an asset turns over `0.5`,
+//! and depleting them turns over `1.0`. The utilization of a position over a window
+//! is the sum of the fractions turned over in that window, so it can exceed `1.0`
+//! when a position is depleted, refilled by trades in the other direction, and
+//! depleted again.
+//!
+//! Turnover is accumulated into the same buckets as the asset volume, kept in a
+//! ring of [`VOLUME_BUCKETS`] slots per position.
+
+use anyhow::{anyhow, ensure, Result};
+use async_trait::async_trait;
+use cnidarium::{StateRead, StateWrite};
+use penumbra_sct::component::clock::EpochRead as _;
+
+use crate::{
+    component::volume::{MAX_VOLUME_WINDOW_BLOCKS, VOLUME_BUCKETS, VOLUME_BUCKET_BLOCKS},
+    lp::position::{self, Position},
+    state_key,
+};
+
+/// The resolution at which turnover is recorded, in parts per unit of reserves.
+const TURNOVER_SCALE: u128 = 1_000_000;
+
+#[async_trait]
+pub trait PositionUtilizationRead: StateRead {
+    /// Gets the fraction of its reserves that the position `id` turned over in the
+    /// last `window_blocks` blocks, up to and including the current one.
+    ///
+    /// A position that was not traded against in the window has a utilization of
+    /// zero. The window is rounded up to a whole number of buckets, and may not
+    /// exceed [`MAX_VOLUME_WINDOW_BLOCKS`].
+    async fn position_utilization(&self, id: &position::Id, window_blocks: u64) -> Result<f64> {
+        ensure!(
+            window_blocks <= MAX_VOLUME_WINDOW_BLOCKS,
+            "utilization window of {window_blocks} blocks exceeds the maximum of {MAX_VOLUME_WINDOW_BLOCKS}"
+        );
+
+        let current_bucket = self.get_block_height().await? / VOLUME_BUCKET_BLOCKS;
+        let num_buckets = window_blocks.div_ceil(VOLUME_BUCKET_BLOCKS);
+
+        let mut total = 0u64;
+        for bucket in (0..num_buckets).filter_map(|i| current_bucket.checked_sub(i)) {
+            // A slot holding an older bucket has aged out of the window.
+            if let Some((stored_bucket, turnover)) = self.turnover_bucket(id, bucket).await? {
+                if stored_bucket == bucket {
+                    total = total.saturating_add(turnover);
+                }
+            }
+        }
+        Ok(total as f64 / TURNOVER_SCALE as f64)
+    }
+
+    /// Gets the contents of the ring slot that `bucket` maps to, if any.
+    async fn turnover_bucket(&self, id: &position::Id, bucket: u64) -> Result<Option<(u64, u64)>> {
+        let key = state_key::position_turnover_slot(id, bucket % VOLUME_BUCKETS);
+        self.nonverifiable_get_raw(key.as_bytes())
+            .await?
+            .map(|bytes| {
+                ensure!(bytes.len() == 16, "malformed turnover bucket for {id}");
+                let (stored_bucket, turnover) = bytes.split_at(8);
+                Ok((
+                    u64::from_be_bytes(stored_bucket.try_into()?),
+                    u64::from_be_bytes(turnover.try_into()?),
+                ))
+            })
+            .transpose()
+    }
+}
+
+impl<T: StateRead + ?Sized> PositionUtilizationRead for T {}
+
+#[async_trait]
+pub(crate) trait PositionUtilizationTracker: StateWrite {
+    /// Adds the turnover of an execution taking a position from `prev_state` to
+    /// `new_state` to its utilization at the current height.
+    ///
+    /// The turnover is the fraction of the reserves given out by the position, which
+    /// is the asset whose reserves decreased.
+    async fn record_position_turnover(
+        &mut self,
+        prev_state: &Position,
+        new_state: &Position,
+    ) -> Result<()> {
+        let pair = prev_state.phi.pair;
+        let output = [pair.asset_1(), pair.asset_2()]
+            .into_iter()
+            .find_map(|asset_id| {
+                let prev = prev_state.reserves_for(asset_id)?;
+                let new = new_state.reserves_for(asset_id)?;
+                (new < prev).then(|| (prev, prev - new))
+            });
+        let Some((prev, given)) = output else {
+            return Ok(());
+        };
+
+        let turnover: u64 = (given
+            .value()
+            .checked_mul(TURNOVER_SCALE)
+            .ok_or_else(|| anyhow!("turnover overflow for position {}", prev_state.id()))?
+            / prev.value())
+        .try_into()?;
+        if turnover == 0 {
+            return Ok(());
+        }
+
+        let id = prev_state.id();
+        let bucket = self.get_block_height().await? / VOLUME_BUCKET_BLOCKS;
+        let total = match self.turnover_bucket(&id, bucket).await? {
+            Some((stored_bucket, total)) if stored_bucket == bucket => {
+                total.saturating_add(turnover)
+            }
+            // Either the slot is empty, or it holds a bucket that has aged out.
+            _ => turnover,
+        };
+
+        let mut bytes = bucket.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&total.to_be_bytes());
+        self.nonverifiable_put_raw(
+            state_key::position_turnover_slot(&id, bucket % VOLUME_BUCKETS).into(),
+            bytes,
+        );
+        Ok(())
+    }
+
+    /// Deletes the turnover recorded for the position `id`, which can no longer be
+    /// traded against once it has been withdrawn.
+    fn clear_position_turnover(&mut self, id: &position::Id) {
+        for slot in 0..VOLUME_BUCKETS {
+            self.nonverifiable_delete(state_key::position_turnover_slot(id, slot).into());
+        }
+    }
+}
+
+impl<T: StateWrite + ?Sized> PositionUtilizationTracker for T {}
+
+#[cfg(test)]
+mod tests {
+    use cnidarium::{StateDelta, TempStorage};
+    use penumbra_asset::asset;
+    use penumbra_sct::component::clock::EpochManager as _;
+
+    use super::*;
+    use crate::{lp::Reserves, DirectedTradingPair, TradingPair};
+
+    #[tokio::test]
+    async fn utilization_sums_turnover_in_the_window() -> anyhow::Result<()> {
+        let storage = TempStorage::new().await?;
+        let mut state = StateDelta::new(storage.latest_snapshot());
+
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let pair = TradingPair::new(gm.id(), gn.id());
+        let with_reserves = |r1: u64, r2: u64| {
+            Position::new_with_nonce(
+                [0u8; 32],
+                DirectedTradingPair::new(pair.asset_1(), pair.asset_2()),
+                0u32,
+                1u64.into(),
+                1u64.into(),
+                Reserves {
+                    r1: r1.into(),
+                    r2: r2.into(),
+                },
+            )
+        };
+        let id = with_reserves(0, 1_000).id();
+
+        // A position that was never traded against has no utilization.
+        state.put_block_height(1);
+        assert_eq!(
+            state
+                .position_utilization(&id, VOLUME_BUCKET_BLOCKS)
+                .await?,
+            0.0
+        );
+
+        // A quarter of the asset 2 reserves turn over, then the rest of them.
+        state
+            .record_position_turnover(&with_reserves(0, 1_000), &with_reserves(250, 750))
+            .await?;
+        state.put_block_height(VOLUME_BUCKET_BLOCKS + 1);
+        state
+            .record_position_turnover(&with_reserves(250, 750), &with_reserves(1_000, 0))
+            .await?;
+
+        assert_eq!(
+            state
+                .position_utilization(&id, VOLUME_BUCKET_BLOCKS)
+                .await?,
+            1.0
+        );
+        assert_eq!(
+            state
+                .position_utilization(&id, 2 * VOLUME_BUCKET_BLOCKS)
+                .await?,
+            1.25
+        );
+
+        // Trades in the other direction then deplete the asset 1 reserves the
+        // position accumulated, turning over its reserves a second time.
+        state
+            .record_position_turnover(&with_reserves(1_000, 0), &with_reserves(0, 1_000))
+            .await?;
+        assert_eq!(
+            state
+                .position_utilization(&id, 2 * VOLUME_BUCKET_BLOCKS)
+                .await?,
+            2.25
+        );
+
+        assert!(state
+            .position_utilization(&id, MAX_VOLUME_WINDOW_BLOCKS + 1)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+//! Rolling per-asset trade volume.
+//!
+//! Volume is accumulated into buckets spanning [`VOLUME_BUCKET_BLOCKS`] blocks each,
+//! kept in a ring of [`VOLUME_BUCKETS`] slots per asset. A slot is overwritten once
+//! its bucket falls out of the ring, so the storage used by an asset is bounded no
+//! matter how long the chain runs.
+
+use anyhow::{ensure, Result};
+use async_trait::async_trait;
+use cnidarium::{StateRead, StateWrite};
+use penumbra_asset::{asset, Value};
+use penumbra_num::Amount;
+use penumbra_sct::component::clock::EpochRead as _;
+
+use crate::state_key;
+
+/// The number of blocks covered by a single volume bucket.
+pub const VOLUME_BUCKET_BLOCKS: u64 = 100;
+
+/// The number of volume buckets retained for each asset.
+pub const VOLUME_BUCKETS: u64 = 144;
+
+/// The largest window, in blocks, over which volume can be queried.
+pub const MAX_VOLUME_WINDOW_BLOCKS: u64 = VOLUME_BUCKET_BLOCKS * VOLUME_BUCKETS;
+
+#[async_trait]
+pub trait AssetVolumeRead: StateRead {
+    /// Gets the volume of `asset` traded in batch swaps over the last `window_blocks`
+    /// blocks, up to and including the current one.
+    ///
+    /// The window is rounded up to a whole number of buckets, and may not exceed
+    /// [`MAX_VOLUME_WINDOW_BLOCKS`].
+    async fn asset_volume(&self, asset: asset::Id, window_blocks: u64) -> Result<Amount> {
+        ensure!(
+            window_blocks <= MAX_VOLUME_WINDOW_BLOCKS,
+            "volume window of {window_blocks} blocks exceeds the maximum of {MAX_VOLUME_WINDOW_BLOCKS}"
+        );
+
+        let current_bucket = self.get_block_height().await? / VOLUME_BUCKET_BLOCKS;
+        let num_buckets = window_blocks.div_ceil(VOLUME_BUCKET_BLOCKS);
+
+        let mut total = Amount::zero();
+        for bucket in (0..num_buckets).filter_map(|i| current_bucket.checked_sub(i)) {
+            // A slot holding an older bucket has aged out of the window.
+            if let Some((stored_bucket, volume)) = self.volume_bucket(&asset, bucket).await? {
+                if stored_bucket == bucket {
+                    total = total.saturating_add(&volume);
+                }
+            }
+        }
+        Ok(total)
+    }
+
+    /// Gets the contents of the ring slot that `bucket` maps to, if any.
+    async fn volume_bucket(&self, asset: &asset::Id, bucket: u64) -> Result<Option<(u64, Amount)>> {
+        let key = state_key::asset_volume_slot(asset, bucket % VOLUME_BUCKETS);
+        self.nonverifiable_get_raw(key.as_bytes())
+            .await?
+            .map(|bytes| {
+                ensure!(bytes.len() == 24, "malformed volume bucket for {asset}");
+                let (stored_bucket, volume) = bytes.split_at(8);
+                Ok((
+                    u64::from_be_bytes(stored_bucket.try_into()?),
+                    Amount::from_be_bytes(volume.try_into()?),
+                ))
+            })
+            .transpose()
+    }
+}
+
+impl<T: StateRead + ?Sized> AssetVolumeRead for T {}
+
+#[async_trait]
+pub(crate) trait AssetVolumeTracker: StateWrite {
+    /// Adds `value` to the volume traded in its asset at `height`.
+    ///
+    /// The volume of a bucket saturates rather than overflowing.
+    async fn record_asset_volume(&mut self, height: u64, value: Value) -> Result<()> {
+        if value.amount == Amount::zero() {
+            return Ok(());
+        }
+
+        let bucket = height / VOLUME_BUCKET_BLOCKS;
+        let volume = match self.volume_bucket(&value.asset_id, bucket).await? {
+            Some((stored_bucket, volume)) if stored_bucket == bucket => {
+                volume.saturating_add(&value.amount)
+            }
+            // Either the slot is empty, or it holds a bucket that has aged out.
+            _ => value.amount,
+        };
+
+        let mut bytes = bucket.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&volume.to_be_bytes());
+        self.nonverifiable_put_raw(
+            state_key::asset_volume_slot(&value.asset_id, bucket % VOLUME_BUCKETS).into(),
+            bytes,
+        );
+        Ok(())
+    }
+}
+
+impl<T: StateWrite + ?Sized> AssetVolumeTracker for T {}
+
+#[cfg(test)]
+mod tests {
+    use cnidarium::{StateDelta, TempStorage};
+    use penumbra_sct::component::clock::EpochManager as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn volume_ages_out_of_the_window() -> anyhow::Result<()> {
+        let storage = TempStorage::new().await?;
+        let mut state = StateDelta::new(storage.latest_snapshot());
+
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+
+        // Two trades in the first bucket, and one in the second.
+        state.record_asset_volume(0, gm.value(10u64.into())).await?;
+        state.record_asset_volume(50, gm.value(5u64.into())).await?;
+        state
+            .record_asset_volume(VOLUME_BUCKET_BLOCKS, gm.value(7u64.into()))
+            .await?;
+        state.record_asset_volume(50, gn.value(3u64.into())).await?;
+
+        state.put_block_height(VOLUME_BUCKET_BLOCKS + 1);
+        assert_eq!(
+            state.asset_volume(gm.id(), VOLUME_BUCKET_BLOCKS).await?,
+            7u64.into()
+        );
+        assert_eq!(
+            state
+                .asset_volume(gm.id(), 2 * VOLUME_BUCKET_BLOCKS)
+                .await?,
+            22u64.into()
+        );
+        assert_eq!(
+            state
+                .asset_volume(gn.id(), MAX_VOLUME_WINDOW_BLOCKS)
+                .await?,
+            3u64.into()
+        );
+        assert_eq!(state.asset_volume(gm.id(), 0).await?, 0u64.into());
+        assert!(state
+            .asset_volume(gm.id(), MAX_VOLUME_WINDOW_BLOCKS + 1)
+            .await
+            .is_err());
+
+        // Once the ring wraps around, the first bucket's slot is reused and its
+        // volume no longer counts towards any window.
+        let wrapped = MAX_VOLUME_WINDOW_BLOCKS;
+        state
+            .record_asset_volume(wrapped, gm.value(1u64.into()))
+            .await?;
+        state.put_block_height(wrapped);
+        assert_eq!(
+            state
+                .asset_volume(gm.id(), MAX_VOLUME_WINDOW_BLOCKS)
+                .await?,
+            8u64.into()
+        );
+
+        // After the second bucket ages out too, only the latest trade remains.
+        state.put_block_height(wrapped + VOLUME_BUCKET_BLOCKS);
+        assert_eq!(
+            state
+                .asset_volume(gm.id(), MAX_VOLUME_WINDOW_BLOCKS)
+                .await?,
+            1u64.into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn volume_saturates_instead_of_overflowing() -> anyhow::Result<()> {
+        let storage = TempStorage::new().await?;
+        let mut state = StateDelta::new(storage.latest_snapshot());
+
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let max = Amount::from(u128::MAX);
+
+        // Two buckets at the maximum volume, each saturating on a further trade.
+        for height in [0, VOLUME_BUCKET_BLOCKS] {
+            state.record_asset_volume(height, gm.value(max)).await?;
+            state
+                .record_asset_volume(height, gm.value(1u64.into()))
+                .await?;
+        }
+
+        state.put_block_height(VOLUME_BUCKET_BLOCKS);
+        assert_eq!(
+            state.asset_volume(gm.id(), VOLUME_BUCKET_BLOCKS).await?,
+            max
+        );
+        assert_eq!(
+            state
+                .asset_volume(gm.id(), 2 * VOLUME_BUCKET_BLOCKS)
+                .await?,
+            max
+        );
+
+        Ok(())
+    }
+}
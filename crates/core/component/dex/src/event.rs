@@ -7,10 +7,13 @@ use crate::{
 use anyhow::{anyhow, Context};
 use penumbra_asset::asset;
 use penumbra_num::Amount;
-use penumbra_proto::{penumbra::core::component::dex::v1 as pb, DomainType};
+use penumbra_proto::{
+    event::EventDomainType, penumbra::core::component::dex::v1 as pb, DomainType,
+};
 use penumbra_sct::Nullifier;
 use penumbra_tct::StateCommitment;
 use prost::Name as _;
+use tendermint::abci;
 
 #[derive(Clone, Debug)]
 pub struct EventSwap {
@@ -661,3 +664,312 @@ impl From<EventCandlestickData> for pb::EventCandlestickData {
 impl DomainType for EventCandlestickData {
     type Proto = pb::EventCandlestickData;
 }
+
+/// Any of the events emitted by the DEX component.
+#[derive(Clone, Debug)]
+pub enum Event {
+    Swap(EventSwap),
+    SwapClaim(EventSwapClaim),
+    PositionOpen(EventPositionOpen),
+    PositionClose(EventPositionClose),
+    QueuePositionClose(EventQueuePositionClose),
+    PositionWithdraw(EventPositionWithdraw),
+    PositionExecution(EventPositionExecution),
+    BatchSwap(EventBatchSwap),
+    ArbExecution(EventArbExecution),
+    ValueCircuitBreakerCredit(EventValueCircuitBreakerCredit),
+    ValueCircuitBreakerDebit(EventValueCircuitBreakerDebit),
+    CandlestickData(EventCandlestickData),
+}
+
+impl TryFrom<&abci::Event> for Event {
+    type Error = anyhow::Error;
+
+    fn try_from(event: &abci::Event) -> Result<Self, Self::Error> {
+        let kind = event.kind.as_str();
+        Ok(match kind {
+            _ if kind == pb::EventSwap::full_name() => {
+                Event::Swap(EventSwap::try_from_event(event)?)
+            }
+            _ if kind == pb::EventSwapClaim::full_name() => {
+                Event::SwapClaim(EventSwapClaim::try_from_event(event)?)
+            }
+            _ if kind == pb::EventPositionOpen::full_name() => {
+                Event::PositionOpen(EventPositionOpen::try_from_event(event)?)
+            }
+            _ if kind == pb::EventPositionClose::full_name() => {
+                Event::PositionClose(EventPositionClose::try_from_event(event)?)
+            }
+            _ if kind == pb::EventQueuePositionClose::full_name() => {
+                Event::QueuePositionClose(EventQueuePositionClose::try_from_event(event)?)
+            }
+            _ if kind == pb::EventPositionWithdraw::full_name() => {
+                Event::PositionWithdraw(EventPositionWithdraw::try_from_event(event)?)
+            }
+            _ if kind == pb::EventPositionExecution::full_name() => {
+                Event::PositionExecution(EventPositionExecution::try_from_event(event)?)
+            }
+            _ if kind == pb::EventBatchSwap::full_name() => {
+                Event::BatchSwap(EventBatchSwap::try_from_event(event)?)
+            }
+            _ if kind == pb::EventArbExecution::full_name() => {
+                Event::ArbExecution(EventArbExecution::try_from_event(event)?)
+            }
+            _ if kind == pb::EventValueCircuitBreakerCredit::full_name() => {
+                Event::ValueCircuitBreakerCredit(EventValueCircuitBreakerCredit::try_from_event(
+                    event,
+                )?)
+            }
+            _ if kind == pb::EventValueCircuitBreakerDebit::full_name() => {
+                Event::ValueCircuitBreakerDebit(EventValueCircuitBreakerDebit::try_from_event(
+                    event,
+                )?)
+            }
+            _ if kind == pb::EventCandlestickData::full_name() => {
+                Event::CandlestickData(EventCandlestickData::try_from_event(event)?)
+            }
+            _ => anyhow::bail!("unknown DEX event kind {}", kind),
+        })
+    }
+}
+
+/// Returns the canonical trading pair an event pertains to, if it has one.
+///
+/// This allows consumers to filter the event stream by trading pair.
+/// Events that aren't scoped to a trading pair, like circuit breaker updates,
+/// or that only reference a position by its ID, return `None`.
+pub fn event_trading_pair(event: &Event) -> Option<TradingPair> {
+    match event {
+        Event::Swap(e) => Some(e.trading_pair),
+        Event::SwapClaim(e) => Some(e.trading_pair),
+        Event::PositionOpen(e) => Some(e.trading_pair),
+        Event::PositionWithdraw(e) => Some(e.trading_pair),
+        Event::PositionExecution(e) => Some(e.trading_pair),
+        Event::BatchSwap(e) => Some(e.batch_swap_output_data.trading_pair),
+        Event::CandlestickData(e) => Some(e.pair.into()),
+        Event::PositionClose(_)
+        | Event::QueuePositionClose(_)
+        | Event::ArbExecution(_)
+        | Event::ValueCircuitBreakerCredit(_)
+        | Event::ValueCircuitBreakerDebit(_) => None,
+    }
+}
+
+/// Returns a human-readable label for `asset_id`: its base denom if it is known to
+/// `cache`, or its hex-encoded ID otherwise.
+pub fn asset_label(asset_id: &asset::Id, cache: &asset::Cache) -> String {
+    match cache.get_by_id(*asset_id) {
+        Some(metadata) => metadata.base_denom().denom,
+        None => hex::encode(asset_id.to_bytes()),
+    }
+}
+
+/// Returns labels for both assets of the trading pair an event pertains to, as
+/// given by [`event_trading_pair`] and [`asset_label`].
+///
+/// This lets consumers present events without looking up each asset themselves.
+pub fn event_pair_labels(event: &Event, cache: &asset::Cache) -> Option<(String, String)> {
+    let pair = event_trading_pair(event)?;
+    Some((
+        asset_label(&pair.asset_1(), cache),
+        asset_label(&pair.asset_2(), cache),
+    ))
+}
+
+/// The attribute holding the index of a DEX event among the DEX events of its block.
+pub const EVENT_INDEX_ATTRIBUTE: &str = "dexEventIndex";
+
+/// The position of a DEX event in the chain's stream of DEX events.
+///
+/// Cursors are ordered by block height, then by the order in which the events of a
+/// block were recorded, so a consumer can checkpoint the cursor of the last event it
+/// processed and resume after it without duplicates or gaps. The index of an event
+/// only depends on the block's contents, so it is stable across re-execution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EventCursor {
+    pub height: u64,
+    pub index: u64,
+}
+
+impl EventCursor {
+    /// Returns the cursor of a DEX `event` from the block at `height`, or `None` if
+    /// the event doesn't carry an [`EVENT_INDEX_ATTRIBUTE`].
+    pub fn from_event(height: u64, event: &abci::Event) -> Option<Self> {
+        let index = event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == EVENT_INDEX_ATTRIBUTE)?
+            .value
+            .parse()
+            .ok()?;
+        Some(Self { height, index })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use decaf377::Fq;
+    use penumbra_asset::Value;
+    use penumbra_proto::event::ProtoEvent as _;
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::lp::Reserves;
+
+    fn pair() -> TradingPair {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        TradingPair::new(gm.id(), gn.id())
+    }
+
+    fn swap_execution(asset_id: asset::Id) -> SwapExecution {
+        let value = Value {
+            amount: 1u64.into(),
+            asset_id,
+        };
+        SwapExecution {
+            traces: vec![vec![value, value]],
+            input: value,
+            output: value,
+        }
+    }
+
+    #[test]
+    fn event_trading_pair_for_each_variant() {
+        let pair = pair();
+        let position = Position::new(
+            OsRng,
+            DirectedTradingPair::new(pair.asset_1(), pair.asset_2()),
+            0,
+            1u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: 1u64.into(),
+                r2: 1u64.into(),
+            },
+        );
+        let position_id = position.id();
+        let commitment = StateCommitment(Fq::from(1u64));
+
+        let events_with_pair = [
+            Event::Swap(EventSwap {
+                trading_pair: pair,
+                delta_1_i: 1u64.into(),
+                delta_2_i: 0u64.into(),
+                swap_commitment: commitment,
+            }),
+            Event::SwapClaim(EventSwapClaim {
+                trading_pair: pair,
+                output_1_commitment: commitment,
+                output_2_commitment: commitment,
+                nullifier: Nullifier(Fq::from(2u64)),
+            }),
+            Event::PositionOpen(position.clone().into()),
+            Event::PositionWithdraw(EventPositionWithdraw::in_context(position_id, &position)),
+            Event::PositionExecution(EventPositionExecution::in_context(
+                &position,
+                &position,
+                DirectedTradingPair::new(pair.asset_1(), pair.asset_2()),
+            )),
+            Event::BatchSwap(EventBatchSwap {
+                batch_swap_output_data: BatchSwapOutputData {
+                    delta_1: 1u64.into(),
+                    delta_2: 0u64.into(),
+                    lambda_1: 0u64.into(),
+                    lambda_2: 0u64.into(),
+                    unfilled_1: 1u64.into(),
+                    unfilled_2: 0u64.into(),
+                    height: 1,
+                    trading_pair: pair,
+                    sct_position_prefix: Default::default(),
+                },
+                swap_execution_1_for_2: None,
+                swap_execution_2_for_1: None,
+            }),
+            // A candlestick in the non-canonical direction still maps to the canonical pair.
+            Event::CandlestickData(EventCandlestickData {
+                pair: DirectedTradingPair::new(pair.asset_2(), pair.asset_1()),
+                stick: CandlestickData {
+                    height: 1,
+                    open: 1.0,
+                    close: 1.0,
+                    high: 1.0,
+                    low: 1.0,
+                    direct_volume: 1.0,
+                    swap_volume: 0.0,
+                },
+            }),
+        ];
+        for event in &events_with_pair {
+            assert_eq!(event_trading_pair(event), Some(pair), "{event:?}");
+        }
+
+        let events_without_pair = [
+            Event::PositionClose(EventPositionClose { position_id }),
+            Event::QueuePositionClose(EventQueuePositionClose { position_id }),
+            Event::ArbExecution(EventArbExecution {
+                height: 1,
+                swap_execution: swap_execution(pair.asset_1()),
+            }),
+            Event::ValueCircuitBreakerCredit(EventValueCircuitBreakerCredit {
+                asset_id: pair.asset_1(),
+                previous_balance: 0u64.into(),
+                new_balance: 1u64.into(),
+            }),
+            Event::ValueCircuitBreakerDebit(EventValueCircuitBreakerDebit {
+                asset_id: pair.asset_1(),
+                previous_balance: 1u64.into(),
+                new_balance: 0u64.into(),
+            }),
+        ];
+        for event in &events_without_pair {
+            assert_eq!(event_trading_pair(event), None, "{event:?}");
+        }
+    }
+
+    #[test]
+    fn event_parses_from_abci() {
+        let pair = pair();
+        let abci_event = pb::EventSwap::from(EventSwap {
+            trading_pair: pair,
+            delta_1_i: 1u64.into(),
+            delta_2_i: 0u64.into(),
+            swap_commitment: StateCommitment(Fq::from(1u64)),
+        })
+        .into_event();
+
+        let event = Event::try_from(&abci_event).unwrap();
+        assert_eq!(event_trading_pair(&event), Some(pair));
+
+        let unknown = abci::Event::new("penumbra.core.component.sct.v1.EventAnchor", vec![]);
+        assert!(Event::try_from(&unknown).is_err());
+    }
+
+    #[test]
+    fn event_pair_labels_fall_back_to_hex_ids() {
+        let cache = asset::Cache::with_known_assets();
+        let gm = cache.get_unit("gm").unwrap();
+        let unknown = asset::Id(Fq::from(12345u64));
+        let pair = TradingPair::new(gm.id(), unknown);
+        let event = Event::Swap(EventSwap {
+            trading_pair: pair,
+            delta_1_i: 1u64.into(),
+            delta_2_i: 0u64.into(),
+            swap_commitment: StateCommitment(Fq::from(1u64)),
+        });
+
+        let (label_1, label_2) = event_pair_labels(&event, &cache).unwrap();
+        let (gm_label, unknown_label) = if pair.asset_1() == gm.id() {
+            (label_1, label_2)
+        } else {
+            (label_2, label_1)
+        };
+        assert_eq!(gm_label, "ugm");
+        assert_eq!(unknown_label, hex::encode(unknown.to_bytes()));
+
+        let close = Event::PositionClose(EventPositionClose {
+            position_id: position::Id([0; 32]),
+        });
+        assert_eq!(event_pair_labels(&close, &cache), None);
+    }
+}
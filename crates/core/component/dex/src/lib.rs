@@ -15,9 +15,14 @@ mod trading_pair;
 
 pub use batch_swap_output_data::BatchSwapOutputData;
 pub use candlestick::CandlestickData;
-pub use params::DexParameters;
+pub use params::{
+    DexParameters, PairFeeFloor, PairSwapLimit, SwapTooLarge, MAX_BATCH_INTERVAL_BLOCKS,
+    MIN_BATCH_OUTPUT_COMPRESSION_BLOCKS, MIN_BATCH_OUTPUT_RETENTION_BLOCKS,
+};
 pub use swap_execution::SwapExecution;
-pub use trading_pair::{DirectedTradingPair, DirectedUnitPair, TradingPair, TradingPairVar};
+pub use trading_pair::{
+    DirectedTradingPair, DirectedUnitPair, IdenticalAssetsError, TradingPair, TradingPairVar,
+};
 
 pub mod lp;
 pub mod swap;
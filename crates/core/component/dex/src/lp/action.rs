@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use penumbra_asset::{balance, Balance, Value};
+use penumbra_asset::{balance, Balance, Value, STAKING_TOKEN_ASSET_ID};
+use penumbra_num::Amount;
 use penumbra_proto::{penumbra::core::component::dex::v1 as pb, DomainType};
 use penumbra_txhash::{EffectHash, EffectingData};
 
@@ -9,7 +10,7 @@ use super::{position, position::Position, LpNft};
 /// A transaction action that opens a new position.
 ///
 /// This action's contribution to the transaction's value balance is to consume
-/// the initial reserves and contribute an opened position NFT.
+/// the initial reserves and the open fee, and contribute an opened position NFT.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(try_from = "pb::PositionOpen", into = "pb::PositionOpen")]
 pub struct PositionOpen {
@@ -18,6 +19,10 @@ pub struct PositionOpen {
     /// Positions are immutable, so the `PositionData` (and hence the `PositionId`)
     /// are unchanged over the entire lifetime of the position.
     pub position: Position,
+    /// The fee paid to open the position, in the staking token, which is burned.
+    ///
+    /// This must cover the position open fee set in the DEX parameters.
+    pub open_fee: Amount,
 }
 
 impl EffectingData for PositionOpen {
@@ -38,8 +43,13 @@ impl PositionOpen {
 
         let reserves = self.position.reserves.balance(&self.position.phi.pair);
 
-        // The action consumes the reserves and produces an LP NFT
-        Balance::from(opened_position_nft) - reserves
+        let open_fee = Value {
+            amount: self.open_fee,
+            asset_id: *STAKING_TOKEN_ASSET_ID,
+        };
+
+        // The action consumes the reserves and the open fee, and produces an LP NFT
+        Balance::from(opened_position_nft) - reserves - open_fee
     }
 }
 
@@ -113,6 +123,9 @@ impl From<PositionOpen> for pb::PositionOpen {
     fn from(value: PositionOpen) -> Self {
         Self {
             position: Some(value.position.into()),
+            // Leaving out a zero fee keeps the encoding, and so the effect hash, of
+            // free position opens unchanged.
+            open_fee: (value.open_fee != Amount::zero()).then(|| value.open_fee.into()),
         }
     }
 }
@@ -126,6 +139,11 @@ impl TryFrom<pb::PositionOpen> for PositionOpen {
                 .position
                 .ok_or_else(|| anyhow::anyhow!("missing position"))?
                 .try_into()?,
+            open_fee: value
+                .open_fee
+                .map(TryInto::try_into)
+                .transpose()?
+                .unwrap_or_default(),
         })
     }
 }
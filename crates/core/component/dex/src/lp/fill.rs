@@ -0,0 +1,27 @@
+use penumbra_asset::Value;
+
+use super::position::Position;
+
+/// Fills a trade of an `input` value against `position`, exactly as the chain does,
+/// returning the output value and the position with its updated reserves.
+///
+/// This wraps [`Position::fill`], which the DEX applies to each position a swap is
+/// routed through, so a client that fills positions with it gets the same amounts,
+/// to the last unit, as the chain would. Only the position's reserves change.
+///
+/// If the position cannot absorb the whole input, it fills as much as it can, and the
+/// rest of the input is left unconsumed. Use [`Position::fill`] to get that amount.
+///
+/// # Errors
+/// This errors as [`Position::fill`] does, if the input asset is not one of the
+/// position's assets, or if the position's reserves would overflow.
+pub fn fill(position: &Position, input: Value) -> anyhow::Result<(Value, Position)> {
+    let (_unfilled, reserves, output) = position.fill(input)?;
+    Ok((
+        output,
+        Position {
+            reserves,
+            ..position.clone()
+        },
+    ))
+}
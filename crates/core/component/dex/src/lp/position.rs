@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Context};
 use penumbra_asset::{asset, Value};
-use penumbra_num::Amount;
+use penumbra_num::{fixpoint::U128x128, Amount};
 use penumbra_proto::{
     penumbra::core::component::dex::v1 as pb, serializers::bech32str, DomainType,
 };
@@ -17,6 +17,48 @@ pub const MAX_RESERVE_AMOUNT: u128 = (1 << 80) - 1;
 /// A trading function's fee (spread) must be at most 50% (5000 bps)
 pub const MAX_FEE_BPS: u32 = 5000;
 
+/// The reserve arithmetic that overflowed, in a [`ReserveOverflow`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReserveOperation {
+    /// Filling an input amount against the position.
+    Fill,
+    /// Filling the position to produce an output amount.
+    FillOutput,
+}
+
+impl std::fmt::Display for ReserveOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReserveOperation::Fill => f.write_str("fill"),
+            ReserveOperation::FillOutput => f.write_str("output fill"),
+        }
+    }
+}
+
+/// An error indicating that executing against a position would overflow its reserves.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("{operation} of position {position_id} would overflow its reserves of {asset_id}")]
+pub struct ReserveOverflow {
+    pub position_id: Id,
+    pub operation: ReserveOperation,
+    /// The asset whose reserves would overflow.
+    pub asset_id: asset::Id,
+}
+
+/// The error returned when opening a position whose ID is already taken.
+///
+/// A position's ID commits to its trading function and its nonce, so two positions
+/// with the same price, fee, and pair only collide if they also share a nonce. Two
+/// such positions can be opened side by side by giving each a distinct nonce, as
+/// [`Position::new`] does.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("position {id} already exists with state {state}, open it with a fresh nonce instead")]
+pub struct PositionIdCollision {
+    pub id: Id,
+    /// The state of the existing position.
+    pub state: State,
+}
+
 /// Encapsulates the immutable parts of the position (phi/nonce), along
 /// with the mutable parts (state/reserves).
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -100,6 +142,9 @@ impl Position {
     }
 
     /// Construct a new opened [Position] with a supplied random nonce.
+    ///
+    /// Positions built with the same nonce and parameters have the same ID, so only
+    /// one of them can be opened, and the others fail with a [`PositionIdCollision`].
     pub fn new_with_nonce(
         nonce: [u8; 32],
         pair: DirectedTradingPair,
@@ -199,6 +244,217 @@ impl Position {
         }
     }
 
+    /// Fills a trade of an `input` value against this position, returning the
+    /// unfilled amount of the input asset, the updated reserves, and the output
+    /// amount. See [`TradingFunction::fill`].
+    ///
+    /// # Errors
+    /// This method errors with a [`ReserveOverflow`] if the reserves of the input
+    /// asset would overflow, and otherwise as [`TradingFunction::fill`] does.
+    pub fn fill(&self, input: Value) -> anyhow::Result<(Value, Reserves, Value)> {
+        let (isolated, input_reserves) = self.isolate_reserves(input.asset_id)?;
+        let (unfilled, new_reserves, output) = self.phi.fill(input, &isolated)?;
+        let new_reserves = self.restore_reserves(
+            new_reserves,
+            input.asset_id,
+            input_reserves,
+            ReserveOperation::Fill,
+        )?;
+        Ok((unfilled, new_reserves, output))
+    }
+
+    /// Computes the input value required to produce the given `output` value,
+    /// returning the input value and updated reserves if successful. Returns
+    /// `None` if the output value exceeds the reserves. See
+    /// [`TradingFunction::fill_output`].
+    ///
+    /// # Errors
+    /// This method errors with a [`ReserveOverflow`] if the reserves of the input
+    /// asset would overflow, and otherwise as [`TradingFunction::fill_output`] does.
+    pub fn fill_output(&self, output: Value) -> anyhow::Result<Option<(Reserves, Value)>> {
+        let input_asset_id = if output.asset_id == self.phi.pair.asset_1() {
+            self.phi.pair.asset_2()
+        } else {
+            self.phi.pair.asset_1()
+        };
+        let (isolated, input_reserves) = self.isolate_reserves(input_asset_id)?;
+        let Some((new_reserves, input)) = self.phi.fill_output(&isolated, output)? else {
+            return Ok(None);
+        };
+        let new_reserves = self.restore_reserves(
+            new_reserves,
+            input_asset_id,
+            input_reserves,
+            ReserveOperation::FillOutput,
+        )?;
+        Ok(Some((new_reserves, input)))
+    }
+
+    /// Computes the impermanent loss of this position, as a fraction of the value of
+    /// simply holding its reserves, after the market price moved from `open_price`
+    /// to `current_price`.
+    ///
+    /// Prices are those of asset 1 of the trading pair in terms of asset 2. Only their
+    /// ratio matters, as the position is assumed to have been opened at the market price,
+    /// i.e. at the price of its trading function. Since a position trades at a constant
+    /// price, any price move sells all of the reserves of the appreciating asset, at the
+    /// position's price. Fees earned by the position are not accounted for.
+    ///
+    /// The result is zero if the price is unchanged, and otherwise lies in `[-1, 0]`.
+    pub fn impermanent_loss(&self, open_price: Amount, current_price: Amount) -> f64 {
+        if open_price == current_price {
+            return 0.0;
+        }
+
+        // The position's price of asset 1, in terms of asset 2.
+        let phi = &self.phi.component;
+        let price = phi.p.value() as f64 / phi.q.value() as f64;
+        // The market price of asset 1 after the move, and both reserves, in terms of asset 2.
+        let moved_price = price * (current_price.value() as f64 / open_price.value() as f64);
+        let value_at = |amount: Amount, price: f64| match amount.value() {
+            0 => 0.0,
+            amount => amount as f64 * price,
+        };
+        let r1 = self.reserves.r1;
+        let r2 = self.reserves.r2.value() as f64;
+
+        let held = value_at(r1, moved_price) + r2;
+        let provided = if current_price > open_price {
+            // Asset 1 appreciated: the position sold all of it at its price.
+            value_at(r1, price) + r2
+        } else {
+            // Asset 1 depreciated: the position bought it with all of asset 2 at its price.
+            value_at(r1, moved_price) + r2 / price * moved_price
+        };
+
+        if held == 0.0 || (!held.is_finite() && !provided.is_finite()) {
+            return 0.0;
+        }
+        (provided / held - 1.0).clamp(-1.0, 0.0)
+    }
+
+    /// Splits this position into a ladder of `steps` positions, whose prices are spread
+    /// evenly from `price_spread_bps` basis points below its price to as many above it.
+    ///
+    /// The reserves are split evenly across the ladder, with any remainder going to the
+    /// first step, so that the ladder holds exactly the reserves of this position. Each
+    /// step gets a distinct nonce derived from this position's. A `steps` of 0 or 1
+    /// returns this position unchanged.
+    pub fn split_ladder(&self, steps: usize, price_spread_bps: u32) -> Vec<Position> {
+        if steps <= 1 {
+            return vec![self.clone()];
+        }
+
+        // Keep the lowest price of the ladder above zero.
+        let spread = i128::from(price_spread_bps.min(9_999));
+        let last_step = (steps - 1) as i128;
+        let num_steps = steps as u128;
+        let share = |amount: Amount, step: usize| {
+            let amount = amount.value();
+            let remainder = if step == 0 { amount % num_steps } else { 0 };
+            Amount::from(amount / num_steps + remainder)
+        };
+
+        (0..steps)
+            .map(|step| {
+                let offset_bps = 2 * spread * step as i128 / last_step - spread;
+                let (p, q) = self.ladder_price(offset_bps);
+
+                let mut nonce = blake2b_simd::Params::default()
+                    .personal(b"penumbra_ladder")
+                    .to_state();
+                nonce.update(&self.nonce);
+                nonce.update(&(step as u64).to_le_bytes());
+                let mut nonce_bytes = [0u8; 32];
+                nonce_bytes.copy_from_slice(&nonce.finalize().as_bytes()[0..32]);
+
+                Position {
+                    state: self.state,
+                    reserves: Reserves {
+                        r1: share(self.reserves.r1, step),
+                        r2: share(self.reserves.r2, step),
+                    },
+                    phi: TradingFunction::new(self.phi.pair, self.phi.component.fee, p, q),
+                    nonce: nonce_bytes,
+                    close_on_fill: self.close_on_fill,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the `p` and `q` coefficients for this position's price, moved by
+    /// `offset_bps` basis points.
+    fn ladder_price(&self, offset_bps: i128) -> (Amount, Amount) {
+        let (p, q) = (self.phi.component.p.value(), self.phi.component.q.value());
+        let scale = (10_000 + offset_bps) as u128;
+
+        // Scale both coefficients to keep the price exact, if they stay in bounds, ...
+        let (exact_p, exact_q) = (p * scale, q * 10_000);
+        if exact_p <= MAX_RESERVE_AMOUNT && exact_q <= MAX_RESERVE_AMOUNT {
+            return (exact_p.into(), exact_q.into());
+        }
+        // ... and otherwise round the price, moving only `p`.
+        ((p * scale / 10_000).max(1).into(), q.into())
+    }
+
+    /// Returns the reserves of the position with those of `asset_id` zeroed out,
+    /// along with the zeroed out amount.
+    ///
+    /// The trading function only ever adds to the reserves of the input asset, so
+    /// filling against the isolated reserves cannot overflow, and the input actually
+    /// consumed is exactly the reserves of the input asset afterwards.
+    fn isolate_reserves(&self, asset_id: asset::Id) -> anyhow::Result<(Reserves, Amount)> {
+        let mut isolated = self.reserves.clone();
+        let reserves = if asset_id == self.phi.pair.asset_1() {
+            &mut isolated.r1
+        } else if asset_id == self.phi.pair.asset_2() {
+            &mut isolated.r2
+        } else {
+            anyhow::bail!(
+                "asset id {:?} did not match either end of trading pair {:?}",
+                asset_id,
+                self.phi.pair
+            );
+        };
+        Ok((isolated, std::mem::replace(reserves, Amount::zero())))
+    }
+
+    /// Adds back the `input_reserves` of `asset_id` set aside by [`Self::isolate_reserves`].
+    fn restore_reserves(
+        &self,
+        mut new_reserves: Reserves,
+        asset_id: asset::Id,
+        input_reserves: Amount,
+        operation: ReserveOperation,
+    ) -> anyhow::Result<Reserves> {
+        let reserves = if asset_id == self.phi.pair.asset_1() {
+            &mut new_reserves.r1
+        } else {
+            &mut new_reserves.r2
+        };
+        *reserves = reserves
+            .checked_add(&input_reserves)
+            .ok_or_else(|| ReserveOverflow {
+                position_id: self.id(),
+                operation,
+                asset_id,
+            })?;
+        Ok(new_reserves)
+    }
+
+    /// Returns the effective price, inclusive of fees, at which this position sells
+    /// `pair.end` for `pair.start`, in units of `pair.start` per unit of `pair.end`.
+    ///
+    /// Prices are in terms of base units. Returns `None` if the position is not on `pair`.
+    pub fn effective_price(&self, pair: &DirectedTradingPair) -> Option<U128x128> {
+        if TradingPair::from(*pair) != self.phi.pair {
+            return None;
+        }
+        self.phi
+            .orient_start(pair.start)
+            .map(|phi| phi.effective_price())
+    }
+
     /// Returns the amount of reserves for asset 1.
     pub fn reserves_1(&self) -> Value {
         Value {
@@ -216,6 +472,49 @@ impl Position {
     }
 }
 
+/// A price threshold selecting positions for a bulk close, e.g. to pull all the
+/// positions on one side of the book when the market moves against them.
+///
+/// Thresholds are exclusive: a position priced exactly at the threshold is not selected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceThreshold {
+    /// Select positions with an effective price strictly below the threshold.
+    Below(U128x128),
+    /// Select positions with an effective price strictly above the threshold.
+    Above(U128x128),
+}
+
+impl PriceThreshold {
+    /// Whether a position with the given effective price is selected.
+    pub fn selects(&self, price: U128x128) -> bool {
+        match self {
+            PriceThreshold::Below(threshold) => price < *threshold,
+            PriceThreshold::Above(threshold) => price > *threshold,
+        }
+    }
+}
+
+/// Returns the IDs of the opened `positions` on `pair` whose effective price, as
+/// defined by [`Position::effective_price`], is selected by the `threshold`.
+///
+/// Positions on other pairs, and positions that are not opened, are never selected.
+pub fn positions_beyond_price<'a>(
+    positions: impl IntoIterator<Item = &'a Position>,
+    pair: &DirectedTradingPair,
+    threshold: PriceThreshold,
+) -> Vec<Id> {
+    positions
+        .into_iter()
+        .filter(|position| position.state == State::Opened)
+        .filter(|position| {
+            position
+                .effective_price(pair)
+                .is_some_and(|price| threshold.selects(price))
+        })
+        .map(Position::id)
+        .collect()
+}
+
 /// A hash of a [`Position`].
 #[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize)]
 #[serde(try_from = "pb::PositionId", into = "pb::PositionId")]
@@ -272,6 +571,26 @@ pub enum State {
     },
 }
 
+impl State {
+    /// Returns whether a position in this state may move to the `next` state.
+    ///
+    /// The lifecycle of a position is `Opened -> Closed -> Withdrawn { 0 } ->
+    /// Withdrawn { 1 } -> ...`, where each withdrawal increments the sequence
+    /// number by exactly one. Staying in `Opened` or `Closed` is allowed, while
+    /// skipping or reverting states is not.
+    pub fn can_transition_to(&self, next: State) -> bool {
+        use State::*;
+        match (*self, next) {
+            (Opened, Opened) | (Opened, Closed) | (Closed, Closed) => true,
+            (Closed, Withdrawn { sequence }) => sequence == 0,
+            (Withdrawn { sequence: old }, Withdrawn { sequence: new }) => {
+                old.saturating_add(1) == new
+            }
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for State {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -305,6 +624,57 @@ impl std::str::FromStr for State {
     }
 }
 
+/// The block heights bounding the period during which a position was open.
+///
+/// These are recorded by the chain as the position moves through its
+/// lifecycle, and are not part of the [`Position`] submitted by its owner.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Lifespan {
+    /// The height of the block in which the position was opened.
+    pub open_height: u64,
+    /// The height of the block in which the position was closed, if it has been.
+    pub close_height: Option<u64>,
+}
+
+impl Lifespan {
+    /// Returns the height of the block in which the position was opened.
+    pub fn open_height(&self) -> u64 {
+        self.open_height
+    }
+
+    /// Returns the height of the block in which the position was closed, if any.
+    pub fn close_height(&self) -> Option<u64> {
+        self.close_height
+    }
+
+    /// Returns the number of blocks the position has been active for, as of `current_height`.
+    ///
+    /// A closed position stops aging at its close height, so this is the duration
+    /// it provided liquidity for, regardless of how much later it is queried.
+    pub fn age(&self, current_height: u64) -> u64 {
+        let end_height = match self.close_height {
+            Some(close_height) => close_height.min(current_height),
+            None => current_height,
+        };
+        end_height.saturating_sub(self.open_height)
+    }
+}
+
+/// The block heights of the stages of a position's lifecycle.
+///
+/// Positions cannot be modified once opened, so a position only ever goes through
+/// these three stages, each of which is `None` until the position reaches it.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct PositionTimeline {
+    /// The height of the block in which the position was opened.
+    pub open_height: u64,
+    /// The height of the block in which the position was closed, if it has been.
+    pub close_height: Option<u64>,
+    /// The height of the block in which the position was first withdrawn from, if it
+    /// has been.
+    pub withdraw_height: Option<u64>,
+}
+
 // ==== Protobuf impls
 
 impl DomainType for Position {
@@ -455,6 +825,162 @@ mod test {
         assert!("withdrawn_1_2_3".parse::<State>().is_err());
     }
 
+    #[test]
+    fn fill_near_max_reserves_overflows_cleanly() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let pair = DirectedTradingPair::new(gm.id(), gn.id());
+        let position = Position::new(
+            OsRng,
+            pair,
+            0u32,
+            1u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: (u128::MAX - 10).into(),
+                r2: 100u64.into(),
+            },
+        );
+        let gm_in = |amount: u64| Value {
+            amount: amount.into(),
+            asset_id: gm.id(),
+        };
+
+        // There is just enough headroom for a small trade.
+        let (unfilled, new_reserves, output) = position.fill(gm_in(10)).unwrap();
+        assert_eq!(unfilled.amount, Amount::zero());
+        assert_eq!(output.amount, 10u64.into());
+        let filled = Position {
+            reserves: new_reserves,
+            ..position.clone()
+        };
+        assert_eq!(filled.reserves_for(gm.id()), Some(u128::MAX.into()));
+
+        let expected = ReserveOverflow {
+            position_id: position.id(),
+            operation: ReserveOperation::Fill,
+            asset_id: gm.id(),
+        };
+        let error = position.fill(gm_in(11)).unwrap_err();
+        assert_eq!(error.downcast_ref::<ReserveOverflow>(), Some(&expected));
+
+        let gn_out = Value {
+            amount: 11u64.into(),
+            asset_id: gn.id(),
+        };
+        let error = position.fill_output(gn_out).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<ReserveOverflow>(),
+            Some(&ReserveOverflow {
+                operation: ReserveOperation::FillOutput,
+                ..expected
+            })
+        );
+    }
+
+    #[test]
+    fn impermanent_loss() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let pair = TradingPair::new(gm.id(), gn.id());
+        // A position selling 100 of asset 1 at 2 of asset 2 each, and buying it with 200 of asset 2.
+        let position = Position::new(
+            OsRng,
+            DirectedTradingPair::new(pair.asset_1(), pair.asset_2()),
+            0u32,
+            2u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: 100u64.into(),
+                r2: 200u64.into(),
+            },
+        );
+
+        assert_eq!(position.impermanent_loss(2u64.into(), 2u64.into()), 0.0);
+        // At twice the price, the position holds 400 of asset 2, holding would be worth 600.
+        let loss = position.impermanent_loss(2u64.into(), 4u64.into());
+        assert!((loss + 1.0 / 3.0).abs() < 1e-12, "unexpected loss {loss}");
+        // At half the price, the position holds 200 of asset 1, worth 200, versus 300.
+        let loss = position.impermanent_loss(2u64.into(), 1u64.into());
+        assert!((loss + 1.0 / 3.0).abs() < 1e-12, "unexpected loss {loss}");
+
+        // Extreme moves lose at most everything, and never produce NaN.
+        for (open, current) in [(1u128, u128::MAX), (u128::MAX, 1), (0, 1), (1, 0)] {
+            let loss = position.impermanent_loss(open.into(), current.into());
+            assert!((-1.0..=0.0).contains(&loss), "unexpected loss {loss}");
+        }
+    }
+
+    #[test]
+    fn split_ladder_conserves_reserves() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let position = Position::new(
+            OsRng,
+            DirectedTradingPair::new(gm.id(), gn.id()),
+            30u32,
+            3u64.into(),
+            2u64.into(),
+            Reserves {
+                r1: 1_000_003u64.into(),
+                r2: 42u64.into(),
+            },
+        );
+
+        let ladder = position.split_ladder(5, 500);
+        assert_eq!(ladder.len(), 5);
+
+        let total = |reserves: fn(&Position) -> Amount| {
+            ladder
+                .iter()
+                .map(reserves)
+                .fold(Amount::zero(), |a, b| a + b)
+        };
+        assert_eq!(total(|p| p.reserves.r1), position.reserves.r1);
+        assert_eq!(total(|p| p.reserves.r2), position.reserves.r2);
+
+        // The prices ascend across the ladder, through the original price.
+        let price = |p: &Position| (p.phi.component.p.value(), p.phi.component.q.value());
+        for step in ladder.windows(2) {
+            let ((p1, q1), (p2, q2)) = (price(&step[0]), price(&step[1]));
+            assert!(p1 * q2 < p2 * q1, "ladder prices should ascend");
+        }
+        let ((p, q), (mid_p, mid_q)) = (price(&position), price(&ladder[2]));
+        assert_eq!(
+            p * mid_q,
+            mid_p * q,
+            "middle step should keep the original price"
+        );
+
+        // Each step is a distinct position.
+        let ids: std::collections::BTreeSet<_> = ladder.iter().map(|p| p.id()).collect();
+        assert_eq!(ids.len(), 5);
+    }
+
+    #[test]
+    fn split_ladder_with_one_step_is_unchanged() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let position = Position::new(
+            OsRng,
+            DirectedTradingPair::new(gm.id(), gn.id()),
+            30u32,
+            1u64.into(),
+            1u64.into(),
+            Reserves {
+                r1: 100u64.into(),
+                r2: 100u64.into(),
+            },
+        );
+
+        for steps in [0, 1] {
+            let ladder = position.split_ladder(steps, 500);
+            assert_eq!(ladder.len(), 1);
+            assert_eq!(ladder[0].id(), position.id());
+            assert_eq!(ladder[0].reserves, position.reserves);
+        }
+    }
+
     fn assert_position_similar(p1: Position, p2: Position) {
         assert_eq!(p1.reserves.r1, p2.reserves.r1);
         assert_eq!(p1.reserves.r2, p2.reserves.r2);
@@ -592,4 +1118,119 @@ mod test {
         assert_position_similar(b_position_3, a_position_2);
         assert_position_similar(b_position_4, a_position_1);
     }
+
+    #[test]
+    fn position_state_transitions() {
+        use State::*;
+        let states = [
+            Opened,
+            Closed,
+            Withdrawn { sequence: 0 },
+            Withdrawn { sequence: 1 },
+            Withdrawn { sequence: 2 },
+        ];
+        // Rows are the current state, columns the next state.
+        let expected = [
+            [true, true, false, false, false],
+            [false, true, true, false, false],
+            [false, false, false, true, false],
+            [false, false, false, false, true],
+            [false, false, false, false, false],
+        ];
+        for (i, current) in states.iter().enumerate() {
+            for (j, next) in states.iter().enumerate() {
+                assert_eq!(
+                    current.can_transition_to(*next),
+                    expected[i][j],
+                    "unexpected result for {current} -> {next}"
+                );
+            }
+        }
+
+        // The sequence number saturates rather than wrapping around.
+        assert!(!Withdrawn { sequence: u64::MAX }.can_transition_to(Withdrawn { sequence: 0 }));
+        assert!(
+            Withdrawn { sequence: u64::MAX }.can_transition_to(Withdrawn { sequence: u64::MAX })
+        );
+    }
+
+    #[test]
+    fn positions_beyond_price_selects_one_side_of_a_ladder() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        // Sells of gm for gn, priced in gn per gm.
+        let pair = DirectedTradingPair::new(gn.id(), gm.id());
+
+        let ladder: Vec<Position> = (1..=5)
+            .map(|price| {
+                crate::lp::SellOrder::parse_str(&format!("1gm@{price}gn"))
+                    .unwrap()
+                    .into_position(OsRng)
+            })
+            .collect();
+        for (position, price) in ladder.iter().zip(1u64..) {
+            assert_eq!(position.effective_price(&pair), Some(price.into()));
+        }
+
+        // A position on another pair, and a closed position, are left alone.
+        let other_pair = crate::lp::SellOrder::parse_str("1gm@1penumbra")
+            .unwrap()
+            .into_position(OsRng);
+        assert_eq!(other_pair.effective_price(&pair), None);
+        let mut closed = ladder[0].clone();
+        closed.nonce = [9u8; 32];
+        closed.state = State::Closed;
+
+        let positions: Vec<&Position> = ladder.iter().chain([&other_pair, &closed]).collect();
+        let ids = |threshold| positions_beyond_price(positions.iter().copied(), &pair, threshold);
+
+        // The position priced exactly at the threshold is kept open, on either side.
+        assert_eq!(
+            ids(PriceThreshold::Below(3u64.into())),
+            vec![ladder[0].id(), ladder[1].id()]
+        );
+        assert_eq!(
+            ids(PriceThreshold::Above(3u64.into())),
+            vec![ladder[3].id(), ladder[4].id()]
+        );
+        assert!(ids(PriceThreshold::Below(1u64.into())).is_empty());
+    }
+
+    #[test]
+    fn position_id_string_round_trip() {
+        let id = Id([7u8; 32]);
+        let encoded = id.to_string();
+        assert!(encoded.starts_with("plpid1"));
+        assert_eq!(encoded.parse::<Id>().unwrap(), id);
+        assert_eq!(format!("{id:?}"), encoded);
+    }
+
+    #[test]
+    fn position_id_fromstr_rejects_invalid_strings() {
+        let encoded = Id([7u8; 32]).to_string();
+
+        // A corrupted checksum must be rejected.
+        let mut corrupted = encoded.clone();
+        let last = corrupted.pop().unwrap();
+        corrupted.push(if last == 'q' { 'p' } else { 'q' });
+        assert!(corrupted.parse::<Id>().is_err());
+
+        // As must a valid bech32m string with the wrong HRP, ...
+        let wrong_hrp = bech32str::encode(
+            &[7u8; 32],
+            bech32str::auction_id::BECH32_PREFIX,
+            bech32str::Bech32m,
+        );
+        let err = wrong_hrp.parse::<Id>().unwrap_err();
+        assert!(err.to_string().contains("human readable part"));
+
+        // ... or the wrong length.
+        let short = bech32str::encode(
+            &[7u8; 31],
+            bech32str::lp_id::BECH32_PREFIX,
+            bech32str::Bech32m,
+        );
+        assert!(short.parse::<Id>().is_err());
+        assert!("".parse::<Id>().is_err());
+    }
 }
@@ -265,7 +265,10 @@ impl BareTradingFunction {
             .expect("rounded up to integral value");
 
         let new_reserves = Reserves {
-            r1: reserves.r1 + fillable_delta_1_exact,
+            r1: reserves
+                .r1
+                .checked_add(&fillable_delta_1_exact)
+                .ok_or_else(|| anyhow!("overflow adding the input to the reserves"))?,
             // We checked that lambda_2 <= reserves.r2 above.
             r2: reserves.r2 - lambda_2,
         };
@@ -330,7 +333,10 @@ impl BareTradingFunction {
                 .try_into()
                 .expect("lambda_2 fits in an Amount");
             let new_reserves = Reserves {
-                r1: reserves.r1 + delta_1,
+                r1: reserves
+                    .r1
+                    .checked_add(&delta_1)
+                    .ok_or_else(|| anyhow!("overflow adding the input to the reserves"))?,
                 r2: reserves.r2 - lambda_2,
             };
             Ok((0u64.into(), new_reserves, lambda_2))
@@ -380,7 +386,10 @@ impl BareTradingFunction {
             let unfilled_amount = delta_1 - fillable_delta_1_exact;
 
             let new_reserves = Reserves {
-                r1: reserves.r1 + fillable_delta_1_exact,
+                r1: reserves
+                    .r1
+                    .checked_add(&fillable_delta_1_exact)
+                    .ok_or_else(|| anyhow!("overflow adding the input to the reserves"))?,
                 r2: 0u64.into(),
             };
             Ok((unfilled_amount, new_reserves, reserves.r2))
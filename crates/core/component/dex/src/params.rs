@@ -1,8 +1,27 @@
 use penumbra_asset::{asset, STAKING_TOKEN_ASSET_ID};
+use penumbra_num::Amount;
 use penumbra_proto::penumbra::core::component::dex::v1 as pb;
 use penumbra_proto::DomainType;
 use serde::{Deserialize, Serialize};
 
+use crate::TradingPair;
+
+/// The shortest window, in blocks, for which batch swap output data is retained if it is
+/// pruned at all.
+///
+/// Swaps can be claimed at any time, so the output data of a batch whose swaps were not
+/// all claimed is retained past the window, until the last of them is.
+pub const MIN_BATCH_OUTPUT_RETENTION_BLOCKS: u64 = 100_800;
+
+/// The shortest window, in blocks, for which batch swap output data is kept in full if
+/// it is compressed at all, so that the batches swaps are usually claimed from, and
+/// those included in DEX snapshots, can be read without decoding.
+pub const MIN_BATCH_OUTPUT_COMPRESSION_BLOCKS: u64 = 100;
+
+/// The longest interval, in blocks, between batch swap executions, so that a deferred
+/// swap never waits long to clear.
+pub const MAX_BATCH_INTERVAL_BLOCKS: u32 = 100;
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(try_from = "pb::DexParameters", into = "pb::DexParameters")]
 /// The configuration parameters for the DEX component.
@@ -12,6 +31,221 @@ pub struct DexParameters {
     pub max_hops: u32,
     pub max_positions_per_pair: u32,
     pub max_execution_budget: u32,
+    /// The number of blocks for which batch swap output data is retained, or zero to
+    /// retain it forever.
+    pub batch_output_retention_blocks: u64,
+    /// The minimum fee, in basis points, of positions opened on pairs without a fee floor.
+    pub min_fee_bps: u32,
+    /// Fee floors for specific trading pairs, overriding `min_fee_bps` on those pairs.
+    pub pair_fee_floors: Vec<PairFeeFloor>,
+    /// Limits on the size of a single swap on specific trading pairs.
+    pub pair_swap_limits: Vec<PairSwapLimit>,
+    /// The number of blocks between batch swap executions, where zero or one
+    /// executes batches every block.
+    pub batch_interval_blocks: u32,
+    /// The minimum number of blocks between the execution of a batch and claims of the
+    /// swaps in it, or zero to allow claims in any later block.
+    pub swap_claim_grace_blocks: u64,
+    /// The fee, in the staking token, charged to open a position, or zero to allow
+    /// positions to be opened for free.
+    pub position_open_fee: Amount,
+    /// The minimum reserves, on each side, of the first position opened on a pair, or
+    /// zero for no minimum.
+    ///
+    /// This only applies while the pair has no open positions. Once a market for the
+    /// pair is established, positions of any size can be added to it.
+    pub pair_bootstrap_min_reserves: Amount,
+    /// The number of blocks after which batch swap output data is stored in a compact
+    /// encoding, or zero to always store it in full.
+    pub batch_output_compression_blocks: u64,
+    /// The assets in which swap claims may pay their fees, where an empty list allows
+    /// only the staking token.
+    pub swap_claim_fee_assets: Vec<asset::Id>,
+}
+
+impl DexParameters {
+    /// Returns whether batch swaps are executed at the end of the block at `height`.
+    ///
+    /// Swaps submitted in any other block are deferred until the next such block.
+    pub fn executes_batches_at(&self, height: u64) -> bool {
+        self.batch_interval_blocks <= 1 || height % u64::from(self.batch_interval_blocks) == 0
+    }
+
+    /// Returns whether a swap in the batch executed at `output_height` can be claimed in
+    /// the block at `height`.
+    ///
+    /// Claims are allowed from the end of the grace period onwards, inclusive.
+    pub fn swap_claimable_at(&self, output_height: u64, height: u64) -> bool {
+        height >= output_height.saturating_add(self.swap_claim_grace_blocks)
+    }
+
+    /// Returns whether the output data of the batch executed at `output_height` is past
+    /// its retention window in the block at `height`.
+    ///
+    /// If the output data is retained forever, it never is.
+    pub fn batch_output_expired_at(&self, output_height: u64, height: u64) -> bool {
+        if self.batch_output_retention_blocks == 0 {
+            return false;
+        }
+        let retention_blocks = self
+            .batch_output_retention_blocks
+            .max(MIN_BATCH_OUTPUT_RETENTION_BLOCKS);
+        height >= output_height.saturating_add(retention_blocks)
+    }
+
+    /// Returns whether a swap claim may pay its fee in `asset_id`.
+    ///
+    /// An empty list of fee assets allows only the staking token, rather than no assets.
+    pub fn allows_swap_claim_fee_asset(&self, asset_id: asset::Id) -> bool {
+        if self.swap_claim_fee_assets.is_empty() {
+            asset_id == *STAKING_TOKEN_ASSET_ID
+        } else {
+            self.swap_claim_fee_assets.contains(&asset_id)
+        }
+    }
+
+    /// Returns whether a swap claim from the batch executed at `output_height` may pay
+    /// its fee in any asset, because the swap was made before the swap claim fee assets
+    /// in effect since `since` applied.
+    ///
+    /// The claim fee is fixed when the swap is made, so a swap made under the previous
+    /// fee assets could otherwise never be claimed. A swap executes in the first batch
+    /// at or after the block it is made in, at most a batch interval past `since`.
+    pub fn grandfathers_swap_claim_fee(&self, output_height: u64, since: u64) -> bool {
+        output_height < since.saturating_add(u64::from(self.batch_interval_blocks.max(1)))
+    }
+
+    /// Returns the minimum fee, in basis points, of positions opened on `pair`.
+    pub fn min_fee_bps_for(&self, pair: &TradingPair) -> u32 {
+        self.pair_fee_floors
+            .iter()
+            .find(|floor| floor.pair == *pair)
+            .map_or(self.min_fee_bps, |floor| floor.min_fee_bps)
+    }
+
+    /// Checks that a swap of `delta_1` and `delta_2` on `pair` is within the pair's
+    /// swap size limit, if it has one.
+    pub fn check_swap_size(
+        &self,
+        pair: &TradingPair,
+        delta_1: Amount,
+        delta_2: Amount,
+    ) -> Result<(), SwapTooLarge> {
+        let Some(limit) = self
+            .pair_swap_limits
+            .iter()
+            .find(|limit| limit.pair == *pair)
+        else {
+            return Ok(());
+        };
+        for (asset_id, input, max) in [
+            (pair.asset_1(), delta_1, limit.max_delta_1),
+            (pair.asset_2(), delta_2, limit.max_delta_2),
+        ] {
+            if max != Amount::zero() && input > max {
+                return Err(SwapTooLarge {
+                    asset_id,
+                    input,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error returned when a swap exceeds the swap size limit of its trading pair.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("swap input of {input} {asset_id} exceeds the maximum of {max} for its pair")]
+pub struct SwapTooLarge {
+    pub asset_id: asset::Id,
+    pub input: Amount,
+    pub max: Amount,
+}
+
+/// The minimum fee of positions opened on a trading pair.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "pb::PairFeeFloor", into = "pb::PairFeeFloor")]
+pub struct PairFeeFloor {
+    pub pair: TradingPair,
+    pub min_fee_bps: u32,
+}
+
+impl DomainType for PairFeeFloor {
+    type Proto = pb::PairFeeFloor;
+}
+
+impl TryFrom<pb::PairFeeFloor> for PairFeeFloor {
+    type Error = anyhow::Error;
+
+    fn try_from(msg: pb::PairFeeFloor) -> anyhow::Result<Self> {
+        Ok(PairFeeFloor {
+            pair: msg
+                .pair
+                .ok_or_else(|| anyhow::anyhow!("missing pair"))?
+                .try_into()?,
+            min_fee_bps: msg.min_fee_bps,
+        })
+    }
+}
+
+impl From<PairFeeFloor> for pb::PairFeeFloor {
+    fn from(floor: PairFeeFloor) -> Self {
+        pb::PairFeeFloor {
+            pair: Some(floor.pair.into()),
+            min_fee_bps: floor.min_fee_bps,
+        }
+    }
+}
+
+/// The maximum input of a single swap on a trading pair.
+///
+/// A zero maximum leaves the input of that asset uncapped.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "pb::PairSwapLimit", into = "pb::PairSwapLimit")]
+pub struct PairSwapLimit {
+    pub pair: TradingPair,
+    /// The maximum input of asset 1 of the pair.
+    pub max_delta_1: Amount,
+    /// The maximum input of asset 2 of the pair.
+    pub max_delta_2: Amount,
+}
+
+impl DomainType for PairSwapLimit {
+    type Proto = pb::PairSwapLimit;
+}
+
+impl TryFrom<pb::PairSwapLimit> for PairSwapLimit {
+    type Error = anyhow::Error;
+
+    fn try_from(msg: pb::PairSwapLimit) -> anyhow::Result<Self> {
+        Ok(PairSwapLimit {
+            pair: msg
+                .pair
+                .ok_or_else(|| anyhow::anyhow!("missing pair"))?
+                .try_into()?,
+            max_delta_1: msg
+                .max_delta_1
+                .map(TryInto::try_into)
+                .transpose()?
+                .unwrap_or_default(),
+            max_delta_2: msg
+                .max_delta_2
+                .map(TryInto::try_into)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl From<PairSwapLimit> for pb::PairSwapLimit {
+    fn from(limit: PairSwapLimit) -> Self {
+        pb::PairSwapLimit {
+            pair: Some(limit.pair.into()),
+            max_delta_1: Some(limit.max_delta_1.into()),
+            max_delta_2: Some(limit.max_delta_2.into()),
+        }
+    }
 }
 
 impl DomainType for DexParameters {
@@ -32,6 +266,36 @@ impl TryFrom<pb::DexParameters> for DexParameters {
             max_hops: msg.max_hops,
             max_positions_per_pair: msg.max_positions_per_pair,
             max_execution_budget: msg.max_execution_budget,
+            batch_output_retention_blocks: msg.batch_output_retention_blocks,
+            min_fee_bps: msg.min_fee_bps,
+            pair_fee_floors: msg
+                .pair_fee_floors
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            pair_swap_limits: msg
+                .pair_swap_limits
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            batch_interval_blocks: msg.batch_interval_blocks,
+            swap_claim_grace_blocks: msg.swap_claim_grace_blocks,
+            position_open_fee: msg
+                .position_open_fee
+                .map(TryInto::try_into)
+                .transpose()?
+                .unwrap_or_default(),
+            pair_bootstrap_min_reserves: msg
+                .pair_bootstrap_min_reserves
+                .map(TryInto::try_into)
+                .transpose()?
+                .unwrap_or_default(),
+            batch_output_compression_blocks: msg.batch_output_compression_blocks,
+            swap_claim_fee_assets: msg
+                .swap_claim_fee_assets
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
         })
     }
 }
@@ -48,6 +312,24 @@ impl From<DexParameters> for pb::DexParameters {
             max_hops: params.max_hops,
             max_positions_per_pair: params.max_positions_per_pair,
             max_execution_budget: params.max_execution_budget,
+            batch_output_retention_blocks: params.batch_output_retention_blocks,
+            min_fee_bps: params.min_fee_bps,
+            pair_fee_floors: params.pair_fee_floors.into_iter().map(Into::into).collect(),
+            pair_swap_limits: params
+                .pair_swap_limits
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            batch_interval_blocks: params.batch_interval_blocks,
+            swap_claim_grace_blocks: params.swap_claim_grace_blocks,
+            position_open_fee: Some(params.position_open_fee.into()),
+            pair_bootstrap_min_reserves: Some(params.pair_bootstrap_min_reserves.into()),
+            batch_output_compression_blocks: params.batch_output_compression_blocks,
+            swap_claim_fee_assets: params
+                .swap_claim_fee_assets
+                .into_iter()
+                .map(Into::into)
+                .collect(),
         }
     }
 }
@@ -72,6 +354,16 @@ impl Default for DexParameters {
             max_hops: 4,
             max_positions_per_pair: 1_000,
             max_execution_budget: 64,
+            batch_output_retention_blocks: 0,
+            min_fee_bps: 0,
+            pair_fee_floors: Vec::new(),
+            pair_swap_limits: Vec::new(),
+            batch_interval_blocks: 1,
+            swap_claim_grace_blocks: 0,
+            position_open_fee: Amount::zero(),
+            pair_bootstrap_min_reserves: Amount::zero(),
+            batch_output_compression_blocks: 0,
+            swap_claim_fee_assets: Vec::new(),
         }
     }
 }
@@ -10,8 +10,28 @@ pub mod config {
     }
 }
 
+/// The height from which the current swap claim fee assets apply.
+pub fn swap_claim_fee_assets_since() -> &'static str {
+    "dex/swap_claim_fee_assets_since"
+}
+
+/// A swap claim fee asset, as recorded when the swap claim fee assets last changed.
+pub fn swap_claim_fee_asset(asset_id: &asset::Id) -> String {
+    format!("{}{asset_id}", swap_claim_fee_assets_prefix())
+}
+
+/// The prefix of the swap claim fee assets recorded when they last changed.
+pub fn swap_claim_fee_assets_prefix() -> &'static str {
+    "dex/swap_claim_fee_assets/"
+}
+
 pub fn value_balance(asset_id: &asset::Id) -> String {
-    format!("dex/value_balance/{asset_id}")
+    format!("{}{asset_id}", value_balance_prefix())
+}
+
+/// The prefix of the value circuit breaker balances of all assets.
+pub fn value_balance_prefix() -> &'static str {
+    "dex/value_balance/"
 }
 
 pub fn positions(trading_pair: &TradingPair, position_id: &str) -> String {
@@ -28,6 +48,60 @@ pub fn all_positions() -> &'static str {
     "dex/position/"
 }
 
+/// The height at which a position was opened.
+pub fn position_open_height(id: &position::Id) -> String {
+    format!("dex/position_lifespan/open/{id}")
+}
+
+/// The height at which a position was closed.
+pub fn position_close_height(id: &position::Id) -> String {
+    format!("dex/position_lifespan/close/{id}")
+}
+
+/// The height at which a position was first withdrawn from.
+pub fn position_withdraw_height(id: &position::Id) -> String {
+    format!("dex/position_lifespan/withdraw/{id}")
+}
+
+/// The position whose LPNFT has the given asset ID.
+pub fn position_by_nft(nft_asset_id: &asset::Id) -> String {
+    format!("dex/position_by_nft/{nft_asset_id}")
+}
+
+/// A slot in the ring of volume buckets for an asset.
+pub fn asset_volume_slot(asset_id: &asset::Id, slot: u64) -> String {
+    format!("dex/asset_volume/{asset_id}/{slot:03}")
+}
+
+/// A slot in the ring of turnover buckets for a position.
+pub fn position_turnover_slot(id: &position::Id, slot: u64) -> String {
+    format!("dex/position_turnover/{id}/{slot:03}")
+}
+
+/// The reserves of a position at the end of an epoch.
+pub fn position_reserves_at_epoch(id: &position::Id, epoch: u64) -> String {
+    format!("{}{id}", position_reserves_at_epoch_prefix(epoch))
+}
+
+/// The prefix of the reserves of every position at the end of an epoch.
+pub fn position_reserves_at_epoch_prefix(epoch: u64) -> String {
+    format!("dex/position_reserves_at_epoch/{epoch:020}/")
+}
+
+pub mod fee_revenue {
+    use penumbra_asset::asset;
+
+    /// The prefix for the fees accrued by positions during an epoch.
+    pub fn by_epoch(epoch: u64) -> String {
+        format!("dex/fee_revenue/{epoch:020}/")
+    }
+
+    /// The fees accrued by positions in an asset during an epoch.
+    pub fn by_epoch_and_asset(epoch: u64, asset_id: &asset::Id) -> String {
+        format!("{}{asset_id}", by_epoch(epoch))
+    }
+}
+
 pub mod candlesticks {
 
     pub mod object {
@@ -78,6 +152,26 @@ pub fn output_data(height: u64, trading_pair: TradingPair) -> String {
     )
 }
 
+/// The prefix of the output data of all batches executed at `height`.
+pub fn output_data_at_height(height: u64) -> String {
+    format!("dex/output/{height:020}/")
+}
+
+/// The compact encoding of the output data of a batch that is no longer kept in full.
+pub fn output_data_compact(height: u64, trading_pair: TradingPair) -> String {
+    format!(
+        "dex/output_compact/{:020}/{}/{}",
+        height,
+        &trading_pair.asset_1(),
+        &trading_pair.asset_2()
+    )
+}
+
+/// The prefix of the compact output data of all batches executed at `height`.
+pub fn output_data_compact_at_height(height: u64) -> String {
+    format!("dex/output_compact/{height:020}/")
+}
+
 pub fn swap_execution(height: u64, trading_pair: DirectedTradingPair) -> String {
     format!(
         "dex/swap_execution/{:020}/{}/{}",
@@ -101,6 +195,48 @@ pub fn swap_flows() -> &'static str {
     "dex/swap_flows"
 }
 
+pub fn claimable_swaps() -> &'static str {
+    "dex/claimable_swaps"
+}
+
+/// The height at which a swap claim with the given proof hash was accepted.
+pub fn swap_claim_proof(proof_hash: &[u8; 32]) -> String {
+    format!("dex/swap_claim_proof/{}", hex::encode(proof_hash))
+}
+
+/// The prefix of the hashes of the swap claim proofs accepted at `height`.
+pub fn swap_claim_proofs_at_height(height: u64) -> String {
+    format!("dex/swap_claim_proof_at_height/{height:020}/")
+}
+
+/// The hash of a swap claim proof accepted at `height`, indexing its record by height.
+pub fn swap_claim_proof_at_height(height: u64, proof_hash: &[u8; 32]) -> String {
+    format!(
+        "{}{}",
+        swap_claim_proofs_at_height(height),
+        hex::encode(proof_hash)
+    )
+}
+
+pub mod unclaimed_swaps {
+    use crate::TradingPair;
+
+    /// The number of unclaimed swaps of the batch of a pair executed at a height.
+    pub fn count(pair: &TradingPair, height: u64) -> String {
+        format!("dex/unclaimed_swaps/count/{pair}/{height:020}")
+    }
+
+    /// The prefix for the inputs of the unclaimed swaps of the batches of a pair.
+    pub fn inputs_by_pair(pair: &TradingPair) -> String {
+        format!("dex/unclaimed_swaps/inputs/{pair}/")
+    }
+
+    /// The inputs of the unclaimed swaps of the batch of a pair executed at a height.
+    pub fn inputs(pair: &TradingPair, height: u64) -> String {
+        format!("{}{height:020}", inputs_by_pair(pair))
+    }
+}
+
 pub fn pending_position_closures() -> &'static str {
     "dex/pending_position_closures"
 }
@@ -121,6 +257,39 @@ pub fn aggregate_value() -> &'static str {
     "dex/aggregate_value"
 }
 
+pub(crate) mod deferred_swaps {
+    /// The prefix of the bodies of swaps deferred to the next batch execution.
+    pub(crate) fn prefix() -> &'static str {
+        "dex/deferred_swaps/body/"
+    }
+
+    /// The key suffix of the `index`-th swap deferred at `height`, ordering deferred
+    /// swaps by submission.
+    pub(crate) fn suffix(height: u64, index: u64) -> String {
+        format!("{height:020}/{index:020}")
+    }
+
+    pub(crate) fn body(suffix: &str) -> String {
+        format!("{}{suffix}", prefix())
+    }
+
+    pub(crate) fn source(suffix: &str) -> String {
+        format!("dex/deferred_swaps/source/{suffix}")
+    }
+
+    /// The number of swaps deferred in the current block so far.
+    pub(crate) fn block_count() -> &'static str {
+        "dex/deferred_swaps/object/block_count"
+    }
+}
+
+pub(crate) mod event_index {
+    /// The index of the next DEX event recorded in the current block.
+    pub(crate) fn next() -> &'static str {
+        "dex/event_index/object/next"
+    }
+}
+
 pub(crate) mod engine {
     use super::*;
     use crate::lp::BareTradingFunction;
@@ -226,9 +395,15 @@ pub(crate) mod eviction_queue {
         use anyhow::ensure;
         use penumbra_num::Amount;
 
+        /// The prefix of the inventory index, which has an entry for each direction of
+        /// every open position.
+        pub(crate) fn prefix() -> &'static [u8] {
+            b"dex/internal/eviction_queue/inventory_index"
+        }
+
         pub(crate) fn by_trading_pair(pair: &DirectedTradingPair) -> [u8; 107] {
             let mut prefix = [0u8; 107];
-            prefix[0..43].copy_from_slice(b"dex/internal/eviction_queue/inventory_index");
+            prefix[0..43].copy_from_slice(self::prefix());
             prefix[43..75].copy_from_slice(&pair.start.to_bytes());
             prefix[75..107].copy_from_slice(&pair.end.to_bytes());
             prefix
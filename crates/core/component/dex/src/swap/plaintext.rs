@@ -7,7 +7,8 @@ use decaf377::Fq;
 use once_cell::sync::Lazy;
 use penumbra_fee::Fee;
 use penumbra_proto::{
-    core::keys::v1 as pb_keys, penumbra::core::component::dex::v1 as pb, DomainType,
+    core::keys::v1 as pb_keys, penumbra::core::component::dex::v1 as pb, serializers::bech32str,
+    DomainType,
 };
 use penumbra_tct::StateCommitment;
 use poseidon377::{hash_1, hash_4, hash_7};
@@ -159,6 +160,42 @@ impl SwapPlaintext {
         }
     }
 
+    /// Creates the plaintext of a swap of `delta_1_i` of asset 1 and `delta_2_i` of
+    /// asset 2, prepaying `claim_fee` to claim its outputs.
+    ///
+    /// The claim fee is a [`Fee`], which carries its asset, so it can't be passed
+    /// where a swap input is expected, or the other way around:
+    ///
+    /// ```no_run
+    /// # use penumbra_dex::{swap::SwapPlaintext, TradingPair};
+    /// # use penumbra_fee::Fee;
+    /// # use penumbra_keys::Address;
+    /// # use penumbra_num::Amount;
+    /// # let (trading_pair, claim_address): (TradingPair, Address) = todo!();
+    /// let plaintext = SwapPlaintext::new(
+    ///     &mut rand_core::OsRng,
+    ///     trading_pair,
+    ///     Amount::from(100u64),
+    ///     Amount::zero(),
+    ///     Fee::from_staking_token_amount(Amount::from(1u64)),
+    ///     claim_address,
+    /// );
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use penumbra_dex::{swap::SwapPlaintext, TradingPair};
+    /// # use penumbra_keys::Address;
+    /// # use penumbra_num::Amount;
+    /// # let (trading_pair, claim_address): (TradingPair, Address) = todo!();
+    /// let plaintext = SwapPlaintext::new(
+    ///     &mut rand_core::OsRng,
+    ///     trading_pair,
+    ///     Amount::from(100u64),
+    ///     Amount::zero(),
+    ///     Amount::from(1u64),
+    ///     claim_address,
+    /// );
+    /// ```
     pub fn new<R: RngCore + CryptoRng>(
         rng: &mut R,
         trading_pair: TradingPair,
@@ -178,6 +215,32 @@ impl SwapPlaintext {
             rseed,
         }
     }
+
+    /// Encodes this plaintext as a compact, URL-safe Bech32m string.
+    ///
+    /// Since Bech32m is case-insensitive, the uppercased string can be
+    /// encoded efficiently in a QR code, e.g. to hand off a pending swap
+    /// to another device.
+    pub fn to_string_encoding(&self) -> String {
+        bech32str::encode(
+            &self.encode_to_vec(),
+            bech32str::swap_plaintext::BECH32_PREFIX,
+            bech32str::Bech32m,
+        )
+    }
+
+    /// Parses a plaintext encoded with [`SwapPlaintext::to_string_encoding`].
+    ///
+    /// The Bech32m checksum ensures that corrupted or truncated strings are
+    /// rejected, rather than decoded into a different plaintext.
+    pub fn from_string_encoding(s: &str) -> Result<SwapPlaintext> {
+        let bytes = bech32str::decode(
+            &s.to_lowercase(),
+            bech32str::swap_plaintext::BECH32_PREFIX,
+            bech32str::Bech32m,
+        )?;
+        SwapPlaintext::decode(bytes.as_slice())
+    }
 }
 
 pub struct SwapPlaintextVar {
@@ -466,4 +529,68 @@ mod tests {
 
         assert_eq!(plaintext, swap);
     }
+
+    fn test_swap_plaintext() -> SwapPlaintext {
+        let mut rng = OsRng;
+
+        let seed_phrase = SeedPhrase::generate(rng);
+        let sk = SpendKey::from_seed_phrase_bip44(seed_phrase, &Bip44Path::new(0));
+        let (dest, _dtk_d) = sk
+            .full_viewing_key()
+            .incoming()
+            .payment_address(0u32.into());
+        let trading_pair = TradingPair::new(
+            asset::Cache::with_known_assets()
+                .get_unit("upenumbra")
+                .unwrap()
+                .id(),
+            asset::Cache::with_known_assets()
+                .get_unit("nala")
+                .unwrap()
+                .id(),
+        );
+
+        SwapPlaintext::new(
+            &mut rng,
+            trading_pair,
+            100000u64.into(),
+            1u64.into(),
+            Fee::from_staking_token_amount(3u64.into()),
+            dest,
+        )
+    }
+
+    #[test]
+    fn swap_plaintext_string_encoding_round_trip() {
+        let swap = test_swap_plaintext();
+
+        let encoded = swap.to_string_encoding();
+        assert!(encoded.starts_with("pswap1"));
+        assert_eq!(SwapPlaintext::from_string_encoding(&encoded).unwrap(), swap);
+        // The uppercased form, as used in QR codes, also decodes.
+        assert_eq!(
+            SwapPlaintext::from_string_encoding(&encoded.to_uppercase()).unwrap(),
+            swap
+        );
+    }
+
+    #[test]
+    fn swap_plaintext_string_encoding_rejects_corrupted_input() {
+        let encoded = test_swap_plaintext().to_string_encoding();
+
+        // Truncated strings fail the checksum.
+        let truncated = &encoded[..encoded.len() - 10];
+        assert!(SwapPlaintext::from_string_encoding(truncated).is_err());
+
+        // As do strings with a corrupted character.
+        let mut corrupted = encoded.clone().into_bytes();
+        let index = corrupted.len() / 2;
+        corrupted[index] = if corrupted[index] == b'q' { b'p' } else { b'q' };
+        let corrupted = String::from_utf8(corrupted).unwrap();
+        assert!(SwapPlaintext::from_string_encoding(&corrupted).is_err());
+
+        // As well as garbage and empty input.
+        assert!(SwapPlaintext::from_string_encoding("not a swap").is_err());
+        assert!(SwapPlaintext::from_string_encoding("").is_err());
+    }
 }
@@ -5,6 +5,8 @@ mod view;
 pub mod proof;
 
 pub use action::{Body, SwapClaim};
-pub use plan::SwapClaimPlan;
-pub use proof::{SwapClaimCircuit, SwapClaimProof, SwapClaimProofPrivate, SwapClaimProofPublic};
+pub use plan::{SwapClaimPlan, SwapClaimSigningData};
+pub use proof::{
+    DuplicateProof, SwapClaimCircuit, SwapClaimProof, SwapClaimProofPrivate, SwapClaimProofPublic,
+};
 pub use view::SwapClaimView;
@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::BatchSwapOutputData;
 
-use super::proof::SwapClaimProof;
+use super::proof::{SwapClaimProof, SwapClaimProofPublic};
 
 #[derive(Debug, Clone)]
 pub struct SwapClaim {
@@ -25,6 +25,23 @@ impl SwapClaim {
     pub fn balance(&self) -> Balance {
         self.body.fee.value().into()
     }
+
+    /// Returns the public inputs to this swap claim's proof.
+    ///
+    /// The `anchor` is not part of the action itself, but is supplied by the
+    /// transaction that includes it. This allows inspecting the nullifier,
+    /// claim fee, batch output data, and output note commitments that a claim
+    /// commits to, without running the proof verifier.
+    pub fn proof_public(&self, anchor: tct::Root) -> SwapClaimProofPublic {
+        SwapClaimProofPublic {
+            anchor,
+            nullifier: self.body.nullifier,
+            claim_fee: self.body.fee.clone(),
+            output_data: self.body.output_data,
+            note_commitment_1: self.body.output_1_commitment,
+            note_commitment_2: self.body.output_2_commitment,
+        }
+    }
 }
 
 impl EffectingData for SwapClaim {
@@ -81,6 +98,13 @@ pub struct Body {
     pub output_data: BatchSwapOutputData,
 }
 
+impl Body {
+    /// Returns the note commitments of the two output notes created by this claim.
+    pub fn output_commitments(&self) -> (tct::StateCommitment, tct::StateCommitment) {
+        (self.output_1_commitment, self.output_2_commitment)
+    }
+}
+
 impl EffectingData for Body {
     fn effect_hash(&self) -> EffectHash {
         EffectHash::from_proto_effecting_data(&self.to_proto())
@@ -130,3 +154,55 @@ impl TryFrom<pb::SwapClaimBody> for Body {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use decaf377::Fq;
+    use penumbra_asset::asset;
+    use penumbra_num::Amount;
+
+    use crate::TradingPair;
+
+    #[test]
+    fn swap_claim_exposes_public_inputs() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+
+        let output_data = BatchSwapOutputData {
+            delta_1: 100u64.into(),
+            delta_2: 0u64.into(),
+            lambda_1: 0u64.into(),
+            lambda_2: 120u64.into(),
+            unfilled_1: 0u64.into(),
+            unfilled_2: 0u64.into(),
+            height: 10,
+            trading_pair: TradingPair::new(gm.id(), gn.id()),
+            sct_position_prefix: Default::default(),
+        };
+        let body = Body {
+            nullifier: Nullifier(Fq::from(1u64)),
+            fee: Fee::from_staking_token_amount(Amount::from(5u64)),
+            output_1_commitment: tct::StateCommitment(Fq::from(2u64)),
+            output_2_commitment: tct::StateCommitment(Fq::from(3u64)),
+            output_data,
+        };
+        let swap_claim = SwapClaim {
+            proof: SwapClaimProof([0u8; GROTH16_PROOF_LENGTH_BYTES]),
+            body: body.clone(),
+            epoch_duration: 20,
+        };
+
+        let anchor = tct::Tree::new().root();
+        let public = swap_claim.proof_public(anchor);
+
+        assert_eq!(public.anchor, anchor);
+        assert_eq!(public.nullifier, body.nullifier);
+        assert_eq!(public.claim_fee, body.fee);
+        assert_eq!(public.output_data, output_data);
+        assert_eq!(
+            (public.note_commitment_1, public.note_commitment_2),
+            body.output_commitments()
+        );
+    }
+}
@@ -1,10 +1,12 @@
 use decaf377::Fq;
 use penumbra_asset::{Balance, Value};
+use penumbra_fee::Fee;
 use penumbra_keys::{keys::IncomingViewingKey, FullViewingKey};
 use penumbra_proof_params::SWAPCLAIM_PROOF_PROVING_KEY;
 use penumbra_proto::{penumbra::core::component::dex::v1 as pb, DomainType};
 use penumbra_sct::Nullifier;
 use penumbra_tct as tct;
+use penumbra_txhash::{EffectHash, EffectingData as _};
 
 use serde::{Deserialize, Serialize};
 use tct::Position;
@@ -17,6 +19,52 @@ use super::{
     SwapClaim,
 };
 
+/// The data an external signer needs to reproduce and check the effect of a [`SwapClaim`].
+///
+/// A swap claim carries no spend authorization signature of its own: it is authorized
+/// by its proof, and the transaction commits to it through its effect hash. The effect
+/// hash is that of the [`swap_claim::Body`] assembled from these fields, which a signer
+/// can recompute with [`SwapClaimSigningData::effect_hash`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapClaimSigningData {
+    /// The nullifier of the swap being claimed.
+    pub nullifier: Nullifier,
+    /// The claim fee, prepaid when the swap was made.
+    pub fee: Fee,
+    /// The note commitment to the first output note.
+    pub output_1_commitment: tct::StateCommitment,
+    /// The note commitment to the second output note.
+    pub output_2_commitment: tct::StateCommitment,
+    /// The output data of the batch the swap was executed in.
+    pub output_data: BatchSwapOutputData,
+    /// The blinding factor of the first output note commitment.
+    pub note_blinding_1: Fq,
+    /// The blinding factor of the second output note commitment.
+    pub note_blinding_2: Fq,
+    /// The first blinding factor used for generating the ZK proof.
+    pub proof_blinding_r: Fq,
+    /// The second blinding factor used for generating the ZK proof.
+    pub proof_blinding_s: Fq,
+}
+
+impl SwapClaimSigningData {
+    /// Assemble the [`swap_claim::Body`] this data describes.
+    pub fn body(&self) -> swap_claim::Body {
+        swap_claim::Body {
+            nullifier: self.nullifier,
+            fee: self.fee,
+            output_1_commitment: self.output_1_commitment,
+            output_2_commitment: self.output_2_commitment,
+            output_data: self.output_data,
+        }
+    }
+
+    /// Compute the effect hash of the swap claim this data describes.
+    pub fn effect_hash(&self) -> EffectHash {
+        self.body().effect_hash()
+    }
+}
+
 /// A planned [`SwapClaim`](SwapClaim).
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(try_from = "pb::SwapClaimPlan", into = "pb::SwapClaimPlan")]
@@ -118,6 +166,24 @@ impl SwapClaimPlan {
         }
     }
 
+    /// Collect the data an external signer needs to reproduce the effect of the
+    /// [`SwapClaim`] described by this plan, and the blinding factors behind it.
+    pub fn signing_data(&self, fvk: &FullViewingKey) -> SwapClaimSigningData {
+        let body = self.swap_claim_body(fvk);
+        let (output_rseed_1, output_rseed_2) = self.swap_plaintext.output_rseeds();
+        SwapClaimSigningData {
+            nullifier: body.nullifier,
+            fee: body.fee,
+            output_1_commitment: body.output_1_commitment,
+            output_2_commitment: body.output_2_commitment,
+            output_data: body.output_data,
+            note_blinding_1: output_rseed_1.derive_note_blinding(),
+            note_blinding_2: output_rseed_2.derive_note_blinding(),
+            proof_blinding_r: self.proof_blinding_r,
+            proof_blinding_s: self.proof_blinding_s,
+        }
+    }
+
     /// Checks whether this plan's output is viewed by the given IVK.
     pub fn is_viewed_by(&self, ivk: &IncomingViewingKey) -> bool {
         ivk.views_address(&self.swap_plaintext.claim_address)
@@ -182,3 +248,62 @@ impl TryFrom<pb::SwapClaimPlan> for SwapClaimPlan {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use penumbra_asset::asset;
+    use penumbra_keys::test_keys;
+    use rand_core::OsRng;
+
+    use super::*;
+    use crate::TradingPair;
+
+    #[test]
+    fn signing_data_reproduces_the_effect_hash() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let trading_pair = TradingPair::new(gm.id(), gn.id());
+
+        let swap_plaintext = SwapPlaintext::new(
+            &mut OsRng,
+            trading_pair,
+            100u64.into(),
+            0u64.into(),
+            Fee::from_staking_token_amount(5u64.into()),
+            test_keys::ADDRESS_0.clone(),
+        );
+        let plan = SwapClaimPlan {
+            swap_plaintext,
+            position: Position::from((1, 0, 0)),
+            output_data: BatchSwapOutputData {
+                delta_1: 100u64.into(),
+                delta_2: 0u64.into(),
+                lambda_1: 0u64.into(),
+                lambda_2: 120u64.into(),
+                unfilled_1: 0u64.into(),
+                unfilled_2: 0u64.into(),
+                height: 10,
+                trading_pair,
+                sct_position_prefix: Position::from((1, 0, 0)),
+            },
+            epoch_duration: 20,
+            proof_blinding_r: Fq::from(7u64),
+            proof_blinding_s: Fq::from(11u64),
+        };
+
+        let fvk = &*test_keys::FULL_VIEWING_KEY;
+        let signing_data = plan.signing_data(fvk);
+        assert_eq!(
+            signing_data.effect_hash(),
+            plan.swap_claim_body(fvk).effect_hash()
+        );
+
+        // The note blinding factors open the output commitments.
+        let (output_1_note, output_2_note) = plan.swap_plaintext.output_notes(&plan.output_data);
+        assert_eq!(output_1_note.note_blinding(), signing_data.note_blinding_1);
+        assert_eq!(output_2_note.note_blinding(), signing_data.note_blinding_2);
+        assert_eq!(output_1_note.commit(), signing_data.output_1_commitment);
+        assert_eq!(output_2_note.commit(), signing_data.output_2_commitment);
+        assert_eq!(signing_data.proof_blinding_r, plan.proof_blinding_r);
+    }
+}
@@ -512,6 +512,32 @@ impl SwapClaimProof {
         .then_some(())
         .ok_or(VerificationError::InvalidProof)
     }
+
+    /// Returns a hash of the proof bytes, identifying this exact proof.
+    pub fn hash(&self) -> [u8; 32] {
+        blake2b_simd::Params::new()
+            .personal(b"Penumbra_SwClPrf")
+            .hash_length(32)
+            .hash(&self.0)
+            .as_bytes()
+            .try_into()
+            .expect("hash is 32 bytes")
+    }
+}
+
+/// The error returned when a swap claim resubmits the exact proof of a claim that was
+/// already accepted.
+///
+/// A resubmitted claim also spends its nullifier a second time, but this tells a client
+/// resubmitting a transaction apart from an attempt to claim a swap twice.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "swap claim proof {} was already submitted at height {height}",
+    hex::encode(proof_hash)
+)]
+pub struct DuplicateProof {
+    pub proof_hash: [u8; 32],
+    pub height: u64,
 }
 
 impl DomainType for SwapClaimProof {
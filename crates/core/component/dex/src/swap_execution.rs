@@ -27,6 +27,31 @@ impl SwapExecution {
         let price = U128x128::ratio(input.amount, output.amount).ok()?;
         Some(price)
     }
+
+    /// Returns the best price of the execution traces, in units of input per unit of
+    /// output.
+    ///
+    /// This is the price paid for the first units of output, and for a small enough
+    /// input it converges with the [`average_price`](Self::average_price).
+    pub fn marginal_price(&self) -> Option<U128x128> {
+        self.traces
+            .iter()
+            .filter_map(|trace| {
+                let input = trace.first()?;
+                let output = trace.last()?;
+                U128x128::ratio(input.amount, output.amount).ok()
+            })
+            .min()
+    }
+
+    /// Returns the price of the whole execution, blended across all traces, in units
+    /// of input per unit of output.
+    ///
+    /// The more of the available liquidity the execution consumes, the closer this
+    /// gets to the price of its worst fill.
+    pub fn average_price(&self) -> Option<U128x128> {
+        U128x128::ratio(self.input.amount, self.output.amount).ok()
+    }
 }
 
 impl DomainType for SwapExecution {
@@ -94,6 +94,15 @@ impl TradingPair {
         }
     }
 
+    /// Constructs the trading pair of two assets, in either order, rejecting a pair
+    /// of an asset with itself.
+    pub fn try_new(a: asset::Id, b: asset::Id) -> Result<Self, IdenticalAssetsError> {
+        if a == b {
+            return Err(IdenticalAssetsError(a));
+        }
+        Ok(Self::new(a, b))
+    }
+
     pub fn asset_1(&self) -> asset::Id {
         self.asset_1
     }
@@ -131,6 +140,11 @@ impl TryFrom<[u8; 64]> for TradingPair {
     }
 }
 
+/// The error returned when constructing a [`TradingPair`] of an asset with itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("a trading pair must have two distinct assets, but both are {0}")]
+pub struct IdenticalAssetsError(pub asset::Id);
+
 /// Represents a trading pair in R1CS.
 pub struct TradingPairVar {
     pub asset_1: asset::AssetIdVar,
@@ -327,3 +341,19 @@ impl Display for DirectedUnitPair {
         write!(f, "{}:{}", self.start, self.end)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_new_rejects_identical_assets() {
+        let cache = asset::Cache::with_known_assets();
+        let gm = cache.get_unit("gm").unwrap().id();
+        let gn = cache.get_unit("gn").unwrap().id();
+
+        assert_eq!(TradingPair::try_new(gm, gm), Err(IdenticalAssetsError(gm)));
+        assert_eq!(TradingPair::try_new(gm, gn), Ok(TradingPair::new(gm, gn)));
+        assert_eq!(TradingPair::try_new(gn, gm), Ok(TradingPair::new(gm, gn)));
+    }
+}
@@ -10,7 +10,7 @@ use tracing::instrument;
 
 use crate::{epoch::Epoch, genesis, params::SctParameters, state_key};
 
-use super::clock::EpochManager;
+use super::clock::{EpochManager, EpochRead};
 
 pub struct Sct {}
 
@@ -52,8 +52,25 @@ impl Component for Sct {
         begin_block: &abci::request::BeginBlock,
     ) {
         let state = Arc::get_mut(state).expect("there's only one reference to the state");
-        state.put_block_height(begin_block.header.height.into());
-        state.put_block_timestamp(begin_block.header.height.into(), begin_block.header.time);
+        let height: u64 = begin_block.header.height.into();
+
+        // The epoch of a block is recorded ahead of time, for the height following the
+        // previous block. If the chain skipped ahead, e.g. because it started above the
+        // height following genesis, carry that epoch forward to the current block.
+        let previous_height = state
+            .get_block_height()
+            .await
+            .expect("able to get the previous block height in begin_block");
+        if height > previous_height + 1 && state.get_epoch_by_height(height).await.is_err() {
+            let epoch = state
+                .get_epoch_by_height(previous_height + 1)
+                .await
+                .expect("the epoch of the block following the previous one is recorded");
+            state.put_epoch_by_height(height, epoch);
+        }
+
+        state.put_block_height(height);
+        state.put_block_timestamp(height, begin_block.header.time);
     }
 
     #[instrument(name = "sct_component", skip(_state, _end_block))]
@@ -234,6 +234,11 @@ pub trait VerificationExt: StateRead {
         }
     }
 
+    /// Check that `nullifier` has not been spent yet.
+    ///
+    /// # Errors
+    /// If it has, the returned error can be downcast to a [`NullifierAlreadySpent`],
+    /// identifying the earlier spend.
     async fn check_nullifier_unspent(&self, nullifier: Nullifier) -> Result<()> {
         if let Some(info) = self
             .get::<NullificationInfo>(&state_key::nullifier_set::spent_nullifier_lookup(
@@ -241,14 +246,30 @@ pub trait VerificationExt: StateRead {
             ))
             .await?
         {
-            anyhow::bail!(
-                "nullifier {} was already spent in {:?}",
+            return Err(NullifierAlreadySpent {
                 nullifier,
-                hex::encode(info.id),
-            );
+                spend_height: info.spend_height,
+                source_id: info.id,
+            }
+            .into());
         }
         Ok(())
     }
 }
 
+/// Returned when a nullifier that was already spent is spent again.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "nullifier {nullifier} was already spent at height {spend_height} in {:?}",
+    hex::encode(source_id)
+)]
+pub struct NullifierAlreadySpent {
+    /// The nullifier spent twice.
+    pub nullifier: Nullifier,
+    /// The height of the block in which the nullifier was first spent.
+    pub spend_height: u64,
+    /// The id of the transaction that first spent the nullifier.
+    pub source_id: [u8; 32],
+}
+
 impl<T: StateRead + ?Sized> VerificationExt for T {}
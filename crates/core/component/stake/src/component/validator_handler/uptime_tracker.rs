@@ -181,6 +181,9 @@ pub trait ValidatorUptimeTracker: StateWrite {
             self.record_proto(event::EventValidatorMissedBlock { identity_key }.to_proto());
         }
 
+        // The chain may have skipped ahead since the last block, if it started above
+        // the height following genesis. There was nothing to sign in between.
+        uptime.skip_to_height(height.saturating_sub(1));
         uptime.mark_height_as_signed(height, voted)?;
         if uptime.num_missed_blocks() as u64 >= params.missed_blocks_maximum {
             self.set_validator_state(&identity_key, validator::State::Jailed)
@@ -49,6 +49,22 @@ impl Uptime {
         }
     }
 
+    /// Skip ahead to the given height, as if the validator signed every block
+    /// up to and including it.
+    ///
+    /// This is for blocks that were never produced, e.g. because the chain started
+    /// above the height following genesis, so that there was nothing to sign.
+    /// This does nothing if the tracker is already at or past `height`.
+    pub fn skip_to_height(&mut self, height: u64) {
+        let window = self.signatures.len() as u64;
+        let skipped = height.saturating_sub(self.as_of_block_height).min(window);
+        for h in (height - skipped + 1)..=height {
+            let index = (h as usize) % self.signatures.len();
+            self.signatures.set(index, true);
+        }
+        self.as_of_block_height = self.as_of_block_height.max(height);
+    }
+
     /// Mark that the validator signed the block at the given height (`true`),
     /// or did not sign (`false`).
     ///
@@ -170,6 +186,27 @@ mod tests {
         assert!(uptime.mark_height_as_signed(0, true).is_err());
     }
 
+    #[test]
+    fn skipped_blocks_are_not_missed() {
+        let window = 128;
+        let mut uptime = Uptime::new(0, window);
+
+        // Miss every other block for a full window, then skip ahead.
+        for h in 1..(window + 1) {
+            uptime.mark_height_as_signed(h as u64, h % 2 == 0).unwrap();
+        }
+        uptime.skip_to_height(1000);
+        assert_eq!(uptime.num_missed_blocks(), 0);
+        uptime.mark_height_as_signed(1001, false).unwrap();
+        assert_eq!(uptime.num_missed_blocks(), 1);
+
+        // Skipping to a height the tracker has already passed does nothing.
+        uptime.skip_to_height(1001);
+        uptime.skip_to_height(500);
+        assert_eq!(uptime.num_missed_blocks(), 1);
+        uptime.mark_height_as_signed(1002, true).unwrap();
+    }
+
     /// Basic check that if we miss block 1, we report that we missed block 1.
     #[test]
     fn enumerate_missed_first_block() {
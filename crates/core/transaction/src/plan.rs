@@ -587,4 +587,72 @@ mod tests {
         //     .expect("can build");
         // assert_eq!(plan_effect_hash, transaction.effect_hash());
     }
+
+    #[test]
+    fn swap_memo_is_recovered_only_by_its_sender() {
+        let mut rng = OsRng;
+        let sk = SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(rng), &Bip44Path::new(0));
+        let fvk = sk.full_viewing_key();
+        let (addr, _dtk) = fvk.incoming().payment_address(0u32.into());
+
+        let trading_pair = TradingPair::new(
+            asset::Cache::with_known_assets()
+                .get_unit("nala")
+                .unwrap()
+                .id(),
+            *STAKING_TOKEN_ASSET_ID,
+        );
+        let swap_plaintext = SwapPlaintext::new(
+            &mut rng,
+            trading_pair,
+            100u64.into(),
+            0u64.into(),
+            Fee::default(),
+            addr.clone(),
+        );
+        let swap_commitment = swap_plaintext.swap_commitment();
+
+        let memo_plaintext =
+            MemoPlaintext::new(addr.clone(), "Swapping nala for penumbra".to_string()).unwrap();
+        let plan = TransactionPlan {
+            actions: vec![
+                OutputPlan::new(
+                    &mut rng,
+                    Value {
+                        amount: 1u64.into(),
+                        asset_id: *STAKING_TOKEN_ASSET_ID,
+                    },
+                    addr,
+                )
+                .into(),
+                SwapPlan::new(&mut rng, swap_plaintext).into(),
+            ],
+            transaction_parameters: TransactionParameters {
+                expiry_height: 0,
+                fee: Fee::default(),
+                chain_id: "penumbra-test".to_string(),
+            },
+            detection_data: None,
+            memo: Some(MemoPlan::new(&mut rng, memo_plaintext.clone())),
+        };
+
+        let auth_data = plan.authorize(rng, &sk).unwrap();
+        let witness_data = WitnessData {
+            anchor: tct::Tree::new().root(),
+            state_commitment_proofs: Default::default(),
+        };
+        let transaction = plan.build(fvk, &witness_data, &auth_data).unwrap();
+
+        assert_eq!(
+            transaction.decrypt_swap_memo(fvk, swap_commitment),
+            Some(memo_plaintext)
+        );
+
+        let other_sk =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(rng), &Bip44Path::new(0));
+        assert_eq!(
+            transaction.decrypt_swap_memo(other_sk.full_viewing_key(), swap_commitment),
+            None
+        );
+    }
 }
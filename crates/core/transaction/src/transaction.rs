@@ -196,6 +196,32 @@ impl Transaction {
         Err(anyhow::anyhow!("unable to decrypt memo"))
     }
 
+    /// Helper function for decrypting the memo associated with a swap given an FVK.
+    ///
+    /// Swaps don't carry a memo of their own, so this is the memo of the transaction
+    /// containing the swap with the given commitment. Returns `None` if there is no
+    /// such swap, if it was not made by `fvk`, or if the transaction has no memo.
+    /// An empty memo is returned as a memo with empty text.
+    pub fn decrypt_swap_memo(
+        &self,
+        fvk: &FullViewingKey,
+        swap_commitment: StateCommitment,
+    ) -> Option<MemoPlaintext> {
+        let swap = self.actions().find_map(|action| match action {
+            Action::Swap(swap) if swap.body.payload.commitment == swap_commitment => Some(swap),
+            _ => None,
+        })?;
+
+        // Only the sender of the swap can decrypt its payload.
+        swap.body
+            .payload
+            .encrypted_swap
+            .decrypt(fvk.outgoing(), swap_commitment)
+            .ok()?;
+
+        self.decrypt_memo(fvk).ok()
+    }
+
     pub fn payload_keys(
         &self,
         fvk: &FullViewingKey,
@@ -495,7 +495,8 @@ fn position_strategy() -> impl Strategy<Value = Position> {
 }
 
 fn position_open_strategy() -> impl Strategy<Value = PositionOpen> {
-    (position_strategy()).prop_map(|position| PositionOpen { position })
+    (position_strategy(), amount_strategy())
+        .prop_map(|(position, open_fee)| PositionOpen { position, open_fee })
 }
 
 fn position_close_strategy() -> impl Strategy<Value = PositionClose> {
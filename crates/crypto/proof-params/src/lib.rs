@@ -237,6 +237,71 @@ pub mod nullifier_derivation {
     include!("gen/nullifier_derivation_id.rs");
 }
 
+/// Check that each embedded verification key matches its hardcoded ID.
+///
+/// This is meant to be run at startup, so that mismatched parameters, e.g. after a
+/// botched upgrade, are reported at boot rather than as proof failures later on.
+pub fn check_verification_keys() -> Result<()> {
+    let keys: [(&str, &PreparedVerifyingKey<Bls12_377>, &str); 7] = [
+        (
+            "spend",
+            &*SPEND_PROOF_VERIFICATION_KEY,
+            spend::VERIFICATION_KEY_ID,
+        ),
+        (
+            "output",
+            &*OUTPUT_PROOF_VERIFICATION_KEY,
+            output::VERIFICATION_KEY_ID,
+        ),
+        (
+            "swap",
+            &*SWAP_PROOF_VERIFICATION_KEY,
+            swap::VERIFICATION_KEY_ID,
+        ),
+        (
+            "swapclaim",
+            &*SWAPCLAIM_PROOF_VERIFICATION_KEY,
+            swapclaim::VERIFICATION_KEY_ID,
+        ),
+        (
+            "convert",
+            &*CONVERT_PROOF_VERIFICATION_KEY,
+            convert::VERIFICATION_KEY_ID,
+        ),
+        (
+            "delegator vote",
+            &*DELEGATOR_VOTE_PROOF_VERIFICATION_KEY,
+            delegator_vote::VERIFICATION_KEY_ID,
+        ),
+        (
+            "nullifier derivation",
+            &*NULLIFIER_DERIVATION_PROOF_VERIFICATION_KEY,
+            nullifier_derivation::VERIFICATION_KEY_ID,
+        ),
+    ];
+    for (name, vk, expected_id) in keys {
+        check_verification_key(name, vk, expected_id)?;
+    }
+    Ok(())
+}
+
+fn check_verification_key(
+    name: &str,
+    vk: &PreparedVerifyingKey<Bls12_377>,
+    expected_id: &str,
+) -> Result<()> {
+    let vk_id = vk.debug_id();
+    if vk_id != expected_id {
+        bail!(
+            "{} verification key ID mismatch: expected {}, loaded {}",
+            name,
+            expected_id,
+            vk_id
+        );
+    }
+    Ok(())
+}
+
 // Note: Here we are using `CanonicalDeserialize::deserialize_uncompressed_unchecked` as the
 // parameters are being loaded from a trusted source (our source code).
 
@@ -281,3 +346,26 @@ fn nullifier_derivation_verification_parameters() -> VerifyingKey<Bls12_377> {
     VerifyingKey::deserialize_uncompressed_unchecked(&vk_params[..])
         .expect("can deserialize VerifyingKey")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_verification_keys_match_their_ids() {
+        check_verification_keys().expect("embedded verification keys are consistent");
+    }
+
+    #[test]
+    fn mismatched_verification_key_is_named() {
+        let err = check_verification_key(
+            "swap",
+            &*SWAP_PROOF_VERIFICATION_KEY,
+            swapclaim::VERIFICATION_KEY_ID,
+        )
+        .expect_err("the swap key does not have the swap claim key ID");
+        assert!(err
+            .to_string()
+            .starts_with("swap verification key ID mismatch"));
+    }
+}
@@ -138,7 +138,9 @@ impl TryFrom<pb::CeremonyCrs> for Phase2RawCeremonyCRS {
 }
 
 /// Holds all of the CRS elements for phase2 in one struct.
-#[derive(Clone, Debug)]
+///
+/// Two CRSes are equal when their group elements are, regardless of how they were encoded.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Phase2CeremonyCRS([Phase2CRSElements; NUM_CIRCUITS]);
 
 impl From<Phase2CeremonyCRS> for Phase2RawCeremonyCRS {
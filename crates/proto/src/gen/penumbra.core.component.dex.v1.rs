@@ -759,6 +759,10 @@ pub struct PositionOpen {
     /// are unchanged over the entire lifetime of the position.
     #[prost(message, optional, tag = "1")]
     pub position: ::core::option::Option<Position>,
+    /// The fee paid to open the position, in the staking token, which must cover
+    /// the position open fee set in the DEX parameters.
+    #[prost(message, optional, tag = "2")]
+    pub open_fee: ::core::option::Option<super::super::super::num::v1::Amount>,
 }
 impl ::prost::Name for PositionOpen {
     const NAME: &'static str = "PositionOpen";
@@ -1599,6 +1603,51 @@ pub struct DexParameters {
     /// for a single pair
     #[prost(uint32, tag = "5")]
     pub max_execution_budget: u32,
+    /// The number of blocks for which batch swap output data is retained before it
+    /// is pruned, which must be at least the minimum retention window. The output
+    /// data of batches with unclaimed swaps is retained until they are claimed. If
+    /// zero, batch swap output data is retained forever.
+    #[prost(uint64, tag = "6")]
+    pub batch_output_retention_blocks: u64,
+    /// The minimum fee, in basis points, of positions opened on trading pairs
+    /// without a fee floor in `pair_fee_floors`.
+    #[prost(uint32, tag = "7")]
+    pub min_fee_bps: u32,
+    /// Fee floors for specific trading pairs, overriding `min_fee_bps` on those pairs.
+    #[prost(message, repeated, tag = "8")]
+    pub pair_fee_floors: ::prost::alloc::vec::Vec<PairFeeFloor>,
+    /// The maximum size of a single swap on specific trading pairs.
+    #[prost(message, repeated, tag = "9")]
+    pub pair_swap_limits: ::prost::alloc::vec::Vec<PairSwapLimit>,
+    /// The number of blocks between batch swap executions. Swaps submitted in
+    /// between are deferred, to clear together at the next multiple of the
+    /// interval. Zero or one clears batches every block.
+    #[prost(uint32, tag = "10")]
+    pub batch_interval_blocks: u32,
+    /// The minimum number of blocks between the execution of a batch and claims
+    /// of the swaps in it. Zero allows claims in any later block.
+    #[prost(uint64, tag = "11")]
+    pub swap_claim_grace_blocks: u64,
+    /// The fee, in the staking token, charged to open a position. The fee is
+    /// burned. Zero allows positions to be opened for free.
+    #[prost(message, optional, tag = "12")]
+    pub position_open_fee: ::core::option::Option<super::super::super::num::v1::Amount>,
+    /// The minimum reserves, on each side, of a position opened on a pair with no
+    /// open positions, or zero for no minimum.
+    #[prost(message, optional, tag = "14")]
+    pub pair_bootstrap_min_reserves: ::core::option::Option<
+        super::super::super::num::v1::Amount,
+    >,
+    /// The age, in blocks, after which batch swap output data is stored in a compact
+    /// encoding, or zero to always store it in full.
+    #[prost(uint64, tag = "15")]
+    pub batch_output_compression_blocks: u64,
+    /// The assets in which swap claims may pay their fees. If empty, claims may only
+    /// pay fees in the staking token.
+    #[prost(message, repeated, tag = "16")]
+    pub swap_claim_fee_assets: ::prost::alloc::vec::Vec<
+        super::super::super::asset::v1::AssetId,
+    >,
 }
 impl ::prost::Name for DexParameters {
     const NAME: &'static str = "DexParameters";
@@ -1607,6 +1656,45 @@ impl ::prost::Name for DexParameters {
         ::prost::alloc::format!("penumbra.core.component.dex.v1.{}", Self::NAME)
     }
 }
+/// The minimum fee of positions opened on a trading pair.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PairFeeFloor {
+    /// The trading pair the fee floor applies to.
+    #[prost(message, optional, tag = "1")]
+    pub pair: ::core::option::Option<TradingPair>,
+    /// The minimum fee, in basis points, of positions opened on the pair.
+    #[prost(uint32, tag = "2")]
+    pub min_fee_bps: u32,
+}
+impl ::prost::Name for PairFeeFloor {
+    const NAME: &'static str = "PairFeeFloor";
+    const PACKAGE: &'static str = "penumbra.core.component.dex.v1";
+    fn full_name() -> ::prost::alloc::string::String {
+        ::prost::alloc::format!("penumbra.core.component.dex.v1.{}", Self::NAME)
+    }
+}
+/// The maximum size of a single swap on a trading pair.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PairSwapLimit {
+    /// The trading pair the limit applies to.
+    #[prost(message, optional, tag = "1")]
+    pub pair: ::core::option::Option<TradingPair>,
+    /// The maximum input of asset 1 of the pair in a single swap, or zero for no limit.
+    #[prost(message, optional, tag = "2")]
+    pub max_delta_1: ::core::option::Option<super::super::super::num::v1::Amount>,
+    /// The maximum input of asset 2 of the pair in a single swap, or zero for no limit.
+    #[prost(message, optional, tag = "3")]
+    pub max_delta_2: ::core::option::Option<super::super::super::num::v1::Amount>,
+}
+impl ::prost::Name for PairSwapLimit {
+    const NAME: &'static str = "PairSwapLimit";
+    const PACKAGE: &'static str = "penumbra.core.component.dex.v1";
+    fn full_name() -> ::prost::alloc::string::String {
+        ::prost::alloc::format!("penumbra.core.component.dex.v1.{}", Self::NAME)
+    }
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GenesisContent {
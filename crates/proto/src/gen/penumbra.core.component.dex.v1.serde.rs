@@ -1714,6 +1714,36 @@ impl serde::Serialize for DexParameters {
         if self.max_execution_budget != 0 {
             len += 1;
         }
+        if self.batch_output_retention_blocks != 0 {
+            len += 1;
+        }
+        if self.min_fee_bps != 0 {
+            len += 1;
+        }
+        if !self.pair_fee_floors.is_empty() {
+            len += 1;
+        }
+        if !self.pair_swap_limits.is_empty() {
+            len += 1;
+        }
+        if self.batch_interval_blocks != 0 {
+            len += 1;
+        }
+        if self.swap_claim_grace_blocks != 0 {
+            len += 1;
+        }
+        if self.position_open_fee.is_some() {
+            len += 1;
+        }
+        if self.pair_bootstrap_min_reserves.is_some() {
+            len += 1;
+        }
+        if self.batch_output_compression_blocks != 0 {
+            len += 1;
+        }
+        if !self.swap_claim_fee_assets.is_empty() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("penumbra.core.component.dex.v1.DexParameters", len)?;
         if self.is_enabled {
             struct_ser.serialize_field("isEnabled", &self.is_enabled)?;
@@ -1730,6 +1760,39 @@ impl serde::Serialize for DexParameters {
         if self.max_execution_budget != 0 {
             struct_ser.serialize_field("maxExecutionBudget", &self.max_execution_budget)?;
         }
+        if self.batch_output_retention_blocks != 0 {
+            #[allow(clippy::needless_borrow)]
+            struct_ser.serialize_field("batchOutputRetentionBlocks", ToString::to_string(&self.batch_output_retention_blocks).as_str())?;
+        }
+        if self.min_fee_bps != 0 {
+            struct_ser.serialize_field("minFeeBps", &self.min_fee_bps)?;
+        }
+        if !self.pair_fee_floors.is_empty() {
+            struct_ser.serialize_field("pairFeeFloors", &self.pair_fee_floors)?;
+        }
+        if !self.pair_swap_limits.is_empty() {
+            struct_ser.serialize_field("pairSwapLimits", &self.pair_swap_limits)?;
+        }
+        if self.batch_interval_blocks != 0 {
+            struct_ser.serialize_field("batchIntervalBlocks", &self.batch_interval_blocks)?;
+        }
+        if self.swap_claim_grace_blocks != 0 {
+            #[allow(clippy::needless_borrow)]
+            struct_ser.serialize_field("swapClaimGraceBlocks", ToString::to_string(&self.swap_claim_grace_blocks).as_str())?;
+        }
+        if let Some(v) = self.position_open_fee.as_ref() {
+            struct_ser.serialize_field("positionOpenFee", v)?;
+        }
+        if let Some(v) = self.pair_bootstrap_min_reserves.as_ref() {
+            struct_ser.serialize_field("pairBootstrapMinReserves", v)?;
+        }
+        if self.batch_output_compression_blocks != 0 {
+            #[allow(clippy::needless_borrow)]
+            struct_ser.serialize_field("batchOutputCompressionBlocks", ToString::to_string(&self.batch_output_compression_blocks).as_str())?;
+        }
+        if !self.swap_claim_fee_assets.is_empty() {
+            struct_ser.serialize_field("swapClaimFeeAssets", &self.swap_claim_fee_assets)?;
+        }
         struct_ser.end()
     }
 }
@@ -1750,6 +1813,26 @@ impl<'de> serde::Deserialize<'de> for DexParameters {
             "maxPositionsPerPair",
             "max_execution_budget",
             "maxExecutionBudget",
+            "batch_output_retention_blocks",
+            "batchOutputRetentionBlocks",
+            "min_fee_bps",
+            "minFeeBps",
+            "pair_fee_floors",
+            "pairFeeFloors",
+            "pair_swap_limits",
+            "pairSwapLimits",
+            "batch_interval_blocks",
+            "batchIntervalBlocks",
+            "swap_claim_grace_blocks",
+            "swapClaimGraceBlocks",
+            "position_open_fee",
+            "positionOpenFee",
+            "pair_bootstrap_min_reserves",
+            "pairBootstrapMinReserves",
+            "batch_output_compression_blocks",
+            "batchOutputCompressionBlocks",
+            "swap_claim_fee_assets",
+            "swapClaimFeeAssets",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -1759,6 +1842,16 @@ impl<'de> serde::Deserialize<'de> for DexParameters {
             MaxHops,
             MaxPositionsPerPair,
             MaxExecutionBudget,
+            BatchOutputRetentionBlocks,
+            MinFeeBps,
+            PairFeeFloors,
+            PairSwapLimits,
+            BatchIntervalBlocks,
+            SwapClaimGraceBlocks,
+            PositionOpenFee,
+            PairBootstrapMinReserves,
+            BatchOutputCompressionBlocks,
+            SwapClaimFeeAssets,
             __SkipField__,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
@@ -1786,6 +1879,16 @@ impl<'de> serde::Deserialize<'de> for DexParameters {
                             "maxHops" | "max_hops" => Ok(GeneratedField::MaxHops),
                             "maxPositionsPerPair" | "max_positions_per_pair" => Ok(GeneratedField::MaxPositionsPerPair),
                             "maxExecutionBudget" | "max_execution_budget" => Ok(GeneratedField::MaxExecutionBudget),
+                            "batchOutputRetentionBlocks" | "batch_output_retention_blocks" => Ok(GeneratedField::BatchOutputRetentionBlocks),
+                            "minFeeBps" | "min_fee_bps" => Ok(GeneratedField::MinFeeBps),
+                            "pairFeeFloors" | "pair_fee_floors" => Ok(GeneratedField::PairFeeFloors),
+                            "pairSwapLimits" | "pair_swap_limits" => Ok(GeneratedField::PairSwapLimits),
+                            "batchIntervalBlocks" | "batch_interval_blocks" => Ok(GeneratedField::BatchIntervalBlocks),
+                            "swapClaimGraceBlocks" | "swap_claim_grace_blocks" => Ok(GeneratedField::SwapClaimGraceBlocks),
+                            "positionOpenFee" | "position_open_fee" => Ok(GeneratedField::PositionOpenFee),
+                            "pairBootstrapMinReserves" | "pair_bootstrap_min_reserves" => Ok(GeneratedField::PairBootstrapMinReserves),
+                            "batchOutputCompressionBlocks" | "batch_output_compression_blocks" => Ok(GeneratedField::BatchOutputCompressionBlocks),
+                            "swapClaimFeeAssets" | "swap_claim_fee_assets" => Ok(GeneratedField::SwapClaimFeeAssets),
                             _ => Ok(GeneratedField::__SkipField__),
                         }
                     }
@@ -1810,6 +1913,16 @@ impl<'de> serde::Deserialize<'de> for DexParameters {
                 let mut max_hops__ = None;
                 let mut max_positions_per_pair__ = None;
                 let mut max_execution_budget__ = None;
+                let mut batch_output_retention_blocks__ = None;
+                let mut min_fee_bps__ = None;
+                let mut pair_fee_floors__ = None;
+                let mut pair_swap_limits__ = None;
+                let mut batch_interval_blocks__ = None;
+                let mut swap_claim_grace_blocks__ = None;
+                let mut position_open_fee__ = None;
+                let mut pair_bootstrap_min_reserves__ = None;
+                let mut batch_output_compression_blocks__ = None;
+                let mut swap_claim_fee_assets__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::IsEnabled => {
@@ -1848,6 +1961,76 @@ impl<'de> serde::Deserialize<'de> for DexParameters {
                                 Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
                             ;
                         }
+                        GeneratedField::BatchOutputRetentionBlocks => {
+                            if batch_output_retention_blocks__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("batchOutputRetentionBlocks"));
+                            }
+                            batch_output_retention_blocks__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::MinFeeBps => {
+                            if min_fee_bps__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("minFeeBps"));
+                            }
+                            min_fee_bps__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PairFeeFloors => {
+                            if pair_fee_floors__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("pairFeeFloors"));
+                            }
+                            pair_fee_floors__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::PairSwapLimits => {
+                            if pair_swap_limits__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("pairSwapLimits"));
+                            }
+                            pair_swap_limits__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::BatchIntervalBlocks => {
+                            if batch_interval_blocks__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("batchIntervalBlocks"));
+                            }
+                            batch_interval_blocks__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::SwapClaimGraceBlocks => {
+                            if swap_claim_grace_blocks__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("swapClaimGraceBlocks"));
+                            }
+                            swap_claim_grace_blocks__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PositionOpenFee => {
+                            if position_open_fee__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("positionOpenFee"));
+                            }
+                            position_open_fee__ = map_.next_value()?;
+                        }
+                        GeneratedField::PairBootstrapMinReserves => {
+                            if pair_bootstrap_min_reserves__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("pairBootstrapMinReserves"));
+                            }
+                            pair_bootstrap_min_reserves__ = map_.next_value()?;
+                        }
+                        GeneratedField::BatchOutputCompressionBlocks => {
+                            if batch_output_compression_blocks__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("batchOutputCompressionBlocks"));
+                            }
+                            batch_output_compression_blocks__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::SwapClaimFeeAssets => {
+                            if swap_claim_fee_assets__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("swapClaimFeeAssets"));
+                            }
+                            swap_claim_fee_assets__ = Some(map_.next_value()?);
+                        }
                         GeneratedField::__SkipField__ => {
                             let _ = map_.next_value::<serde::de::IgnoredAny>()?;
                         }
@@ -1859,6 +2042,16 @@ impl<'de> serde::Deserialize<'de> for DexParameters {
                     max_hops: max_hops__.unwrap_or_default(),
                     max_positions_per_pair: max_positions_per_pair__.unwrap_or_default(),
                     max_execution_budget: max_execution_budget__.unwrap_or_default(),
+                    batch_output_retention_blocks: batch_output_retention_blocks__.unwrap_or_default(),
+                    min_fee_bps: min_fee_bps__.unwrap_or_default(),
+                    pair_fee_floors: pair_fee_floors__.unwrap_or_default(),
+                    pair_swap_limits: pair_swap_limits__.unwrap_or_default(),
+                    batch_interval_blocks: batch_interval_blocks__.unwrap_or_default(),
+                    swap_claim_grace_blocks: swap_claim_grace_blocks__.unwrap_or_default(),
+                    position_open_fee: position_open_fee__,
+                    pair_bootstrap_min_reserves: pair_bootstrap_min_reserves__,
+                    batch_output_compression_blocks: batch_output_compression_blocks__.unwrap_or_default(),
+                    swap_claim_fee_assets: swap_claim_fee_assets__.unwrap_or_default(),
                 })
             }
         }
@@ -4662,6 +4855,252 @@ impl<'de> serde::Deserialize<'de> for LpNft {
         deserializer.deserialize_struct("penumbra.core.component.dex.v1.LpNft", FIELDS, GeneratedVisitor)
     }
 }
+impl serde::Serialize for PairFeeFloor {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.pair.is_some() {
+            len += 1;
+        }
+        if self.min_fee_bps != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("penumbra.core.component.dex.v1.PairFeeFloor", len)?;
+        if let Some(v) = self.pair.as_ref() {
+            struct_ser.serialize_field("pair", v)?;
+        }
+        if self.min_fee_bps != 0 {
+            struct_ser.serialize_field("minFeeBps", &self.min_fee_bps)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for PairFeeFloor {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "pair",
+            "min_fee_bps",
+            "minFeeBps",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Pair,
+            MinFeeBps,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "pair" => Ok(GeneratedField::Pair),
+                            "minFeeBps" | "min_fee_bps" => Ok(GeneratedField::MinFeeBps),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = PairFeeFloor;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct penumbra.core.component.dex.v1.PairFeeFloor")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<PairFeeFloor, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut pair__ = None;
+                let mut min_fee_bps__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Pair => {
+                            if pair__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("pair"));
+                            }
+                            pair__ = map_.next_value()?;
+                        }
+                        GeneratedField::MinFeeBps => {
+                            if min_fee_bps__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("minFeeBps"));
+                            }
+                            min_fee_bps__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(PairFeeFloor {
+                    pair: pair__,
+                    min_fee_bps: min_fee_bps__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("penumbra.core.component.dex.v1.PairFeeFloor", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for PairSwapLimit {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.pair.is_some() {
+            len += 1;
+        }
+        if self.max_delta_1.is_some() {
+            len += 1;
+        }
+        if self.max_delta_2.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("penumbra.core.component.dex.v1.PairSwapLimit", len)?;
+        if let Some(v) = self.pair.as_ref() {
+            struct_ser.serialize_field("pair", v)?;
+        }
+        if let Some(v) = self.max_delta_1.as_ref() {
+            struct_ser.serialize_field("maxDelta1", v)?;
+        }
+        if let Some(v) = self.max_delta_2.as_ref() {
+            struct_ser.serialize_field("maxDelta2", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for PairSwapLimit {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "pair",
+            "max_delta_1",
+            "maxDelta1",
+            "max_delta_2",
+            "maxDelta2",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Pair,
+            MaxDelta1,
+            MaxDelta2,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "pair" => Ok(GeneratedField::Pair),
+                            "maxDelta1" | "max_delta_1" => Ok(GeneratedField::MaxDelta1),
+                            "maxDelta2" | "max_delta_2" => Ok(GeneratedField::MaxDelta2),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = PairSwapLimit;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct penumbra.core.component.dex.v1.PairSwapLimit")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<PairSwapLimit, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut pair__ = None;
+                let mut max_delta_1__ = None;
+                let mut max_delta_2__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Pair => {
+                            if pair__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("pair"));
+                            }
+                            pair__ = map_.next_value()?;
+                        }
+                        GeneratedField::MaxDelta1 => {
+                            if max_delta_1__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("maxDelta1"));
+                            }
+                            max_delta_1__ = map_.next_value()?;
+                        }
+                        GeneratedField::MaxDelta2 => {
+                            if max_delta_2__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("maxDelta2"));
+                            }
+                            max_delta_2__ = map_.next_value()?;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(PairSwapLimit {
+                    pair: pair__,
+                    max_delta_1: max_delta_1__,
+                    max_delta_2: max_delta_2__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("penumbra.core.component.dex.v1.PairSwapLimit", FIELDS, GeneratedVisitor)
+    }
+}
 impl serde::Serialize for Position {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -5052,10 +5491,16 @@ impl serde::Serialize for PositionOpen {
         if self.position.is_some() {
             len += 1;
         }
+        if self.open_fee.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("penumbra.core.component.dex.v1.PositionOpen", len)?;
         if let Some(v) = self.position.as_ref() {
             struct_ser.serialize_field("position", v)?;
         }
+        if let Some(v) = self.open_fee.as_ref() {
+            struct_ser.serialize_field("openFee", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -5067,11 +5512,14 @@ impl<'de> serde::Deserialize<'de> for PositionOpen {
     {
         const FIELDS: &[&str] = &[
             "position",
+            "open_fee",
+            "openFee",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
             Position,
+            OpenFee,
             __SkipField__,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
@@ -5095,6 +5543,7 @@ impl<'de> serde::Deserialize<'de> for PositionOpen {
                     {
                         match value {
                             "position" => Ok(GeneratedField::Position),
+                            "openFee" | "open_fee" => Ok(GeneratedField::OpenFee),
                             _ => Ok(GeneratedField::__SkipField__),
                         }
                     }
@@ -5115,6 +5564,7 @@ impl<'de> serde::Deserialize<'de> for PositionOpen {
                     V: serde::de::MapAccess<'de>,
             {
                 let mut position__ = None;
+                let mut open_fee__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::Position => {
@@ -5123,6 +5573,12 @@ impl<'de> serde::Deserialize<'de> for PositionOpen {
                             }
                             position__ = map_.next_value()?;
                         }
+                        GeneratedField::OpenFee => {
+                            if open_fee__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("openFee"));
+                            }
+                            open_fee__ = map_.next_value()?;
+                        }
                         GeneratedField::__SkipField__ => {
                             let _ = map_.next_value::<serde::de::IgnoredAny>()?;
                         }
@@ -5130,6 +5586,7 @@ impl<'de> serde::Deserialize<'de> for PositionOpen {
                 }
                 Ok(PositionOpen {
                     position: position__,
+                    open_fee: open_fee__,
                 })
             }
         }
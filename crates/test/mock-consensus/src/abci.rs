@@ -74,10 +74,42 @@ where
     pub async fn deliver_tx(&mut self, tx: Bytes) -> Result<response::DeliverTx, anyhow::Error> {
         let request = ConsensusRequest::DeliverTx(request::DeliverTx { tx });
         let service = self.service().await?;
-        match service
+        let response = service
             .tap(|_| trace!("sending DeliverTx request"))
             .call(request)
-            .await
+            .await;
+        Self::deliver_tx_response(response)
+    }
+
+    /// Sends a [`ConsensusRequest::DeliverTx`] request for each of `txs` to the ABCI application.
+    ///
+    /// Like CometBFT, this sends each request without waiting for the response to the
+    /// previous one, so the application may find several transactions queued at once.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn deliver_txs(
+        &mut self,
+        txs: Vec<Bytes>,
+    ) -> Result<Vec<response::DeliverTx>, anyhow::Error> {
+        let mut pending = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let request = ConsensusRequest::DeliverTx(request::DeliverTx { tx });
+            let service = self.service().await?;
+            trace!("sending DeliverTx request");
+            pending.push(service.call(request));
+        }
+
+        let mut responses = Vec::with_capacity(pending.len());
+        for response in pending {
+            responses.push(Self::deliver_tx_response(response.await)?);
+        }
+        Ok(responses)
+    }
+
+    /// Checks the consensus service's response to a [`ConsensusRequest::DeliverTx`] request.
+    fn deliver_tx_response(
+        response: Result<ConsensusResponse, BoxError>,
+    ) -> Result<response::DeliverTx, anyhow::Error> {
+        match response
             .tap_err(|error| error!(?error, "consensus service returned error"))
             .map_err(|_| anyhow!("consensus service returned error"))?
         {
@@ -137,17 +137,18 @@ where
         let last_commit_info = Self::last_commit_info(last_commit);
 
         trace!("sending block");
-        test_node.begin_block(header, last_commit_info).await?;
-        let mut deliver_tx_responses = Vec::new();
-        for tx in data {
-            let tx = tx.into();
-            // The caller may want to access the DeliverTx responses
-            deliver_tx_responses.push(test_node.deliver_tx(tx).await?);
-        }
+        test_node.last_begin_block_events = test_node
+            .begin_block(header, last_commit_info)
+            .await?
+            .events;
+        // The caller may want to access the DeliverTx responses
+        let deliver_tx_responses = test_node
+            .deliver_txs(data.into_iter().map(Into::into).collect())
+            .await?;
 
         // The CheckTx, BeginBlock, DeliverTx, EndBlock methods include an Events field.
-        // The mock consensus code only handles EndBlock and DeliverTx events.
-        // Extract the events emitted during end_block.
+        // The mock consensus code only returns EndBlock and DeliverTx events, BeginBlock events
+        // are kept on the test node. Extract the events emitted during end_block.
         let events = test_node.end_block().await?.events;
         let deliver_tx_events = deliver_tx_responses
             .iter()
@@ -47,6 +47,13 @@ mod sign {
         }
     }
 
+    /// Returns a [commit signature] saying this validator did not vote.
+    ///
+    /// [commit signature]: CommitSig
+    pub(super) fn absent() -> CommitSig {
+        CommitSig::BlockIdFlagAbsent
+    }
+
     /// Returns a [commit signature] saying this validator voted nil.
     ///
     /// [commit signature]: CommitSig
@@ -69,6 +76,8 @@ impl<C> TestNode<C> {
 
     /// Returns an [`Iterator`] of signatures for validators in the keyring.
     /// Signatures sign the given block header.
+    ///
+    /// Validators marked as absent at the header's height do not sign it.
     pub(super) fn generate_signatures(
         &self,
         header: &tendermint::block::Header,
@@ -91,19 +100,27 @@ impl<C> TestNode<C> {
             chain_id: self.chain_id.clone(),
         };
         tracing::trace!(vote=?canonical,"canonical vote constructed");
+        let height = header.height.value();
 
         return self
             .keyring
             .iter()
             .map(|(vk, sk)| {
                 (
+                    vk,
                     <Sha256 as Digest>::digest(vk).as_slice()[0..20]
                         .try_into()
                         .expect(""),
                     sk,
                 )
             })
-            .map(move |(id, sk)| self::sign::commit(account::Id::new(id), sk, &canonical));
+            .map(move |(vk, id, sk)| {
+                if self.is_absent(vk, height) {
+                    self::sign::absent()
+                } else {
+                    self::sign::commit(account::Id::new(id), sk, &canonical)
+                }
+            });
     }
 }
 
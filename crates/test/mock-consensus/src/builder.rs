@@ -10,6 +10,7 @@ use {
     anyhow::Result,
     bytes::Bytes,
     ed25519_consensus::{SigningKey, VerificationKey},
+    sha2::{Digest as _, Sha256},
     std::time::Duration,
     tendermint::{Genesis, Time},
 };
@@ -28,6 +29,8 @@ pub struct Builder {
     pub on_block: Option<OnBlockFn>,
     pub ts_callback: Option<TsCallbackFn>,
     pub initial_timestamp: Option<Time>,
+    /// The height of the first block after genesis, if not 1.
+    pub initial_height: Option<u64>,
     pub chain_id: Option<String>,
     /// Hardcodes a genesis to be used for the chain.
     /// Useful if you're trying to test cometbft compatibility
@@ -39,6 +42,9 @@ pub struct Builder {
     /// The default behavior is to generate random keys if none are
     /// supplied.
     pub keys: Vec<(SigningKey, VerificationKey)>,
+    /// A seed from which generated validator keys are derived.
+    /// If unset, keys are generated from the operating system's randomness.
+    pub seed: Option<[u8; 32]>,
 }
 
 impl TestNode<()> {
@@ -86,7 +92,7 @@ impl Builder {
         if self.keys.len() >= 1 {
             Self::add_key(&mut keyring, self.keys[0].clone());
         } else {
-            let key = Self::generate_key();
+            let key = Self::generate_key(self.seed, 0);
             Self::add_key(&mut keyring, key);
         }
 
@@ -111,18 +117,56 @@ impl Builder {
             Self::add_key(&mut keyring, self.keys[0].clone());
             Self::add_key(&mut keyring, self.keys[1].clone());
         } else {
-            let key = Self::generate_key();
+            let key = Self::generate_key(self.seed, 0);
             Self::add_key(&mut keyring, key);
-            let key = Self::generate_key();
+            let key = Self::generate_key(self.seed, 1);
             Self::add_key(&mut keyring, key);
         }
 
         Self { keyring, ..self }
     }
 
-    /// Generates consensus keys.
-    fn generate_key() -> (SigningKey, VerificationKey) {
-        let sk = ed25519_consensus::SigningKey::new(rand_core::OsRng);
+    /// Generates `count` sets of validator keys.
+    ///
+    /// As with [`Builder::single_validator()`], the keys provided to
+    /// [`Builder::with_keys()`] are used first.
+    pub fn validators(self, count: u32) -> Self {
+        let Self { keyring: prev, .. } = self;
+
+        // Log a warning if we are about to overwrite any existing keys.
+        if !prev.is_empty() {
+            tracing::warn!(
+                count = %prev.len(),
+                "builder overwriting entries in keyring, this may be a bug!"
+            );
+        }
+
+        // Generate the keys and place them in the keyring.
+        let mut keyring = Keyring::new();
+        for index in 0..count {
+            let key = match self.keys.get(index as usize) {
+                Some(key) => key.clone(),
+                None => Self::generate_key(self.seed, index),
+            };
+            Self::add_key(&mut keyring, key);
+        }
+
+        Self { keyring, ..self }
+    }
+
+    /// Generates consensus keys for the validator at `index`.
+    ///
+    /// If a `seed` is provided, the keys are derived from the seed and the index.
+    fn generate_key(seed: Option<[u8; 32]>, index: u32) -> (SigningKey, VerificationKey) {
+        let sk = match seed {
+            Some(seed) => {
+                let mut hasher = Sha256::new();
+                hasher.update(seed);
+                hasher.update(index.to_le_bytes());
+                ed25519_consensus::SigningKey::from(<[u8; 32]>::from(hasher.finalize()))
+            }
+            None => ed25519_consensus::SigningKey::new(rand_core::OsRng),
+        };
         let vk = sk.verification_key();
         tracing::trace!(verification_key = ?vk, "generated consensus key");
 
@@ -167,6 +211,18 @@ impl Builder {
         }
     }
 
+    /// Sets the height of the first block executed after genesis. If not called,
+    /// the first block has height 1.
+    ///
+    /// Genesis is still applied as usual, and the chain then jumps straight to
+    /// `initial_height`, skipping the blocks in between.
+    pub fn with_initial_height(self, initial_height: u64) -> Self {
+        Self {
+            initial_height: Some(initial_height),
+            ..self
+        }
+    }
+
     /// Sets the keys used by validators.
     pub fn with_keys(self, keys: Vec<(SigningKey, VerificationKey)>) -> Self {
         let Self {
@@ -181,6 +237,26 @@ impl Builder {
         Self { keys: keys, ..self }
     }
 
+    /// Sets a seed from which validator keys are generated.
+    ///
+    /// The same seed always produces the same keys, so that tests depending on the
+    /// validator set, such as the ordering of validator identities, are reproducible.
+    /// Keys provided via [`Builder::with_keys()`] take precedence over generated ones.
+    pub fn with_seed(self, seed: [u8; 32]) -> Self {
+        let Self {
+            keyring: ref prev, ..
+        } = self;
+
+        if !prev.is_empty() {
+            panic!("with_seed should be called prior to constructing the keyring");
+        }
+
+        Self {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
     /// Add the provided Tendermint [`Genesis`] to the builder.
     ///
     /// This will override other configurations and hardcode the genesis data.
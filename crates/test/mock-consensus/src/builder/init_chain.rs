@@ -46,6 +46,7 @@ impl Builder {
             keyring,
             on_block,
             initial_timestamp,
+            initial_height,
             ts_callback,
             chain_id,
             keys: _,
@@ -60,6 +61,12 @@ impl Builder {
         )?;
 
         let timestamp = initial_timestamp.unwrap_or(Time::now());
+        // The height of the node is that of the last block, which precedes the first one.
+        let height = match initial_height {
+            Some(0) => bail!("the first block cannot have height 0"),
+            Some(initial_height) => Height::try_from(initial_height - 1)?,
+            None => Height::from(0_u8),
+        };
         let request = match hardcoded_genesis {
             // If there is a hardcoded genesis, ignore whatever else was configured on the builder.
             Some(genesis) => ConsensusRequest::InitChain(InitChain {
@@ -78,9 +85,13 @@ impl Builder {
                 initial_height: Height::try_from(genesis.initial_height)?,
             }),
             // Use whatever state was configured on the builder.
-            None => {
-                Self::init_chain_request(app_state, &keyring, chain_id.clone(), timestamp.clone())?
-            }
+            None => Self::init_chain_request(
+                app_state,
+                &keyring,
+                chain_id.clone(),
+                timestamp.clone(),
+                initial_height.map(Height::try_from).transpose()?,
+            )?,
         };
         let service = consensus
             .ready()
@@ -143,7 +154,9 @@ impl Builder {
 
         Ok(TestNode {
             consensus,
-            height: block::Height::from(0_u8),
+            height,
+            last_begin_block_events: Vec::new(),
+            absences: Vec::new(),
             last_app_hash: app_hash.as_bytes().to_owned(),
             // TODO: hook this up correctly
             last_validator_set_hash: Some(
@@ -182,6 +195,7 @@ impl Builder {
         keyring: &BTreeMap<ed25519_consensus::VerificationKey, ed25519_consensus::SigningKey>,
         chain_id: tendermint::chain::Id,
         timestamp: Time,
+        initial_height: Option<Height>,
     ) -> Result<ConsensusRequest, anyhow::Error> {
         let consensus_params = Self::consensus_params();
 
@@ -206,7 +220,7 @@ impl Builder {
                 })
                 .collect::<Vec<_>>(),
             app_state_bytes,
-            initial_height: 0_u32.into(),
+            initial_height: initial_height.unwrap_or(0_u32.into()),
         }))
     }
 
@@ -31,8 +31,12 @@
 
 use {
     ed25519_consensus::{SigningKey, VerificationKey},
-    std::collections::BTreeMap,
+    std::{
+        collections::BTreeMap,
+        ops::{Bound, RangeBounds},
+    },
     tendermint::{
+        abci::Event,
         block::{Commit, Height},
         Time,
     },
@@ -84,6 +88,8 @@ pub struct TestNode<C> {
     last_validator_set_hash: Option<tendermint::Hash>,
     /// The last tendermint block header commit value.
     last_commit: Option<tendermint::block::Commit>,
+    /// The events emitted during the last `BeginBlock` request.
+    last_begin_block_events: Vec<Event>,
     /// The consensus params hash.
     consensus_params_hash: Vec<u8>,
     /// The current block [`Height`][tendermint::block::Height].
@@ -92,6 +98,10 @@ pub struct TestNode<C> {
     ///
     /// Entries in this keyring consist of a [`VerificationKey`] and a [`SigningKey`].
     keyring: Keyring,
+    /// Validators that should not sign blocks at the given heights.
+    ///
+    /// See [`TestNode::mark_absent()`].
+    absences: Vec<(VerificationKey, (Bound<u64>, Bound<u64>))>,
     /// A callback that will be invoked when a new block is constructed.
     on_block: Option<OnBlockFn>,
     /// A callback that will be invoked when a new block is committed, to produce the next timestamp.
@@ -128,6 +138,13 @@ impl<C> TestNode<C> {
         self.last_commit.as_ref()
     }
 
+    /// Returns the events emitted while beginning the last block.
+    ///
+    /// These are not included in the events returned by [`block::Builder::execute()`].
+    pub fn last_begin_block_events(&self) -> &[Event] {
+        &self.last_begin_block_events
+    }
+
     /// Returns the last `validator_set_hash` value.
     pub fn last_validator_set_hash(&self) -> Option<&tendermint::Hash> {
         self.last_validator_set_hash.as_ref()
@@ -164,6 +181,26 @@ impl<C> TestNode<C> {
     }
 }
 
+/// Validator uptime interfaces.
+impl<C> TestNode<C> {
+    /// Marks the validator with the given consensus key as absent for the given block heights.
+    ///
+    /// An absent validator does not sign the blocks at these heights, so its signature is left out
+    /// of their commits. This can be used to drive the application's uptime tracking, e.g. to
+    /// test that a validator is slashed for downtime.
+    pub fn mark_absent(&mut self, validator: VerificationKey, heights: impl RangeBounds<u64>) {
+        let heights = (heights.start_bound().cloned(), heights.end_bound().cloned());
+        self.absences.push((validator, heights));
+    }
+
+    /// Returns true if the validator with the given consensus key is absent at the given height.
+    fn is_absent(&self, validator: &VerificationKey, height: u64) -> bool {
+        self.absences
+            .iter()
+            .any(|(absent, heights)| absent == validator && heights.contains(&height))
+    }
+}
+
 /// Fast forward interfaces.
 impl<C> TestNode<C>
 where
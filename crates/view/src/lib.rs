@@ -17,6 +17,7 @@ mod client;
 mod metrics;
 mod note_record;
 mod planner;
+mod position_transfer;
 mod service;
 mod status;
 mod storage;
@@ -29,8 +30,11 @@ pub use crate::client::ViewClient;
 pub use crate::metrics::register_metrics;
 pub use crate::note_record::SpendableNoteRecord;
 pub use crate::planner::Planner;
+pub use crate::position_transfer::received_active_positions;
 pub use crate::service::ViewServer;
 pub use crate::status::StatusStreamResponse;
 pub use crate::storage::Storage;
-pub use crate::swap_record::SwapRecord;
+pub use crate::swap_record::{
+    swaps_nearing_claim_deadline, PendingClaim, SwapOutputIndex, SwapRecord,
+};
 pub use crate::transaction_info::TransactionInfo;
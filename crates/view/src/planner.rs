@@ -23,12 +23,12 @@ use penumbra_community_pool::CommunityPoolDeposit;
 use penumbra_dex::{
     lp::action::{PositionClose, PositionOpen},
     lp::plan::PositionWithdrawPlan,
-    lp::position::{self, Position},
+    lp::position::{self, Position, PriceThreshold},
     lp::Reserves,
     swap::SwapPlaintext,
     swap::SwapPlan,
     swap_claim::SwapClaimPlan,
-    TradingPair,
+    DirectedTradingPair, TradingPair,
 };
 use penumbra_fee::{Fee, FeeTier, GasPrices};
 use penumbra_governance::{
@@ -65,6 +65,8 @@ pub struct Planner<R: RngCore + CryptoRng> {
     memo_text: Option<String>,
     /// A user-specified memo return address, if any.
     memo_return_address: Option<Address>,
+    /// The fee paid for each position opened by this transaction.
+    position_open_fee: Amount,
 }
 
 impl<R: RngCore + CryptoRng> Debug for Planner<R> {
@@ -77,6 +79,7 @@ impl<R: RngCore + CryptoRng> Debug for Planner<R> {
             .field("change_address", &self.change_address)
             .field("memo_text", &self.memo_text)
             .field("memo_return_address", &self.memo_return_address)
+            .field("position_open_fee", &self.position_open_fee)
             .finish()
     }
 }
@@ -93,6 +96,7 @@ impl<R: RngCore + CryptoRng> Planner<R> {
             change_address: None,
             memo_text: None,
             memo_return_address: None,
+            position_open_fee: Amount::zero(),
         }
     }
 
@@ -116,6 +120,15 @@ impl<R: RngCore + CryptoRng> Planner<R> {
         self
     }
 
+    /// Set the fee paid for each position opened by the transaction.
+    ///
+    /// This must cover the position open fee in the current DEX parameters.
+    #[instrument(skip(self))]
+    pub fn set_position_open_fee(&mut self, fee: Amount) -> &mut Self {
+        self.position_open_fee = fee;
+        self
+    }
+
     /// Set the expiry height for the transaction.
     #[instrument(skip(self))]
     pub fn expiry_height(&mut self, expiry_height: u64) -> &mut Self {
@@ -175,7 +188,10 @@ impl<R: RngCore + CryptoRng> Planner<R> {
     /// Open a liquidity position in the order book.
     #[instrument(skip(self))]
     pub fn position_open(&mut self, position: Position) -> &mut Self {
-        self.action_list.push(PositionOpen { position });
+        self.action_list.push(PositionOpen {
+            position,
+            open_fee: self.position_open_fee,
+        });
         self
     }
 
@@ -186,6 +202,25 @@ impl<R: RngCore + CryptoRng> Planner<R> {
         self
     }
 
+    /// Close all of the given liquidity positions on `pair` whose effective price is
+    /// beyond the `threshold`, in one transaction.
+    ///
+    /// Prices are those at which the positions sell `pair.end` for `pair.start`, and a
+    /// position priced exactly at the threshold is left open, as described by
+    /// [`position::positions_beyond_price`].
+    #[instrument(skip(self, positions))]
+    pub fn position_close_beyond_price<'a>(
+        &mut self,
+        positions: impl IntoIterator<Item = &'a Position>,
+        pair: &DirectedTradingPair,
+        threshold: PriceThreshold,
+    ) -> &mut Self {
+        for position_id in position::positions_beyond_price(positions, pair, threshold) {
+            self.position_close(position_id);
+        }
+        self
+    }
+
     /// Withdraw a liquidity position in the order book.
     ///
     /// Note: Currently this only supports an initial withdrawal from Closed, with no rewards.
@@ -0,0 +1,93 @@
+use std::collections::BTreeSet;
+
+use penumbra_asset::asset;
+use penumbra_dex::lp::{position, LpNft};
+
+use crate::SpendableNoteRecord;
+
+/// Returns the IDs of the open positions whose LP NFTs are held by the given notes.
+///
+/// LP NFTs can be transferred between wallets, so a wallet may come to control a
+/// position it did not open. A note is only recognized as an LP NFT if its asset
+/// metadata is in the `cache`, and only unspent notes are considered.
+///
+/// The LP NFT of a position is replaced each time the position changes state, so a
+/// note holding the NFT of a closed or withdrawn position does not resolve to an
+/// active position.
+pub fn received_active_positions<'a>(
+    notes: impl IntoIterator<Item = &'a SpendableNoteRecord>,
+    cache: &asset::Cache,
+) -> Vec<position::Id> {
+    notes
+        .into_iter()
+        .filter(|record| record.height_spent.is_none())
+        .filter_map(|record| cache.get_by_id(record.note.asset_id()))
+        .filter_map(|metadata| LpNft::try_from(metadata).ok())
+        .filter(|lp_nft| lp_nft.state() == position::State::Opened)
+        .map(|lp_nft| lp_nft.position_id())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use decaf377::Fq;
+    use penumbra_asset::Value;
+    use penumbra_keys::{keys::AddressIndex, test_keys};
+    use penumbra_sct::{CommitmentSource, Nullifier};
+    use penumbra_shielded_pool::Note;
+    use rand_core::OsRng;
+
+    use super::*;
+
+    fn note_record(asset_id: asset::Id, height_spent: Option<u64>) -> SpendableNoteRecord {
+        let note = Note::generate(
+            &mut OsRng,
+            &test_keys::ADDRESS_0,
+            Value {
+                amount: 1u64.into(),
+                asset_id,
+            },
+        );
+        SpendableNoteRecord {
+            note_commitment: note.commit(),
+            note,
+            address_index: AddressIndex::new(0),
+            nullifier: Nullifier(Fq::rand(&mut OsRng)),
+            height_created: 1,
+            height_spent,
+            position: 0u64.into(),
+            source: CommitmentSource::Transaction { id: None },
+            return_address: None,
+        }
+    }
+
+    #[test]
+    fn only_open_position_nfts_resolve_to_active_positions() {
+        let open = LpNft::new(position::Id([1; 32]), position::State::Opened);
+        let withdrawn = LpNft::new(
+            position::Id([2; 32]),
+            position::State::Withdrawn { sequence: 0 },
+        );
+        let spent = LpNft::new(position::Id([3; 32]), position::State::Opened);
+        let unknown = LpNft::new(position::Id([4; 32]), position::State::Opened);
+
+        let mut cache = asset::Cache::with_known_assets();
+        cache.extend([open.denom(), withdrawn.denom(), spent.denom()]);
+        let gm = cache.get_unit("gm").expect("gm is a known asset");
+
+        let notes = [
+            note_record(open.asset_id(), None),
+            note_record(withdrawn.asset_id(), None),
+            note_record(spent.asset_id(), Some(2)),
+            note_record(unknown.asset_id(), None),
+            note_record(gm.id(), None),
+        ];
+
+        assert_eq!(
+            received_active_positions(&notes, &cache),
+            vec![open.position_id()]
+        );
+    }
+}
@@ -518,6 +518,7 @@ impl ViewService for ViewServer {
         // fee paid
         let mut planner = Planner::new(OsRng);
         planner.set_gas_prices(gas_prices);
+        planner.set_position_open_fee(app_params.dex_params.position_open_fee);
         planner.expiry_height(prq.expiry_height);
 
         for output in prq.outputs {
@@ -495,6 +495,31 @@ impl Storage {
         }
     }
 
+    /// Query for the commitment of the swap whose claim reveals `nullifier`.
+    ///
+    /// The chain cannot link a claim to the swap it spends, since the swap commitment
+    /// is hidden by the claim proof, but the wallet that made the swap can. Returns
+    /// `None` if the nullifier is not that of one of this wallet's swaps.
+    pub async fn swap_by_nullifier(
+        &self,
+        nullifier: &Nullifier,
+    ) -> anyhow::Result<Option<StateCommitment>> {
+        let pool = self.pool.clone();
+        let nullifier = nullifier.to_bytes().to_vec();
+
+        spawn_blocking(move || {
+            pool.get()?
+                .prepare_cached("SELECT swap_commitment FROM swaps WHERE nullifier = ?1")?
+                .query_and_then([nullifier], |row| {
+                    let bytes: Vec<u8> = row.get("swap_commitment")?;
+                    StateCommitment::try_from(bytes.as_slice()).context("invalid swap commitment")
+                })?
+                .next()
+                .transpose()
+        })
+        .await?
+    }
+
     /// Query for all unclaimed swaps.
     pub async fn unclaimed_swaps(&self) -> anyhow::Result<Vec<SwapRecord>> {
         let pool = self.pool.clone();
@@ -1728,6 +1753,18 @@ impl Storage {
         &self,
         return_address: &Address,
     ) -> anyhow::Result<Vec<SpendableNoteRecord>> {
+        let mut records = self
+            .notes_by_senders(std::slice::from_ref(return_address))
+            .await?;
+        Ok(records.pop().unwrap_or_default())
+    }
+
+    /// Get the notes sent by each of `return_addresses`, in the same order, reading them
+    /// all over a single connection.
+    pub async fn notes_by_senders(
+        &self,
+        return_addresses: &[Address],
+    ) -> anyhow::Result<Vec<Vec<SpendableNoteRecord>>> {
         let pool = self.pool.clone();
 
         let query = "SELECT notes.note_commitment,
@@ -1746,12 +1783,20 @@ impl Storage {
             JOIN tx ON spendable_notes.tx_hash = tx.tx_hash
             WHERE tx.return_address = ?1";
 
-        let return_address = return_address.to_vec();
+        let return_addresses: Vec<Vec<u8>> = return_addresses
+            .iter()
+            .map(|address| address.to_vec())
+            .collect();
 
         let records = spawn_blocking(move || {
-            pool.get()?
-                .prepare(query)?
-                .query_and_then([return_address], |record| record.try_into())?
+            let conn = pool.get()?;
+            let mut stmt = conn.prepare(query)?;
+            return_addresses
+                .into_iter()
+                .map(|return_address| {
+                    stmt.query_and_then([return_address], |record| record.try_into())?
+                        .collect::<anyhow::Result<Vec<_>>>()
+                })
                 .collect::<anyhow::Result<Vec<_>>>()
         })
         .await??;
@@ -1786,3 +1831,88 @@ impl Storage {
         .await?
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use penumbra_dex::{swap::SwapPlaintext, BatchSwapOutputData};
+    use penumbra_keys::test_keys;
+    use rand_core::OsRng;
+
+    use super::*;
+
+    /// Record a new, unclaimed swap in `storage`, as syncing the block including it would.
+    fn record_swap(storage: &Storage, swap: &SwapRecord) -> anyhow::Result<()> {
+        storage.pool.get()?.execute(
+            "INSERT INTO swaps (swap_commitment, swap, position, nullifier, output_data, height_claimed, source)
+            VALUES (?1, ?2, ?3, ?4, ?5, NULL, ?6)",
+            (
+                swap.swap_commitment.0.to_bytes().to_vec(),
+                swap.swap.encode_to_vec(),
+                u64::from(swap.position) as i64,
+                swap.nullifier.to_bytes().to_vec(),
+                swap.output_data.encode_to_vec(),
+                swap.source.encode_to_vec(),
+            ),
+        )?;
+        Ok(())
+    }
+
+    fn swap_record() -> SwapRecord {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let trading_pair = TradingPair::new(gm.id(), gn.id());
+        let swap = SwapPlaintext::new(
+            &mut OsRng,
+            trading_pair,
+            100u64.into(),
+            0u64.into(),
+            Default::default(),
+            (*test_keys::ADDRESS_0).clone(),
+        );
+        SwapRecord {
+            swap_commitment: swap.swap_commitment(),
+            swap,
+            position: 0u64.into(),
+            nullifier: Nullifier(Fq::rand(&mut OsRng)),
+            output_data: BatchSwapOutputData {
+                delta_1: 100u64.into(),
+                delta_2: 0u64.into(),
+                lambda_1: 0u64.into(),
+                lambda_2: 100u64.into(),
+                unfilled_1: 0u64.into(),
+                unfilled_2: 0u64.into(),
+                height: 1,
+                trading_pair,
+                sct_position_prefix: 0u64.into(),
+            },
+            height_claimed: None,
+            source: CommitmentSource::Transaction { id: None },
+        }
+    }
+
+    #[tokio::test]
+    async fn swaps_are_found_by_their_nullifier() -> anyhow::Result<()> {
+        let storage = Storage::initialize(
+            None::<&str>,
+            test_keys::FULL_VIEWING_KEY.clone(),
+            AppParameters::default(),
+        )
+        .await?;
+        let swap = swap_record();
+        record_swap(&storage, &swap)?;
+
+        assert_eq!(
+            storage.swap_by_nullifier(&swap.nullifier).await?,
+            Some(swap.swap_commitment)
+        );
+        // A nullifier which doesn't belong to any of our swaps isn't an error.
+        assert_eq!(
+            storage
+                .swap_by_nullifier(&Nullifier(Fq::rand(&mut OsRng)))
+                .await?,
+            None
+        );
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use penumbra_dex::{swap::SwapPlaintext, BatchSwapOutputData};
 use penumbra_proto::{view::v1 as pb, DomainType};
 use penumbra_sct::{CommitmentSource, Nullifier};
@@ -83,3 +85,168 @@ impl TryFrom<&Row<'_>> for SwapRecord {
         })
     }
 }
+
+/// A swap that has not been claimed yet, and whose claim deadline is approaching.
+#[derive(Debug, Clone)]
+pub struct PendingClaim<'a> {
+    pub swap: &'a SwapRecord,
+    /// The number of blocks left until the claim deadline, zero if it has passed.
+    pub blocks_remaining: u64,
+}
+
+/// Returns the unclaimed `swaps` that are within `warning_threshold` blocks of their
+/// claim deadline at `current_height`, so that the user can be reminded to claim them.
+///
+/// The protocol does not expire swap outputs, so the deadline is chosen by the wallet: it
+/// falls `claim_window` blocks after the swap's batch was executed. Swaps past their
+/// deadline are returned with no blocks remaining.
+pub fn swaps_nearing_claim_deadline(
+    swaps: &[SwapRecord],
+    current_height: u64,
+    claim_window: u64,
+    warning_threshold: u64,
+) -> Vec<PendingClaim<'_>> {
+    swaps
+        .iter()
+        .filter(|swap| swap.height_claimed.is_none())
+        .filter_map(|swap| {
+            let deadline = swap.output_data.height.saturating_add(claim_window);
+            let blocks_remaining = deadline.saturating_sub(current_height);
+            (blocks_remaining <= warning_threshold).then_some(PendingClaim {
+                swap,
+                blocks_remaining,
+            })
+        })
+        .collect()
+}
+
+/// Resolves the output notes of swap claims back to the swaps they claimed.
+///
+/// The outputs of a swap claim are fully determined by the swap plaintext and the
+/// output data of its batch, so they can be indexed whether or not the swap has been
+/// claimed yet.
+#[derive(Debug, Clone, Default)]
+pub struct SwapOutputIndex {
+    swap_by_output: BTreeMap<tct::StateCommitment, tct::StateCommitment>,
+}
+
+impl SwapOutputIndex {
+    /// Index the outputs of the claims of all of the given `swaps`.
+    pub fn new<'a>(swaps: impl IntoIterator<Item = &'a SwapRecord>) -> Self {
+        let mut index = Self::default();
+        for swap in swaps {
+            index.insert(swap);
+        }
+        index
+    }
+
+    /// Index the two output notes of the claim of `swap`.
+    pub fn insert(&mut self, swap: &SwapRecord) {
+        let (output_1, output_2) = swap.swap.output_notes(&swap.output_data);
+        for output in [output_1, output_2] {
+            self.swap_by_output
+                .insert(output.commit(), swap.swap_commitment);
+        }
+    }
+
+    /// Returns the commitment of the swap whose claim produced the note with the
+    /// given commitment, or `None` if the note was not produced by an indexed swap claim.
+    pub fn swap_for_output(
+        &self,
+        note_commitment: &tct::StateCommitment,
+    ) -> Option<tct::StateCommitment> {
+        self.swap_by_output.get(note_commitment).copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use decaf377::Fq;
+    use penumbra_asset::asset;
+    use penumbra_dex::TradingPair;
+    use penumbra_keys::test_keys;
+    use rand_core::OsRng;
+
+    use super::*;
+
+    fn swap_record(height: u64, height_claimed: Option<u64>) -> SwapRecord {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        let trading_pair = TradingPair::new(gm.id(), gn.id());
+        let swap = SwapPlaintext::new(
+            &mut OsRng,
+            trading_pair,
+            100u64.into(),
+            0u64.into(),
+            Default::default(),
+            (*test_keys::ADDRESS_0).clone(),
+        );
+        SwapRecord {
+            swap_commitment: swap.swap_commitment(),
+            swap,
+            position: 0u64.into(),
+            nullifier: Nullifier(Fq::rand(&mut OsRng)),
+            output_data: BatchSwapOutputData {
+                delta_1: 100u64.into(),
+                delta_2: 0u64.into(),
+                lambda_1: 0u64.into(),
+                lambda_2: 100u64.into(),
+                unfilled_1: 0u64.into(),
+                unfilled_2: 0u64.into(),
+                height,
+                trading_pair,
+                sct_position_prefix: 0u64.into(),
+            },
+            height_claimed,
+            source: CommitmentSource::Transaction { id: None },
+        }
+    }
+
+    #[test]
+    fn only_unclaimed_swaps_near_their_deadline_are_returned() {
+        let swaps = vec![
+            // Fresh: the deadline is 1000 blocks away.
+            swap_record(1000, None),
+            // Near the deadline: 50 blocks to go.
+            swap_record(150, None),
+            // Past the deadline.
+            swap_record(10, None),
+            // Near the deadline, but already claimed.
+            swap_record(150, Some(160)),
+        ];
+
+        let pending = swaps_nearing_claim_deadline(&swaps, 1100, 1000, 100);
+
+        let returned: Vec<_> = pending
+            .iter()
+            .map(|claim| (claim.swap.output_data.height, claim.blocks_remaining))
+            .collect();
+        assert_eq!(returned, vec![(150, 50), (10, 0)]);
+    }
+
+    #[test]
+    fn swap_claim_outputs_resolve_to_their_swap() {
+        let claimed = swap_record(100, Some(110));
+        let unclaimed = swap_record(200, None);
+        let index = SwapOutputIndex::new([&claimed, &unclaimed]);
+
+        for record in [&claimed, &unclaimed] {
+            let (output_1, output_2) = record.swap.output_notes(&record.output_data);
+            assert_eq!(
+                index.swap_for_output(&output_1.commit()),
+                Some(record.swap_commitment)
+            );
+            assert_eq!(
+                index.swap_for_output(&output_2.commit()),
+                Some(record.swap_commitment)
+            );
+        }
+
+        // Neither the swap itself, nor a note it did not produce, resolves to a swap.
+        assert_eq!(index.swap_for_output(&claimed.swap_commitment), None);
+        assert_eq!(
+            index.swap_for_output(&tct::StateCommitment(Fq::rand(&mut OsRng))),
+            None
+        );
+    }
+}
@@ -5,15 +5,29 @@ use decaf377::Fq;
 use rand_core::{CryptoRng, RngCore};
 use tracing::instrument;
 
-use penumbra_dex::swap_claim::SwapClaimPlan;
-use penumbra_keys::keys::AddressIndex;
+use penumbra_asset::{asset, Value};
+use penumbra_dex::{
+    lp::position::{self, Position},
+    swap::{SwapPlaintext, SwapPlan},
+    swap_claim::SwapClaimPlan,
+    TradingPair,
+};
+use penumbra_fee::Fee;
+use penumbra_keys::{keys::AddressIndex, Address};
+use penumbra_num::Amount;
 use penumbra_proto::view::v1::NotesRequest;
 use penumbra_transaction::{TransactionParameters, TransactionPlan};
 pub use penumbra_view::Planner;
-use penumbra_view::{SpendableNoteRecord, ViewClient};
+use penumbra_view::{SpendableNoteRecord, SwapRecord, ViewClient};
 
 pub const SWEEP_COUNT: usize = 8;
 
+/// The largest number of swap claims batched into a single transaction by [`claim_swaps`].
+///
+/// A swap claim action takes up less than a kilobyte, so this keeps claim transactions
+/// well within the chain's 96 KiB limit on the size of a transaction.
+pub const MAX_SWAP_CLAIMS_PER_TRANSACTION: usize = 64;
+
 #[instrument(skip(view, rng))]
 pub async fn sweep<V, R>(view: &mut V, mut rng: R) -> anyhow::Result<Vec<TransactionPlan>>
 where
@@ -84,6 +98,192 @@ where
     Ok(plans)
 }
 
+/// Plans claims for `swaps`, batching as many of them as possible into each transaction.
+///
+/// Swaps whose batch has not cleared yet, and swaps that were already claimed, are skipped.
+#[instrument(skip(view, swaps, rng))]
+pub async fn claim_swaps<V, R>(
+    view: &mut V,
+    swaps: &[SwapPlaintext],
+    rng: R,
+) -> anyhow::Result<Vec<TransactionPlan>>
+where
+    V: ViewClient,
+    R: RngCore + CryptoRng,
+{
+    let app_params = view.app_params().await?;
+    let unclaimed_swaps = view.unclaimed_swaps().await?;
+
+    Ok(plan_swap_claims(
+        swaps,
+        &unclaimed_swaps,
+        &app_params.chain_id,
+        app_params.sct_params.epoch_duration,
+        rng,
+    ))
+}
+
+/// Plans claims for those of `swaps` found among the `unclaimed_swaps`, i.e. those whose
+/// batch has cleared.
+///
+/// Since the claim fees of a transaction's swaps pay for it, swaps are batched together
+/// with others whose claim fee is in the same asset, at most
+/// [`MAX_SWAP_CLAIMS_PER_TRANSACTION`] at a time.
+pub fn plan_swap_claims<R>(
+    swaps: &[SwapPlaintext],
+    unclaimed_swaps: &[SwapRecord],
+    chain_id: &str,
+    epoch_duration: u64,
+    mut rng: R,
+) -> Vec<TransactionPlan>
+where
+    R: RngCore + CryptoRng,
+{
+    let commitments: Vec<_> = swaps.iter().map(SwapPlaintext::swap_commitment).collect();
+
+    let mut swaps_by_fee_asset = BTreeMap::<_, Vec<&SwapRecord>>::new();
+    for swap in unclaimed_swaps {
+        if commitments.contains(&swap.swap_commitment) {
+            swaps_by_fee_asset
+                .entry(swap.swap.claim_fee.asset_id())
+                .or_default()
+                .push(swap);
+        }
+    }
+
+    let mut plans = Vec::new();
+    for (asset_id, swaps) in swaps_by_fee_asset {
+        for batch in swaps.chunks(MAX_SWAP_CLAIMS_PER_TRANSACTION) {
+            let fee = batch
+                .iter()
+                .map(|swap| swap.swap.claim_fee.amount())
+                .fold(Amount::zero(), |total, amount| total + amount);
+
+            let mut plan = TransactionPlan {
+                transaction_parameters: TransactionParameters {
+                    chain_id: chain_id.to_string(),
+                    fee: Fee(Value {
+                        amount: fee,
+                        asset_id,
+                    }),
+                    ..Default::default()
+                },
+                // The transaction doesn't need a memo, because it's to ourselves.
+                memo: None,
+                ..Default::default()
+            };
+            for swap in batch {
+                let action_plan = SwapClaimPlan {
+                    swap_plaintext: swap.swap.clone(),
+                    position: swap.position,
+                    output_data: swap.output_data,
+                    epoch_duration,
+                    proof_blinding_r: Fq::rand(&mut rng),
+                    proof_blinding_s: Fq::rand(&mut rng),
+                };
+                plan.actions.push(action_plan.into());
+            }
+            plans.push(plan);
+        }
+    }
+
+    plans
+}
+
+/// Plans a swap of `input_asset` for the `fee` needed to pay for a transaction.
+///
+/// The input of the swap is computed from `positions`, the open liquidity positions
+/// trading `input_asset` for the fee asset, as the cheapest way to fill the fee. The
+/// swap's `claim_fee` is prepaid along with the swap, and once the batch has cleared,
+/// the swap can be claimed with [`claim_swaps`].
+///
+/// Returns an error if the positions don't have enough of the fee asset to cover `fee`.
+pub fn plan_fee_swap<R>(
+    fee: Value,
+    input_asset: asset::Id,
+    positions: &[Position],
+    claim_fee: Fee,
+    claim_address: Address,
+    mut rng: R,
+) -> anyhow::Result<SwapPlan>
+where
+    R: RngCore + CryptoRng,
+{
+    let input = fee_swap_input(fee, input_asset, positions)?;
+
+    let trading_pair = TradingPair::new(input_asset, fee.asset_id);
+    let (delta_1, delta_2) = if trading_pair.asset_1() == input_asset {
+        (input.amount, Amount::zero())
+    } else {
+        (Amount::zero(), input.amount)
+    };
+    let swap_plaintext = SwapPlaintext::new(
+        &mut rng,
+        trading_pair,
+        delta_1,
+        delta_2,
+        claim_fee,
+        claim_address,
+    );
+
+    Ok(SwapPlan::new(&mut rng, swap_plaintext))
+}
+
+/// Computes the input of `input_asset` needed to acquire `fee` from `positions`,
+/// filling against the best priced positions first.
+fn fee_swap_input(
+    fee: Value,
+    input_asset: asset::Id,
+    positions: &[Position],
+) -> anyhow::Result<Value> {
+    if input_asset == fee.asset_id {
+        anyhow::bail!("the input asset is already the fee asset {}", fee.asset_id);
+    }
+
+    let mut positions: Vec<_> = positions
+        .iter()
+        .filter(|position| position.state == position::State::Opened)
+        .filter_map(|position| {
+            let phi = position.phi.orient_start(input_asset)?;
+            let available = position.reserves_for(fee.asset_id)?;
+            Some((phi.effective_price(), available, position))
+        })
+        .collect();
+    positions.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+
+    let mut needed = fee.amount;
+    let mut input = Amount::zero();
+    for (_, available, position) in positions {
+        if needed == Amount::zero() {
+            break;
+        }
+        let output = Value {
+            amount: needed.min(available),
+            asset_id: fee.asset_id,
+        };
+        let Some((_, filled_by)) = position.fill_output(output)? else {
+            continue;
+        };
+        input += filled_by.amount;
+        needed -= output.amount;
+    }
+
+    if needed > Amount::zero() {
+        anyhow::bail!(
+            "insufficient liquidity to acquire a fee of {} {} with {}: {} unfilled",
+            fee.amount,
+            fee.asset_id,
+            input_asset,
+            needed
+        );
+    }
+
+    Ok(Value {
+        amount: input,
+        asset_id: input_asset,
+    })
+}
+
 #[instrument(skip(view, rng))]
 async fn sweep_notes<V, R>(view: &mut V, mut rng: R) -> anyhow::Result<Vec<TransactionPlan>>
 where
@@ -143,3 +343,144 @@ where
 
     Ok(plans)
 }
+
+#[cfg(test)]
+mod tests {
+    use penumbra_dex::{lp::SellOrder, BatchSwapOutputData};
+    use penumbra_keys::test_keys;
+    use penumbra_sct::{CommitmentSource, Nullifier};
+    use penumbra_transaction::ActionPlan;
+    use rand_core::OsRng;
+
+    use super::*;
+
+    fn swap(claim_fee: Fee) -> SwapPlaintext {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let gn = asset::Cache::with_known_assets().get_unit("gn").unwrap();
+        SwapPlaintext::new(
+            &mut OsRng,
+            TradingPair::new(gm.id(), gn.id()),
+            100u64.into(),
+            0u64.into(),
+            claim_fee,
+            (*test_keys::ADDRESS_0).clone(),
+        )
+    }
+
+    /// The record of a swap whose batch cleared.
+    fn cleared(swap: &SwapPlaintext) -> SwapRecord {
+        SwapRecord {
+            swap_commitment: swap.swap_commitment(),
+            swap: swap.clone(),
+            position: 0u64.into(),
+            nullifier: Nullifier(Fq::rand(&mut OsRng)),
+            output_data: BatchSwapOutputData {
+                delta_1: 100u64.into(),
+                delta_2: 0u64.into(),
+                lambda_1: 0u64.into(),
+                lambda_2: 100u64.into(),
+                unfilled_1: 0u64.into(),
+                unfilled_2: 0u64.into(),
+                height: 1,
+                trading_pair: swap.trading_pair,
+                sct_position_prefix: 0u64.into(),
+            },
+            height_claimed: None,
+            source: CommitmentSource::Transaction { id: None },
+        }
+    }
+
+    fn num_claims(plan: &TransactionPlan) -> usize {
+        plan.actions
+            .iter()
+            .filter(|action| matches!(action, ActionPlan::SwapClaim(_)))
+            .count()
+    }
+
+    #[test]
+    fn fee_swap_fills_from_the_best_positions() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let penumbra = asset::Cache::with_known_assets()
+            .get_unit("penumbra")
+            .unwrap();
+        let positions: Vec<_> = ["100penumbra@3gm", "100penumbra@2gm"]
+            .into_iter()
+            .map(|order| SellOrder::parse_str(order).unwrap().into_position(OsRng))
+            .collect();
+
+        // 100penumbra at 2gm each, then 50penumbra at 3gm each.
+        let fee = penumbra.value(150u64.into());
+        let plan = plan_fee_swap(
+            fee,
+            gm.id(),
+            &positions,
+            Fee::default(),
+            (*test_keys::ADDRESS_0).clone(),
+            OsRng,
+        )
+        .unwrap();
+
+        let swap = plan.swap_plaintext;
+        assert_eq!(swap.trading_pair, TradingPair::new(gm.id(), penumbra.id()));
+        let input = if swap.trading_pair.asset_1() == gm.id() {
+            swap.delta_1_i
+        } else {
+            swap.delta_2_i
+        };
+        assert_eq!(input, gm.value(350u64.into()).amount);
+    }
+
+    #[test]
+    fn fee_swap_with_insufficient_liquidity_is_an_error() {
+        let gm = asset::Cache::with_known_assets().get_unit("gm").unwrap();
+        let penumbra = asset::Cache::with_known_assets()
+            .get_unit("penumbra")
+            .unwrap();
+        let positions = vec![SellOrder::parse_str("100penumbra@2gm")
+            .unwrap()
+            .into_position(OsRng)];
+
+        let fee = penumbra.value(150u64.into());
+        let error = plan_fee_swap(
+            fee,
+            gm.id(),
+            &positions,
+            Fee::default(),
+            (*test_keys::ADDRESS_0).clone(),
+            OsRng,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("insufficient liquidity"));
+    }
+
+    #[test]
+    fn pending_swaps_are_not_claimed() {
+        let fee = Fee::from_staking_token_amount(10u64.into());
+        let swaps: Vec<_> = (0..3).map(|_| swap(fee)).collect();
+        // The batch of the last swap hasn't cleared yet.
+        let unclaimed_swaps = vec![cleared(&swaps[0]), cleared(&swaps[1])];
+
+        let plans = plan_swap_claims(&swaps, &unclaimed_swaps, "test", 100, OsRng);
+
+        assert_eq!(plans.len(), 1);
+        assert_eq!(num_claims(&plans[0]), 2);
+        assert_eq!(
+            plans[0].transaction_parameters.fee,
+            Fee::from_staking_token_amount(20u64.into())
+        );
+    }
+
+    #[test]
+    fn claims_are_split_across_transactions() {
+        let fee = Fee::from_staking_token_amount(1u64.into());
+        let swaps: Vec<_> = (0..MAX_SWAP_CLAIMS_PER_TRANSACTION + 1)
+            .map(|_| swap(fee))
+            .collect();
+        let unclaimed_swaps: Vec<_> = swaps.iter().map(cleared).collect();
+
+        let plans = plan_swap_claims(&swaps, &unclaimed_swaps, "test", 100, OsRng);
+
+        let claims: Vec<_> = plans.iter().map(num_claims).collect();
+        assert_eq!(claims, vec![MAX_SWAP_CLAIMS_PER_TRANSACTION, 1]);
+    }
+}
@@ -1,3 +1,41 @@
+use std::time::Duration;
+
+/// Configuration for the pool of database connections.
+///
+/// The defaults match those of an unconfigured pool.
+#[derive(Clone, Copy)]
+pub struct PoolConfig {
+    /// The maximum number of connections open at once.
+    pub max_size: u32,
+    /// How long to wait for a connection when all of them are in use, before failing.
+    pub connection_timeout: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            connection_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl PoolConfig {
+    pub fn with_max_size(mut self, x: Option<u32>) -> Self {
+        if let Some(x) = x {
+            self.max_size = x;
+        }
+        self
+    }
+
+    pub fn with_connection_timeout_secs(mut self, x: Option<u64>) -> Self {
+        if let Some(x) = x {
+            self.connection_timeout = Duration::from_secs(x);
+        }
+        self
+    }
+}
+
 /// Configuration for the summoner.
 #[derive(Clone, Copy)]
 pub struct Config {
@@ -5,6 +43,7 @@ pub struct Config {
     pub phase2_timeout_secs: u64,
     pub min_bid_u64: u64,
     pub max_strikes: u64,
+    pub pool: PoolConfig,
 }
 
 impl Default for Config {
@@ -14,6 +53,7 @@ impl Default for Config {
             phase2_timeout_secs: 8 * 60,
             min_bid_u64: 1,
             max_strikes: 3,
+            pool: PoolConfig::default(),
         }
     }
 }
@@ -46,4 +86,9 @@ impl Config {
         }
         self
     }
+
+    pub fn with_pool(mut self, pool: PoolConfig) -> Self {
+        self.pool = pool;
+        self
+    }
 }
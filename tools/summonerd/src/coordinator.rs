@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use anyhow::Result;
+use tokio::task::JoinHandle;
 
 use crate::{
     config::Config, participant::Participant, phase::Phase, queue::ParticipantQueue,
@@ -50,23 +51,29 @@ impl Coordinator {
         }
     }
 
+    /// Request a contribution from `contributor`, striking them if it times out.
+    ///
+    /// Their slot assignment is recorded in storage while the coordinator waits, so
+    /// that a [`spawn_timeout_reaper`] task can reclaim it if it's left behind.
     #[tracing::instrument(skip_all, fields(address = ?contributor.address().display_short_form()))]
     async fn contribute<P: Phase>(&mut self, mut contributor: Participant) -> Result<()> {
         let address = contributor.address();
-        match tokio::time::timeout(
-            Duration::from_secs(P::contribution_time(self.config)),
-            self.contribute_inner::<P>(&mut contributor),
-        )
-        .await
-        {
+        let timeout = Duration::from_secs(P::contribution_time(self.config));
+        self.storage.assign_slot(&address, timeout).await?;
+        match tokio::time::timeout(timeout, self.contribute_inner::<P>(&mut contributor)).await {
             Ok(Ok(_)) => Ok(()),
             Err(_) => {
-                tracing::info!("STRIKE (timeout)");
-                self.storage.strike(&address).await?;
+                // The reaper may have reclaimed the slot first, striking them already.
+                if self.storage.release_slot(&address, true).await? {
+                    tracing::info!("STRIKE (timeout)");
+                }
                 contributor.try_notify_timeout();
                 Ok(())
             }
-            Ok(Err(e)) => Err(e),
+            Ok(Err(e)) => {
+                self.storage.release_slot(&address, false).await?;
+                Err(e)
+            }
         }
     }
 
@@ -97,8 +104,33 @@ impl Coordinator {
                 return Ok(());
             }
         }
-        tracing::info!("STRIKE (invalid or partial contribution)");
-        self.storage.strike(&address).await?;
+        if self.storage.release_slot(&address, true).await? {
+            tracing::info!("STRIKE (invalid or partial contribution)");
+        }
         Ok(())
     }
 }
+
+/// Spawn a task reclaiming the slots whose assignments have expired, every `interval`.
+///
+/// The coordinator releases the slots it assigns itself, so this picks up those it
+/// doesn't, such as the ones left behind when a previous run stopped mid-contribution.
+pub fn spawn_timeout_reaper(storage: Storage, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match storage.reclaim_expired_slots().await {
+                Ok(reclaimed) => {
+                    for address in reclaimed {
+                        tracing::info!(
+                            address = ?address.display_short_form(),
+                            "STRIKE (slot reclaimed after timeout)"
+                        );
+                    }
+                }
+                Err(e) => tracing::warn!(?e, "failed to reclaim expired slots"),
+            }
+        }
+    })
+}
@@ -14,7 +14,7 @@ use ark_serialize::CanonicalSerialize;
 use camino::Utf8Path;
 use camino::Utf8PathBuf;
 use clap::Parser;
-use coordinator::Coordinator;
+use coordinator::{spawn_timeout_reaper, Coordinator};
 use decaf377::Bls12_377;
 use metrics_tracing_context::MetricsLayer;
 use penumbra_keys::FullViewingKey;
@@ -38,7 +38,7 @@ use tracing::Instrument;
 use tracing_subscriber::{prelude::*, EnvFilter};
 use url::Url;
 
-use crate::config::Config;
+use crate::config::{Config, PoolConfig};
 use crate::phase::Phase1;
 use crate::phase::Phase2;
 use crate::phase::PhaseMarker;
@@ -108,12 +108,18 @@ enum Command {
         /// Filepath to phase 1 root.
         #[clap(long, display_order = 200)]
         phase1_root: Utf8PathBuf,
+        /// Passphrase encrypting the sqlite3 database at rest, if it is encrypted.
+        #[clap(long, env = "SUMMONERD_DB_PASSPHRASE", hide_env_values = true)]
+        db_passphrase: Option<String>,
     },
     /// Transition between phases
     Transition {
         #[clap(long, display_order = 100)]
         /// Directory for storing the sqlite3 database containing contributions.
         storage_dir: Utf8PathBuf,
+        /// Passphrase encrypting the sqlite3 database at rest, if it is encrypted.
+        #[clap(long, env = "SUMMONERD_DB_PASSPHRASE", hide_env_values = true)]
+        db_passphrase: Option<String>,
     },
     /// Start the coordinator.
     Start {
@@ -140,6 +146,41 @@ enum Command {
         min_bid_u64: Option<u64>,
         #[clap(long, display_order = 1002)]
         max_strikes: Option<u64>,
+        /// The maximum number of database connections open at once.
+        #[clap(long, display_order = 1003)]
+        db_pool_max_size: Option<u32>,
+        /// How long to wait for a free database connection before failing a request.
+        #[clap(long, display_order = 1004)]
+        db_pool_timeout_secs: Option<u64>,
+        /// How long to reuse the amount an address has bid, as long as no new blocks
+        /// are synced. Zero disables the cache.
+        #[clap(long, display_order = 1005)]
+        amount_cache_ttl_secs: Option<u64>,
+        /// How often to reclaim the slots of participants whose time to contribute has
+        /// run out, e.g. after a restart. No reclaiming is done if this isn't set.
+        #[clap(long, display_order = 1006)]
+        slot_reaper_interval_secs: Option<u64>,
+        /// Passphrase encrypting the sqlite3 database at rest, if it is encrypted.
+        #[clap(long, env = "SUMMONERD_DB_PASSPHRASE", hide_env_values = true)]
+        db_passphrase: Option<String>,
+    },
+    /// Finalize the ceremony, so that no further contributions are accepted.
+    Finalize {
+        #[clap(long, display_order = 100)]
+        /// Directory for storing the sqlite3 database containing contributions.
+        storage_dir: Utf8PathBuf,
+        /// Passphrase encrypting the sqlite3 database at rest, if it is encrypted.
+        #[clap(long, env = "SUMMONERD_DB_PASSPHRASE", hide_env_values = true)]
+        db_passphrase: Option<String>,
+    },
+    /// Print a summary of the state of the ceremony.
+    Status {
+        #[clap(long, display_order = 100)]
+        /// Directory for storing the sqlite3 database containing contributions.
+        storage_dir: Utf8PathBuf,
+        /// Passphrase encrypting the sqlite3 database at rest, if it is encrypted.
+        #[clap(long, env = "SUMMONERD_DB_PASSPHRASE", hide_env_values = true)]
+        db_passphrase: Option<String>,
     },
     /// Export the output of the ceremony
     Export {
@@ -149,6 +190,9 @@ enum Command {
         #[clap(long, display_order = 200)]
         /// Directory for storing the exported ceremony output.
         target_dir: Utf8PathBuf,
+        /// Passphrase encrypting the sqlite3 database at rest, if it is encrypted.
+        #[clap(long, env = "SUMMONERD_DB_PASSPHRASE", hide_env_values = true)]
+        db_passphrase: Option<String>,
     },
 }
 
@@ -171,29 +215,51 @@ impl Opt {
                 phase2_timeout_secs,
                 min_bid_u64,
                 max_strikes,
+                db_pool_max_size,
+                db_pool_timeout_secs,
+                amount_cache_ttl_secs,
+                slot_reaper_interval_secs,
+                db_passphrase,
             } => {
                 let config = Config::default()
                     .with_phase1_timeout_secs(phase1_timeout_secs)
                     .with_phase2_timeout_secs(phase2_timeout_secs)
                     .with_min_bid_u64(min_bid_u64)
-                    .with_max_strikes(max_strikes);
+                    .with_max_strikes(max_strikes)
+                    .with_pool(
+                        PoolConfig::default()
+                            .with_max_size(db_pool_max_size)
+                            .with_connection_timeout_secs(db_pool_timeout_secs),
+                    );
                 let marker = match phase {
                     1 => PhaseMarker::P1,
                     2 => PhaseMarker::P2,
                     _ => anyhow::bail!("Phase must be 1 or 2."),
                 };
                 let storage =
-                    Storage::load_or_initialize(config, ceremony_db(&storage_dir)).await?;
+                    Storage::load_or_initialize(config, ceremony_db(&storage_dir), db_passphrase)
+                        .await?;
                 // Check if we've transitioned, for a nice error message
                 if marker == PhaseMarker::P2
                     && storage.transition_extra_information().await?.is_none()
                 {
                     anyhow::bail!("Please run the transition command before this command 8^)");
                 }
-                let knower =
-                    PenumbraKnower::load_or_initialize(storage_dir.join("penumbra.db"), &fvk, node)
-                        .await?;
+                let knower = PenumbraKnower::load_with_checkpoint(
+                    storage_dir.join("penumbra.db"),
+                    &fvk,
+                    node,
+                )
+                .await?
+                .with_amount_cache_ttl(
+                    amount_cache_ttl_secs
+                        .map(std::time::Duration::from_secs)
+                        .unwrap_or(penumbra_knower::DEFAULT_AMOUNT_CACHE_TTL),
+                );
                 let queue = ParticipantQueue::new();
+                if let Some(interval) = slot_reaper_interval_secs {
+                    spawn_timeout_reaper(storage.clone(), std::time::Duration::from_secs(interval));
+                }
                 let coordinator = Coordinator::new(config, storage.clone(), queue.clone());
                 let coordinator_span = tracing::error_span!("coordinator");
                 let coordinator_handle = match marker {
@@ -205,7 +271,7 @@ impl Opt {
                     }
                 };
                 let service =
-                    CoordinatorService::new(knower, storage.clone(), queue.clone(), marker);
+                    CoordinatorService::new(knower.clone(), storage.clone(), queue.clone(), marker);
                 let grpc_server = Server::builder().add_service(
                     CeremonyCoordinatorServiceServer::new(service)
                         .max_encoding_message_size(max_message_size(marker))
@@ -215,6 +281,7 @@ impl Opt {
                 let web_app = web_app(
                     fvk.payment_address(0u32.into()).0,
                     config,
+                    knower,
                     marker,
                     queue,
                     storage,
@@ -237,6 +304,7 @@ impl Opt {
             Command::Init {
                 storage_dir,
                 phase1_root,
+                db_passphrase,
             } => {
                 let file = File::open(phase1_root)?;
                 let mut reader = BufReader::new(file);
@@ -251,17 +319,26 @@ impl Opt {
                 // This is assumed to be valid as it's the starting point for the ceremony.
                 let phase_1_root = phase_1_raw_root.assume_valid();
 
-                let mut storage =
-                    Storage::load_or_initialize(Config::default(), ceremony_db(&storage_dir))
-                        .await?;
+                let mut storage = Storage::load_or_initialize(
+                    Config::default(),
+                    ceremony_db(&storage_dir),
+                    db_passphrase,
+                )
+                .await?;
                 storage.set_root(phase_1_root).await?;
 
                 Ok(())
             }
-            Command::Transition { storage_dir } => {
-                let mut storage =
-                    Storage::load_or_initialize(Config::default(), ceremony_db(&storage_dir))
-                        .await?;
+            Command::Transition {
+                storage_dir,
+                db_passphrase,
+            } => {
+                let mut storage = Storage::load_or_initialize(
+                    Config::default(),
+                    ceremony_db(&storage_dir),
+                    db_passphrase,
+                )
+                .await?;
 
                 let phase1_crs = match storage.phase1_current_crs().await? {
                     Some(x) => x,
@@ -272,13 +349,55 @@ impl Opt {
 
                 Ok(())
             }
-            Command::Export {
+            Command::Finalize {
                 storage_dir,
-                target_dir,
+                db_passphrase,
             } => {
+                // Finalizing a ceremony that doesn't exist is a mistake, not a fresh start.
                 let storage =
-                    Storage::load_or_initialize(Config::default(), ceremony_db(&storage_dir))
+                    Storage::load(Config::default(), ceremony_db(&storage_dir), db_passphrase)
                         .await?;
+                storage.finalize().await?;
+
+                Ok(())
+            }
+            Command::Status {
+                storage_dir,
+                db_passphrase,
+            } => {
+                let storage =
+                    Storage::load(Config::default(), ceremony_db(&storage_dir), db_passphrase)
+                        .await?;
+                let summary = storage.summary().await?;
+                let phase = match summary.phase {
+                    PhaseMarker::P1 => 1,
+                    PhaseMarker::P2 => 2,
+                };
+                println!("phase: {}", phase);
+                println!("slot: {}", summary.slot);
+                println!("contributions: {}", summary.contribution_count);
+                match summary.latest_contributor {
+                    Some(address) => println!("latest contributor: {}", address),
+                    None => println!("latest contributor: none"),
+                }
+                println!(
+                    "latest hash: {}",
+                    summary.latest_hash.as_deref().unwrap_or("none")
+                );
+
+                Ok(())
+            }
+            Command::Export {
+                storage_dir,
+                target_dir,
+                db_passphrase,
+            } => {
+                let storage = Storage::load_or_initialize(
+                    Config::default(),
+                    ceremony_db(&storage_dir),
+                    db_passphrase,
+                )
+                .await?;
                 // Grab phase1 output
                 let phase1_crs = match storage.phase1_current_crs().await? {
                     Some(x) => x,
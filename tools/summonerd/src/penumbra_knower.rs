@@ -1,11 +1,33 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use anyhow::Result;
 use camino::Utf8Path;
 use penumbra_asset::STAKING_TOKEN_ASSET_ID;
 use penumbra_keys::{Address, FullViewingKey};
 use penumbra_num::Amount;
+use penumbra_proto::util::tendermint_proxy::v1::{
+    tendermint_proxy_service_client::TendermintProxyServiceClient, GetStatusRequest,
+};
 use penumbra_view::{Storage, ViewServer};
 use url::Url;
 
+/// The hash of a penumbra transaction.
+pub type TxHash = [u8; 32];
+
+/// How long a total computed by [`PenumbraKnower::total_amount_sent_to_me`] is reused
+/// for, unless configured otherwise.
+pub const DEFAULT_AMOUNT_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// A total sent to us by some address, as of a given sync height.
+#[derive(Clone, Copy)]
+struct CachedAmount {
+    amount: Amount,
+    sync_height: Option<u64>,
+    computed_at: Instant,
+}
+
 /// Knows things about a running penumbra system, requires internet connectivity
 #[derive(Clone)]
 pub struct PenumbraKnower {
@@ -14,8 +36,11 @@ pub struct PenumbraKnower {
     // by the view service.
     storage: Storage,
     // Not sure if storing this is necessary, but seems like a good idea to avoid things getting
-    // dropped on the floor
-    _view: ViewServer,
+    // dropped on the floor. This is only missing in tests, which populate the storage directly.
+    _view: Option<ViewServer>,
+    // The totals sent to us, keyed by the encoding of the sending address.
+    amount_cache: Arc<Mutex<HashMap<Vec<u8>, CachedAmount>>>,
+    amount_cache_ttl: Duration,
 }
 
 impl PenumbraKnower {
@@ -31,20 +56,436 @@ impl PenumbraKnower {
         let view = ViewServer::new(storage.clone(), node).await?;
         Ok(Self {
             storage,
-            _view: view,
+            _view: Some(view),
+            amount_cache: Default::default(),
+            amount_cache_ttl: DEFAULT_AMOUNT_CACHE_TTL,
         })
     }
 
+    /// Create a knower reading from `storage`, without syncing it.
+    #[cfg(test)]
+    fn from_storage(storage: Storage) -> Self {
+        Self {
+            storage,
+            _view: None,
+            amount_cache: Default::default(),
+            amount_cache_ttl: DEFAULT_AMOUNT_CACHE_TTL,
+        }
+    }
+
+    /// Reuse the totals computed by [`Self::total_amount_sent_to_me`] for up to `ttl`,
+    /// where a zero `ttl` disables the cache.
+    pub fn with_amount_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.amount_cache_ttl = ttl;
+        self
+    }
+
+    /// Create the knower, resuming from the penumbra data synced by a previous run, if any.
+    ///
+    /// The storage records the height it was last synced to, which serves as a checkpoint.
+    /// If the checkpoint is ahead of the node's current height, e.g. after the node was rolled
+    /// back, the storage may not reflect the funds the node knows about, so it's discarded
+    /// and synced again from genesis.
+    pub async fn load_with_checkpoint(
+        storage_path: impl AsRef<Utf8Path>,
+        fvk: &FullViewingKey,
+        node: Url,
+    ) -> Result<Self> {
+        let storage_path = storage_path.as_ref();
+        if storage_path.exists() {
+            let node_height = Self::node_height(&node).await?;
+            Self::discard_checkpoint_ahead_of(storage_path, node_height).await?;
+        }
+        Self::load_or_initialize(storage_path, fvk, node).await
+    }
+
+    /// Remove the storage at `storage_path` if its checkpoint is ahead of `node_height`,
+    /// returning whether it was removed.
+    async fn discard_checkpoint_ahead_of(
+        storage_path: &Utf8Path,
+        node_height: u64,
+    ) -> Result<bool> {
+        let checkpoint = Storage::load(storage_path)
+            .await?
+            .last_sync_height()
+            .await?;
+        match checkpoint {
+            Some(checkpoint) if checkpoint > node_height => {
+                tracing::warn!(
+                    checkpoint,
+                    node_height,
+                    "penumbra checkpoint is ahead of the node, re-syncing from genesis"
+                );
+                Self::remove_storage(storage_path)?;
+                Ok(true)
+            }
+            _ => {
+                tracing::info!(
+                    ?checkpoint,
+                    node_height,
+                    "resuming from penumbra checkpoint"
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    /// The latest block height of the node.
+    async fn node_height(node: &Url) -> Result<u64> {
+        let mut client = TendermintProxyServiceClient::connect(node.to_string()).await?;
+        let sync_info = client
+            .get_status(GetStatusRequest {})
+            .await?
+            .into_inner()
+            .sync_info
+            .ok_or_else(|| anyhow::anyhow!("could not parse sync_info in gRPC response"))?;
+        Ok(sync_info.latest_block_height)
+    }
+
+    /// Remove the storage at `storage_path`, along with its write-ahead log.
+    fn remove_storage(storage_path: &Utf8Path) -> Result<()> {
+        std::fs::remove_file(storage_path)?;
+        for suffix in ["-wal", "-shm"] {
+            let path = format!("{storage_path}{suffix}");
+            if Utf8Path::new(&path).exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The total amount of the staking token that `by` sent to us.
+    ///
+    /// Totals are cached for the configured TTL, but only as long as no new blocks are
+    /// synced, so funds sent by an address count as soon as the block including them is.
     pub async fn total_amount_sent_to_me(&self, by: &Address) -> Result<Amount> {
-        let notes = self.storage.notes_by_sender(by).await?;
+        let mut totals = self
+            .total_amounts_sent_to_me(std::slice::from_ref(by))
+            .await?;
+        Ok(totals.pop().expect("one total is computed per sender"))
+    }
+
+    /// The total amount of the staking token that each of `senders` sent to us, in order.
+    ///
+    /// This caches totals like [`Self::total_amount_sent_to_me`], and reads the notes of
+    /// all the senders whose totals aren't cached together.
+    pub async fn total_amounts_sent_to_me(&self, senders: &[Address]) -> Result<Vec<Amount>> {
+        let sync_height = self.storage.last_sync_height().await?;
+        let mut totals: Vec<Option<Amount>> = {
+            let cache = self
+                .amount_cache
+                .lock()
+                .expect("amount cache lock is not poisoned");
+            senders
+                .iter()
+                .map(|by| {
+                    cache
+                        .get(&by.to_vec())
+                        .filter(|cached| {
+                            cached.sync_height == sync_height
+                                && cached.computed_at.elapsed() < self.amount_cache_ttl
+                        })
+                        .map(|cached| cached.amount)
+                })
+                .collect()
+        };
+
+        let uncached: Vec<Address> = senders
+            .iter()
+            .zip(&totals)
+            .filter(|(_, total)| total.is_none())
+            .map(|(by, _)| by.clone())
+            .collect();
+        if uncached.is_empty() {
+            return Ok(totals.into_iter().flatten().collect());
+        }
+
+        let mut computed = self
+            .compute_total_amounts_sent_to_me(&uncached)
+            .await?
+            .into_iter();
+        for total in totals.iter_mut().filter(|total| total.is_none()) {
+            *total = computed.next();
+        }
+
+        if !self.amount_cache_ttl.is_zero() {
+            let mut cache = self
+                .amount_cache
+                .lock()
+                .expect("amount cache lock is not poisoned");
+            // Drop the totals that can no longer be reused, so the cache only holds the
+            // addresses checked recently.
+            cache.retain(|_, cached| {
+                cached.sync_height == sync_height
+                    && cached.computed_at.elapsed() < self.amount_cache_ttl
+            });
+            let computed_at = Instant::now();
+            for (by, total) in senders.iter().zip(&totals) {
+                cache.entry(by.to_vec()).or_insert(CachedAmount {
+                    amount: total.expect("every total is cached or computed"),
+                    sync_height,
+                    computed_at,
+                });
+            }
+        }
+        Ok(totals
+            .into_iter()
+            .map(|total| total.expect("every total is cached or computed"))
+            .collect())
+    }
+
+    async fn compute_total_amounts_sent_to_me(&self, senders: &[Address]) -> Result<Vec<Amount>> {
+        let notes_by_sender = self.storage.notes_by_senders(senders).await?;
+        let what_i_want = STAKING_TOKEN_ASSET_ID.to_owned();
+        Ok(notes_by_sender
+            .iter()
+            .map(|notes| {
+                notes
+                    .iter()
+                    .filter(|note| note.note.asset_id() == what_i_want)
+                    .fold(Amount::zero(), |total, note| {
+                        total.saturating_add(&note.note.amount())
+                    })
+            })
+            .collect())
+    }
+
+    /// The individual transfers that `address` sent to us, as the hash, amount, and
+    /// height of each transaction, in the order they were included.
+    ///
+    /// Only transfers of the staking token count, as in [`Self::total_amount_sent_to_me`],
+    /// and the amounts of several notes created by one transaction are added up. An
+    /// address that never sent us anything has no transfers.
+    pub async fn transfers_to(&self, address: &Address) -> Result<Vec<(TxHash, Amount, u64)>> {
+        let notes = self.storage.notes_by_sender(address).await?;
         let what_i_want = STAKING_TOKEN_ASSET_ID.to_owned();
-        let mut total = Amount::zero();
+        let mut transfers = BTreeMap::<(u64, TxHash), Amount>::new();
         for note in &notes {
             if note.note.asset_id() != what_i_want {
                 continue;
             }
-            total = total.saturating_add(&note.note.amount());
+            let Some(tx_hash) = note.source.id() else {
+                continue;
+            };
+            let amount = transfers.entry((note.height_created, tx_hash)).or_default();
+            *amount = amount.saturating_add(&note.note.amount());
         }
-        Ok(total)
+        Ok(transfers
+            .into_iter()
+            .map(|((height, tx_hash), amount)| (tx_hash, amount, height))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+    use penumbra_app::params::AppParameters;
+    use penumbra_asset::Value;
+    use penumbra_keys::{keys::AddressIndex, test_keys};
+    use penumbra_proto::DomainType;
+    use penumbra_sct::CommitmentSource;
+    use penumbra_shielded_pool::Note;
+    use r2d2_sqlite::rusqlite::Connection;
+    use rand_core::OsRng;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Create the view database of a fresh knower in `dir`, returning its path.
+    async fn view_storage(dir: &TempDir) -> Result<Utf8PathBuf> {
+        let path = Utf8PathBuf::from_path_buf(dir.path().join("penumbra.db"))
+            .expect("temporary directories have utf-8 paths");
+        Storage::initialize(
+            Some(&path),
+            test_keys::FULL_VIEWING_KEY.clone(),
+            AppParameters::default(),
+        )
+        .await?;
+        Ok(path)
+    }
+
+    /// Mark the view database at `path` as synced up to `height`.
+    fn set_sync_height(path: &Utf8Path, height: u64) -> Result<()> {
+        Connection::open(path)?.execute("UPDATE sync_height SET height = ?1", [height])?;
+        Ok(())
+    }
+
+    /// Record in the view database at `path` that `sender` sent us `amount` of the staking
+    /// token in the transaction `tx_hash`, included at `height`, as syncing it would.
+    ///
+    /// The database is marked as synced up to `height`, if it wasn't already.
+    fn record_transfer(
+        path: &Utf8Path,
+        sender: &Address,
+        amount: u64,
+        height: u64,
+        tx_hash: TxHash,
+    ) -> Result<()> {
+        let note = Note::generate(
+            &mut OsRng,
+            &test_keys::ADDRESS_0,
+            Value {
+                amount: amount.into(),
+                asset_id: *STAKING_TOKEN_ASSET_ID,
+            },
+        );
+        let commitment = note.commit().0.to_bytes().to_vec();
+        let conn = Connection::open(path)?;
+        conn.execute(
+            "INSERT INTO notes (note_commitment, address, amount, asset_id, rseed)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            (
+                &commitment,
+                note.address().to_vec(),
+                u128::from(note.amount()).to_be_bytes().to_vec(),
+                note.asset_id().to_bytes().to_vec(),
+                note.rseed().to_bytes().to_vec(),
+            ),
+        )?;
+        conn.execute(
+            "INSERT INTO spendable_notes
+            (note_commitment, nullifier, position, height_created, address_index, source, height_spent, tx_hash)
+            VALUES (?1, ?2, 0, ?3, ?4, ?5, NULL, ?6)",
+            (
+                &commitment,
+                [0u8; 32].to_vec(),
+                height,
+                AddressIndex::new(0).to_bytes().to_vec(),
+                CommitmentSource::Transaction { id: Some(tx_hash) }.encode_to_vec(),
+                tx_hash.to_vec(),
+            ),
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO tx (tx_hash, tx_bytes, block_height, return_address, memo_text)
+            VALUES (?1, x'', ?2, ?3, NULL)",
+            (tx_hash.to_vec(), height, sender.to_vec()),
+        )?;
+        conn.execute("UPDATE sync_height SET height = MAX(height, ?1)", [height])?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn checkpoint_is_kept_unless_ahead_of_the_node() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = view_storage(&dir).await?;
+        set_sync_height(&path, 5)?;
+
+        // Restarting against a node that is at or past the checkpoint resumes from it.
+        assert!(!PenumbraKnower::discard_checkpoint_ahead_of(&path, 5).await?);
+        assert!(!PenumbraKnower::discard_checkpoint_ahead_of(&path, 10).await?);
+        assert_eq!(
+            Storage::load(&path).await?.last_sync_height().await?,
+            Some(5)
+        );
+
+        // A node behind the checkpoint may not know of the funds the storage does.
+        assert!(PenumbraKnower::discard_checkpoint_ahead_of(&path, 3).await?);
+        assert!(!path.exists());
+        assert!(!Utf8Path::new(&format!("{path}-wal")).exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn transfers_are_listed_per_transaction() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = view_storage(&dir).await?;
+        let sender = test_keys::ADDRESS_1.clone();
+        record_transfer(&path, &sender, 3, 1, [1; 32])?;
+        // Two notes created by the same transaction make up a single transfer.
+        record_transfer(&path, &sender, 4, 2, [2; 32])?;
+        record_transfer(&path, &sender, 1, 2, [2; 32])?;
+        record_transfer(&path, &test_keys::ADDRESS_0, 7, 2, [3; 32])?;
+
+        let knower = PenumbraKnower::from_storage(Storage::load(&path).await?);
+        assert_eq!(
+            knower.transfers_to(&sender).await?,
+            vec![
+                ([1; 32], Amount::from(3u64), 1),
+                ([2; 32], Amount::from(5u64), 2)
+            ]
+        );
+        assert_eq!(
+            knower.total_amount_sent_to_me(&sender).await?,
+            Amount::from(8u64)
+        );
+
+        // An address which never sent us anything has no transfers, rather than an error.
+        let unfunded = test_keys::FULL_VIEWING_KEY.payment_address(7u32.into()).0;
+        assert!(knower.transfers_to(&unfunded).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn batched_totals_are_in_input_order() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = view_storage(&dir).await?;
+        let first = test_keys::ADDRESS_1.clone();
+        let second = test_keys::ADDRESS_0.clone();
+        let unfunded = test_keys::FULL_VIEWING_KEY.payment_address(7u32.into()).0;
+        record_transfer(&path, &first, 3, 1, [1; 32])?;
+        record_transfer(&path, &second, 4, 1, [2; 32])?;
+        record_transfer(&path, &second, 1, 1, [3; 32])?;
+
+        let knower = PenumbraKnower::from_storage(Storage::load(&path).await?)
+            .with_amount_cache_ttl(Duration::from_secs(3600));
+        // Cache the first total, so the batch mixes cached and computed totals.
+        assert_eq!(
+            knower.total_amount_sent_to_me(&first).await?,
+            Amount::from(3u64)
+        );
+        assert_eq!(
+            knower
+                .total_amounts_sent_to_me(&[second.clone(), unfunded, first, second])
+                .await?,
+            vec![
+                Amount::from(5u64),
+                Amount::zero(),
+                Amount::from(3u64),
+                Amount::from(5u64)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cached_total_is_refreshed_by_new_blocks() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = view_storage(&dir).await?;
+        let sender = test_keys::ADDRESS_1.clone();
+        record_transfer(&path, &sender, 3, 1, [1; 32])?;
+
+        let storage = Storage::load(&path).await?;
+        let cached = PenumbraKnower::from_storage(storage.clone())
+            .with_amount_cache_ttl(Duration::from_secs(3600));
+        let uncached = PenumbraKnower::from_storage(storage).with_amount_cache_ttl(Duration::ZERO);
+        assert_eq!(
+            cached.total_amount_sent_to_me(&sender).await?,
+            Amount::from(3u64)
+        );
+
+        // Without a new block being synced, the cached total is reused within its TTL,
+        // while a zero TTL always computes the total afresh.
+        record_transfer(&path, &sender, 4, 1, [2; 32])?;
+        assert_eq!(
+            cached.total_amount_sent_to_me(&sender).await?,
+            Amount::from(3u64)
+        );
+        assert_eq!(
+            uncached.total_amount_sent_to_me(&sender).await?,
+            Amount::from(7u64)
+        );
+
+        // Syncing a new block invalidates the cached total.
+        record_transfer(&path, &sender, 5, 2, [3; 32])?;
+        assert_eq!(
+            cached.total_amount_sent_to_me(&sender).await?,
+            Amount::from(12u64)
+        );
+
+        Ok(())
     }
 }
@@ -79,7 +79,21 @@ impl server::CeremonyCoordinatorService for CoordinatorService {
             })? {
             ContributionAllowed::Yes(amount) => amount,
             ContributionAllowed::DidntBidEnough(amount) => {
-                tracing::debug!(?address, ?amount, "did not bid enough");
+                // Record the transfers the bid was made of, in case the bidder disputes it.
+                match self.knower.transfers_to(&address).await {
+                    Ok(transfers) => {
+                        let transfers: Vec<_> = transfers
+                            .iter()
+                            .map(|(tx_hash, amount, height)| {
+                                format!("{} ({amount} at height {height})", hex::encode(tx_hash))
+                            })
+                            .collect();
+                        tracing::debug!(?address, ?amount, ?transfers, "did not bid enough");
+                    }
+                    Err(e) => {
+                        tracing::debug!(?address, ?amount, ?e, "did not bid enough");
+                    }
+                }
                 return Err(Status::permission_denied(format!(
                     "Bid amount {} is not large enough",
                     amount
@@ -91,6 +105,13 @@ impl server::CeremonyCoordinatorService for CoordinatorService {
                     "Unfortunately, you have been banned from participating in the ceremony, most likely because of timeouts. Repeatedly timing out prevents other users from participating, and we want to enable as many people to participate as possible. Your machine's network connection and performance are not sufficient to participate, and re-attempting without changing these will result in another timeout and your contribution not being included.".to_string(),
                 ));
             }
+            ContributionAllowed::Finalized => {
+                tracing::debug!(?address, "ceremony finalized");
+                return Err(Status::permission_denied(
+                    "The ceremony has been finalized, and is no longer accepting contributions."
+                        .to_string(),
+                ));
+            }
             ContributionAllowed::AlreadyContributed => {
                 tracing::debug!(?address, "already contributed");
                 return Err(Status::permission_denied("Thanks again for your contribution! Participating once is enough to guarantee security, and we'd like to allow other people to participate as well.".to_string()));
@@ -1,6 +1,9 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    io::Read,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use camino::Utf8Path;
 use penumbra_keys::Address;
 use penumbra_num::Amount;
@@ -19,12 +22,16 @@ use penumbra_proto::{
     Message,
 };
 use r2d2_sqlite::{
-    rusqlite::{OpenFlags, OptionalExtension},
+    rusqlite::{Connection, ErrorCode, OpenFlags, OptionalExtension, TransactionBehavior},
     SqliteConnectionManager,
 };
 use tokio::task::spawn_blocking;
 
-use crate::{config::Config, penumbra_knower::PenumbraKnower, phase::PhaseMarker};
+use crate::{
+    config::{Config, PoolConfig},
+    penumbra_knower::PenumbraKnower,
+    phase::PhaseMarker,
+};
 
 /// The current time as a unix timestamp.
 ///
@@ -39,6 +46,39 @@ fn current_time_unix() -> u64 {
         .unwrap_or(0)
 }
 
+/// Check whether the ceremony has been finalized, using an open connection.
+///
+/// This takes a connection, rather than the pool, so that the check can be made
+/// inside the same transaction as a write it guards.
+fn is_finalized(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row("SELECT 1 FROM finalization", [], |_| Ok(()))
+        .optional()?
+        .is_some())
+}
+
+/// Add a strike against `address`, using an open connection.
+fn strike_in(conn: &Connection, address: &Address) -> Result<()> {
+    conn.execute(
+        "INSERT INTO participant_metadata VALUES(?1, 1) ON CONFLICT(address) DO UPDATE SET strikes = strikes + 1;",
+        [address.to_vec()],
+    )?;
+    Ok(())
+}
+
+/// Determine the phase the ceremony is in, which is phase 2 once its root has been set.
+fn current_phase(conn: &Connection) -> Result<PhaseMarker> {
+    let phase2_started = conn
+        .query_row("SELECT 1 FROM phase2_contributions LIMIT 1", [], |_| Ok(()))
+        .optional()?
+        .is_some();
+    Ok(if phase2_started {
+        PhaseMarker::P2
+    } else {
+        PhaseMarker::P1
+    })
+}
+
 /// Represents the possible outcomes of checking contribution eligibility.
 #[derive(Clone, Debug)]
 pub enum ContributionAllowed {
@@ -46,6 +86,100 @@ pub enum ContributionAllowed {
     DidntBidEnough(Amount),
     AlreadyContributed,
     Banned,
+    Finalized,
+}
+
+/// How long a health check waits for a connection from the pool before giving up.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The ways in which a [`Storage::health_check`] can fail.
+#[derive(Debug)]
+pub enum HealthCheckError {
+    /// No connection became available from the pool within the timeout.
+    PoolExhausted(r2d2::Error),
+    /// A connection was available, but the database failed to answer a trivial query.
+    Query(r2d2_sqlite::rusqlite::Error),
+}
+
+impl std::fmt::Display for HealthCheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HealthCheckError::PoolExhausted(e) => {
+                write!(f, "no database connection available: {}", e)
+            }
+            HealthCheckError::Query(e) => write!(f, "database query failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HealthCheckError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HealthCheckError::PoolExhausted(e) => Some(e),
+            HealthCheckError::Query(e) => Some(e),
+        }
+    }
+}
+
+/// The ways in which opening an encrypted database can fail.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DatabaseKeyError {
+    /// The database is encrypted, but no passphrase was supplied.
+    MissingKey,
+    /// The supplied passphrase does not decrypt the database.
+    WrongKey,
+    /// A passphrase was supplied, but the database is not encrypted.
+    NotEncrypted,
+}
+
+impl std::fmt::Display for DatabaseKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatabaseKeyError::MissingKey => {
+                write!(
+                    f,
+                    "the database is encrypted, but no passphrase was supplied"
+                )
+            }
+            DatabaseKeyError::WrongKey => {
+                write!(f, "the supplied passphrase does not decrypt the database")
+            }
+            DatabaseKeyError::NotEncrypted => {
+                write!(
+                    f,
+                    "a passphrase was supplied, but the database is not encrypted"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DatabaseKeyError {}
+
+/// The header at the start of every unencrypted SQLite database file.
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Check whether the database file at `path` is unencrypted.
+fn is_plaintext_database(path: &Utf8Path) -> Result<bool> {
+    let mut header = [0u8; 16];
+    let mut file = std::fs::File::open(path)?;
+    Ok(file.read_exact(&mut header).is_ok() && &header == PLAINTEXT_HEADER)
+}
+
+/// A point-in-time summary of the state of the ceremony.
+#[derive(Clone, Debug)]
+pub struct CeremonySummary {
+    /// The phase the ceremony is in, which is phase 2 once its root has been set.
+    pub phase: PhaseMarker,
+    /// The slot of the latest contribution in the current phase.
+    pub slot: u64,
+    /// The number of contributions made in the current phase, excluding the root.
+    pub contribution_count: u64,
+    /// The address of the latest contributor in the current phase, if any.
+    pub latest_contributor: Option<Address>,
+    /// The hash of the latest contribution in the current phase, which the next
+    /// contribution builds on, or `None` if only the root is present.
+    pub latest_hash: Option<String>,
 }
 
 #[derive(Clone)]
@@ -56,24 +190,28 @@ pub struct Storage {
 
 impl Storage {
     /// If the database at `storage_path` exists, [`Self::load`] it, otherwise, [`Self::initialize`] it.
+    ///
+    /// If a `passphrase` is supplied, the database is encrypted at rest with it.
     pub async fn load_or_initialize(
         config: Config,
         storage_path: impl AsRef<Utf8Path>,
+        passphrase: Option<String>,
     ) -> anyhow::Result<Self> {
         if storage_path.as_ref().exists() {
-            return Self::load(config, storage_path).await;
+            return Self::load(config, storage_path, passphrase).await;
         }
 
-        Self::initialize(config, storage_path).await
+        Self::initialize(config, storage_path, passphrase).await
     }
 
     /// Initialize creates the database, but does not insert anything into it.
     async fn initialize(
         config: Config,
         storage_path: impl AsRef<Utf8Path>,
+        passphrase: Option<String>,
     ) -> anyhow::Result<Self> {
         // Connect to the database (or create it)
-        let pool = Self::connect(storage_path)?;
+        let pool = Self::connect(storage_path, passphrase, config.pool)?;
 
         spawn_blocking(move || {
             // In one database transaction, populate everything
@@ -82,6 +220,8 @@ impl Storage {
 
             // Create the tables
             tx.execute_batch(include_str!("storage/schema-new.sql"))?;
+            tx.execute_batch(include_str!("storage/finalization.sql"))?;
+            tx.execute_batch(include_str!("storage/slot_assignments.sql"))?;
 
             tx.commit()?;
 
@@ -90,13 +230,46 @@ impl Storage {
         .await?
     }
 
-    async fn load(config: Config, path: impl AsRef<Utf8Path>) -> anyhow::Result<Self> {
-        let storage = Self {
-            config,
-            pool: Self::connect(path)?,
-        };
+    /// Load an existing database.
+    ///
+    /// This fails with a [`DatabaseKeyError`] if the database is encrypted, and the
+    /// `passphrase` is missing or wrong, or if a `passphrase` is supplied for a database
+    /// that is not encrypted.
+    pub async fn load(
+        config: Config,
+        path: impl AsRef<Utf8Path>,
+        passphrase: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let has_passphrase = passphrase.is_some();
+        let pool = Self::connect(path, passphrase, config.pool)?;
+
+        // The key is only checked once the database is read, so read something now,
+        // rather than failing later on with what looks like a corrupt database.
+        let conn = pool.get()?;
+        match conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+            row.get::<usize, u64>(0)
+        }) {
+            Ok(_) => {}
+            Err(e) if e.sqlite_error_code() == Some(ErrorCode::NotADatabase) => {
+                // Keying a connection to an unencrypted database makes it unreadable too.
+                return Err(if !has_passphrase {
+                    DatabaseKeyError::MissingKey
+                } else if is_plaintext_database(path)? {
+                    DatabaseKeyError::NotEncrypted
+                } else {
+                    DatabaseKeyError::WrongKey
+                }
+                .into());
+            }
+            Err(e) => return Err(e.into()),
+        }
+        // Databases created before finalization or slot assignments existed lack their tables.
+        conn.execute_batch(include_str!("storage/finalization.sql"))?;
+        conn.execute_batch(include_str!("storage/slot_assignments.sql"))?;
+        drop(conn);
 
-        Ok(storage)
+        Ok(Self { config, pool })
     }
 
     /// Set the root we need for phase1.
@@ -145,35 +318,92 @@ impl Storage {
         Ok(())
     }
 
-    fn connect(path: impl AsRef<Utf8Path>) -> anyhow::Result<r2d2::Pool<SqliteConnectionManager>> {
+    /// Open a pool of connections to the database at `path`, sized by `pool_config`.
+    ///
+    /// Once all `max_size` connections are in use, a request for another one waits for
+    /// at most the configured timeout, and then fails.
+    fn connect(
+        path: impl AsRef<Utf8Path>,
+        passphrase: Option<String>,
+        pool_config: PoolConfig,
+    ) -> anyhow::Result<r2d2::Pool<SqliteConnectionManager>> {
+        if pool_config.max_size == 0 {
+            bail!("the database connection pool must hold at least one connection");
+        }
         let manager = SqliteConnectionManager::file(path.as_ref())
             .with_flags(
                 // Don't allow opening URIs, because they can change the behavior of the database; we
                 // just want to open normal filepaths.
                 OpenFlags::default() & !OpenFlags::SQLITE_OPEN_URI,
             )
-            .with_init(|conn| {
+            .with_init(move |conn| {
+                // SQLCipher requires the key to be set before anything else is done with the
+                // connection.
+                if let Some(passphrase) = &passphrase {
+                    conn.pragma_update(None, "key", passphrase)?;
+                }
                 // We use `prepare_cached` a fair amount: this is an overestimate of the number
                 // of cached prepared statements likely to be used.
                 conn.set_prepared_statement_cache_capacity(32);
                 Ok(())
             });
-        Ok(r2d2::Pool::new(manager)?)
+        Ok(r2d2::Pool::builder()
+            .max_size(pool_config.max_size)
+            .connection_timeout(pool_config.connection_timeout)
+            .build(manager)?)
     }
 
-    pub async fn strike(&self, address: &Address) -> Result<()> {
+    /// Check that the database is responsive, by running a trivial query through the pool.
+    ///
+    /// The query reads the schema, so that a database file which can no longer be read
+    /// is reported as [`HealthCheckError::Query`]. This waits at most
+    /// [`HEALTH_CHECK_TIMEOUT`] for a connection, so that a wedged pool is reported as
+    /// [`HealthCheckError::PoolExhausted`] rather than hanging.
+    pub async fn health_check(&self) -> Result<(), HealthCheckError> {
+        let pool = self.pool.clone();
+        spawn_blocking(move || {
+            let conn = pool
+                .get_timeout(HEALTH_CHECK_TIMEOUT)
+                .map_err(HealthCheckError::PoolExhausted)?;
+            conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+                row.get::<usize, u64>(0)
+            })
+            .map_err(HealthCheckError::Query)?;
+            Ok(())
+        })
+        .await
+        .expect("health check task should not panic")
+    }
+
+    /// Finalize the ceremony, so that no further contributions are accepted.
+    ///
+    /// The current CRS of each phase can still be read afterwards. Finalizing an
+    /// already finalized ceremony does nothing.
+    pub async fn finalize(&self) -> Result<()> {
         let mut conn = self.pool.get()?;
         let tx = conn.transaction()?;
         tx.execute(
-        "INSERT INTO participant_metadata VALUES(?1, 1) ON CONFLICT(address) DO UPDATE SET strikes = strikes + 1;",
-            [
-            address.to_vec()
-            ],
+            "INSERT OR IGNORE INTO finalization VALUES (0, ?1)",
+            (current_time_unix(),),
         )?;
         tx.commit()?;
         Ok(())
     }
 
+    /// Check whether the ceremony has been finalized.
+    pub async fn is_finalized(&self) -> Result<bool> {
+        let conn = self.pool.get()?;
+        is_finalized(&conn)
+    }
+
+    pub async fn strike(&self, address: &Address) -> Result<()> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        strike_in(&tx, address)?;
+        tx.commit()?;
+        Ok(())
+    }
+
     async fn get_strikes(&self, address: &Address) -> Result<u64> {
         let mut conn = self.pool.get()?;
         let tx = conn.transaction()?;
@@ -188,6 +418,88 @@ impl Storage {
         Ok(out)
     }
 
+    /// Record that `address` has been asked for a contribution, which it has `timeout`
+    /// to make before its slot can be reclaimed.
+    pub async fn assign_slot(&self, address: &Address, timeout: Duration) -> Result<()> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO slot_assignments VALUES (?1, ?2)",
+            (
+                address.to_vec(),
+                current_time_unix().saturating_add(timeout.as_secs()),
+            ),
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Release the slot assigned to `address`, striking them if `strike` is set.
+    ///
+    /// This returns whether the slot was still assigned: if it was already reclaimed,
+    /// nothing happens, so a participant is struck at most once per slot.
+    pub async fn release_slot(&self, address: &Address, strike: bool) -> Result<bool> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let released = tx.execute(
+            "DELETE FROM slot_assignments WHERE address = ?1",
+            [address.to_vec()],
+        )? > 0;
+        if released && strike {
+            strike_in(&tx, address)?;
+        }
+        tx.commit()?;
+        Ok(released)
+    }
+
+    /// Reclaim every slot whose assignment has expired, striking its participant, and
+    /// return the addresses they were assigned to.
+    ///
+    /// Each reclaim checks that the assignment is still held and expired in the same
+    /// transaction that removes it, so a slot whose contribution lands after the
+    /// expired assignments are listed is left alone.
+    pub async fn reclaim_expired_slots(&self) -> Result<Vec<Address>> {
+        let expired = self.expired_slot_assignments().await?;
+        let mut reclaimed = Vec::with_capacity(expired.len());
+        for address in expired {
+            if self.reclaim_slot_if_expired(&address).await? {
+                reclaimed.push(address);
+            }
+        }
+        Ok(reclaimed)
+    }
+
+    /// The addresses whose slot assignments have expired.
+    async fn expired_slot_assignments(&self) -> Result<Vec<Address>> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let mut stmt = tx.prepare("SELECT address FROM slot_assignments WHERE expires_at <= ?1")?;
+        let mut rows = stmt.query([current_time_unix()])?;
+        let mut out = Vec::new();
+        while let Some(row) = rows.next()? {
+            out.push(Address::try_from(row.get::<usize, Vec<u8>>(0)?)?);
+        }
+        Ok(out)
+    }
+
+    /// Reclaim the slot assigned to `address`, striking them, if the assignment is still
+    /// held and has expired, returning whether it was reclaimed.
+    async fn reclaim_slot_if_expired(&self, address: &Address) -> Result<bool> {
+        let mut conn = self.pool.get()?;
+        // Take the write lock up front, so that a contribution can't commit between
+        // checking the assignment and reclaiming it.
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let reclaimed = tx.execute(
+            "DELETE FROM slot_assignments WHERE address = ?1 AND expires_at <= ?2",
+            (address.to_vec(), current_time_unix()),
+        )? > 0;
+        if reclaimed {
+            strike_in(&tx, address)?;
+        }
+        tx.commit()?;
+        Ok(reclaimed)
+    }
+
     /// Check if a participant can contribute.
     ///
     /// If they can't, None will be returned, otherwise we'll have Some(amount),
@@ -198,7 +510,11 @@ impl Storage {
         address: &Address,
         marker: PhaseMarker,
     ) -> Result<ContributionAllowed> {
+        if self.is_finalized().await? {
+            return Ok(ContributionAllowed::Finalized);
+        }
         // Criteria:
+        // - Ceremony not finalized
         // - Bid more than min amount
         // - Hasn't already contributed
         // - Not banned
@@ -226,6 +542,61 @@ impl Storage {
         Ok(ContributionAllowed::Yes(amount))
     }
 
+    /// Check if each of several participants can contribute, in the current phase.
+    ///
+    /// This applies the same criteria as [`Storage::can_contribute`], but fetches the
+    /// bids of all addresses together, and looks up their contribution and strike status
+    /// in a single transaction.
+    ///
+    /// The results are in the same order as `addresses`: None for a participant who
+    /// can't contribute, otherwise Some(amount), with the amount indicating their bid.
+    /// Once the ceremony is finalized, nobody can contribute.
+    pub async fn can_contribute_batch(
+        &self,
+        knower: &PenumbraKnower,
+        addresses: &[Address],
+    ) -> Result<Vec<Option<Amount>>> {
+        let amounts = knower.total_amounts_sent_to_me(addresses).await?;
+        self.eligible_bids(addresses.iter().cloned().zip(amounts).collect())
+            .await
+    }
+
+    /// Keep the bids of the participants who can contribute in the current phase, in order.
+    async fn eligible_bids(&self, bids: Vec<(Address, Amount)>) -> Result<Vec<Option<Amount>>> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        if is_finalized(&tx)? {
+            return Ok(vec![None; bids.len()]);
+        }
+        let contributed_query = match current_phase(&tx)? {
+            PhaseMarker::P1 => "SELECT 1 FROM phase1_contributions WHERE address = ?1",
+            PhaseMarker::P2 => "SELECT 1 FROM phase2_contributions WHERE address = ?1",
+        };
+        let mut contributed = tx.prepare(contributed_query)?;
+        let mut strikes =
+            tx.prepare("SELECT strikes FROM participant_metadata WHERE address = ?1")?;
+
+        let mut out = Vec::with_capacity(bids.len());
+        for (address, amount) in bids {
+            if amount < Amount::from(self.config.min_bid_u64) {
+                out.push(None);
+                continue;
+            }
+            let address = address.to_vec();
+            let has_contributed = contributed
+                .query_row([&address], |_| Ok(()))
+                .optional()?
+                .is_some();
+            let is_banned = strikes
+                .query_row([&address], |row| row.get::<usize, u64>(0))
+                .optional()?
+                .unwrap_or(0)
+                >= self.config.max_strikes;
+            out.push((!has_contributed && !is_banned).then_some(amount));
+        }
+        Ok(out)
+    }
+
     pub async fn phase1_current_crs(&self) -> Result<Option<Phase1CeremonyCRS>> {
         let mut conn = self.pool.get()?;
         let tx = conn.transaction()?;
@@ -290,25 +661,27 @@ impl Storage {
         Ok(Some(crs))
     }
 
+    /// Check whether the current phase 2 CRS is `other`.
+    ///
+    /// This compares the CRS elements themselves, so it can be used to check that
+    /// a mirror or a backup is in the same state as this database. Returns `false`
+    /// if phase 2 has not started yet.
+    pub async fn crs_matches(&self, other: &Phase2CeremonyCRS) -> Result<bool> {
+        Ok(self
+            .phase2_current_crs()
+            .await?
+            .is_some_and(|current| &current == other))
+    }
+
     pub async fn phase1_commit_contribution(
         &self,
         contributor: Address,
         contribution: Phase1CeremonyContribution,
     ) -> Result<()> {
-        let mut conn = self.pool.get()?;
-        let tx = conn.transaction()?;
-        let contributor_bytes = contributor.to_vec();
         let hash = contribution.hash().as_ref().to_owned();
-        tx.execute(
-            "INSERT INTO phase1_contribution_data VALUES(NULL, ?1)",
-            (PBContribution::try_from(contribution)?.encode_to_vec(),),
-        )?;
-        tx.execute(
-            "INSERT INTO phase1_contributions VALUES(NULL, 0, ?1, ?2, ?3)",
-            (hash, contributor_bytes, current_time_unix()),
-        )?;
-        tx.commit()?;
-        Ok(())
+        let data = PBContribution::try_from(contribution)?.encode_to_vec();
+        self.insert_contribution(PhaseMarker::P1, contributor, hash, data)
+            .await
     }
 
     pub async fn phase2_commit_contribution(
@@ -316,20 +689,60 @@ impl Storage {
         contributor: Address,
         contribution: Phase2CeremonyContribution,
     ) -> Result<()> {
-        let mut conn = self.pool.get()?;
-        let tx = conn.transaction()?;
-        let contributor_bytes = contributor.to_vec();
         let hash = contribution.hash().as_ref().to_owned();
-        tx.execute(
-            "INSERT INTO phase2_contribution_data VALUES(NULL, ?1)",
-            (PBContribution::try_from(contribution)?.encode_to_vec(),),
-        )?;
-        tx.execute(
-            "INSERT INTO phase2_contributions VALUES(NULL, 0, ?1, ?2, ?3)",
-            (hash, contributor_bytes, current_time_unix()),
-        )?;
-        tx.commit()?;
-        Ok(())
+        let data = PBContribution::try_from(contribution)?.encode_to_vec();
+        self.insert_contribution(PhaseMarker::P2, contributor, hash, data)
+            .await
+    }
+
+    /// Insert an encoded contribution, and the record of who made it, in one transaction.
+    ///
+    /// The transaction runs on a blocking task, which is not cancelled if the returned
+    /// future is dropped, e.g. when the contributor disconnects. Once started, the
+    /// transaction is thus either committed or rolled back as a whole.
+    ///
+    /// This fails if the ceremony has been finalized, which is checked in the same
+    /// transaction, so that a contribution racing with [`Self::finalize`] is rejected.
+    async fn insert_contribution(
+        &self,
+        marker: PhaseMarker,
+        contributor: Address,
+        hash: Vec<u8>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let (data_query, contribution_query) = match marker {
+            PhaseMarker::P1 => (
+                "INSERT INTO phase1_contribution_data VALUES(NULL, ?1)",
+                "INSERT INTO phase1_contributions VALUES(NULL, 0, ?1, ?2, ?3)",
+            ),
+            PhaseMarker::P2 => (
+                "INSERT INTO phase2_contribution_data VALUES(NULL, ?1)",
+                "INSERT INTO phase2_contributions VALUES(NULL, 0, ?1, ?2, ?3)",
+            ),
+        };
+        let pool = self.pool.clone();
+        spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            // Take the write lock up front, so that finalization can't commit between
+            // checking for it and inserting.
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            if is_finalized(&tx)? {
+                bail!("the ceremony has been finalized, and accepts no more contributions");
+            }
+            tx.execute(data_query, (data,))?;
+            tx.execute(
+                contribution_query,
+                (hash, contributor.to_vec(), current_time_unix()),
+            )?;
+            // The contributor's slot is filled, so it can no longer be reclaimed.
+            tx.execute(
+                "DELETE FROM slot_assignments WHERE address = ?1",
+                [contributor.to_vec()],
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await?
     }
 
     pub async fn current_slot(&self, marker: PhaseMarker) -> Result<u64> {
@@ -345,6 +758,85 @@ impl Storage {
         Ok(out)
     }
 
+    /// Get the slot, root flag, encoded contribution or CRS, and contributor of every
+    /// row in the current phase after `slot`, in slot order.
+    ///
+    /// A mirror can poll this with the last slot it holds to append new contributions
+    /// as they land. Passing the [`Self::current_slot`] returns nothing.
+    pub async fn contributions_since(
+        &self,
+        slot: u64,
+    ) -> Result<Vec<(u64, bool, Vec<u8>, Option<Address>)>> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let query = match current_phase(&tx)? {
+            PhaseMarker::P1 => {
+                "SELECT p1.slot, p1.is_root, p1_data.contribution_or_crs, p1.address
+ FROM phase1_contributions AS p1
+ JOIN phase1_contribution_data AS p1_data ON p1.slot = p1_data.slot
+ WHERE p1.slot > ?1 ORDER BY p1.slot"
+            }
+            PhaseMarker::P2 => {
+                "SELECT p2.slot, p2.is_root, p2_data.contribution_or_crs, p2.address
+ FROM phase2_contributions AS p2
+ JOIN phase2_contribution_data AS p2_data ON p2.slot = p2_data.slot
+ WHERE p2.slot > ?1 ORDER BY p2.slot"
+            }
+        };
+
+        let mut out = Vec::new();
+        let mut stmt = tx.prepare(query)?;
+        let mut rows = stmt.query([slot])?;
+        while let Some(row) = rows.next()? {
+            let slot: u64 = row.get(0)?;
+            let is_root: bool = row.get(1)?;
+            let contribution_or_crs: Vec<u8> = row.get(2)?;
+            let address_bytes: Option<Vec<u8>> = row.get(3)?;
+            let address = address_bytes.map(Address::try_from).transpose()?;
+            out.push((slot, is_root, contribution_or_crs, address));
+        }
+
+        Ok(out)
+    }
+
+    /// Summarize the state of the ceremony.
+    ///
+    /// All the fields are read in a single transaction, so they describe the same snapshot.
+    pub async fn summary(&self) -> Result<CeremonySummary> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        let phase = current_phase(&tx)?;
+        let (count_query, latest_query) = match phase {
+            PhaseMarker::P1 => (
+                "SELECT COUNT(*) FROM phase1_contributions WHERE NOT is_root",
+                "SELECT slot, hash, address FROM phase1_contributions ORDER BY slot DESC LIMIT 1",
+            ),
+            PhaseMarker::P2 => (
+                "SELECT COUNT(*) FROM phase2_contributions WHERE NOT is_root",
+                "SELECT slot, hash, address FROM phase2_contributions ORDER BY slot DESC LIMIT 1",
+            ),
+        };
+        let contribution_count = tx.query_row(count_query, [], |row| row.get::<usize, u64>(0))?;
+        let (slot, hash, address) = tx
+            .query_row(latest_query, [], |row| {
+                Ok((
+                    row.get::<usize, u64>(0)?,
+                    row.get::<usize, Option<Vec<u8>>>(1)?,
+                    row.get::<usize, Option<Vec<u8>>>(2)?,
+                ))
+            })
+            .optional()?
+            .unwrap_or((0, None, None));
+        let latest_contributor = address.map(Address::try_from).transpose()?;
+        Ok(CeremonySummary {
+            phase,
+            slot,
+            contribution_count,
+            latest_contributor,
+            latest_hash: hash.map(hex::encode_upper),
+        })
+    }
+
     /// Get Phase 1 root.
     #[allow(dead_code)]
     pub async fn phase1_root(&self) -> Result<Phase1CeremonyCRS> {
@@ -437,3 +929,491 @@ impl Storage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+    use penumbra_keys::test_keys;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// The path of the ceremony database in `dir`.
+    fn db_path(dir: &TempDir) -> Utf8PathBuf {
+        Utf8PathBuf::from_path_buf(dir.path().join("ceremony.db"))
+            .expect("temporary directories have utf-8 paths")
+    }
+
+    /// Create a fresh, unencrypted ceremony database in a temporary directory.
+    async fn temp_storage(config: Config) -> Result<(TempDir, Storage)> {
+        let dir = tempfile::tempdir()?;
+        let storage = Storage::load_or_initialize(config, db_path(&dir), None).await?;
+        Ok((dir, storage))
+    }
+
+    /// Distinct addresses, for telling participants apart.
+    fn addresses<const N: usize>() -> [Address; N] {
+        std::array::from_fn(|i| {
+            test_keys::FULL_VIEWING_KEY
+                .payment_address((i as u32).into())
+                .0
+        })
+    }
+
+    /// Insert a placeholder root for a phase, in the way that setting the root does.
+    ///
+    /// The root is not a valid CRS, so this is only useful for tests that don't read it back.
+    fn put_root(storage: &Storage, marker: PhaseMarker) -> Result<()> {
+        let (data_table, contributions_table) = match marker {
+            PhaseMarker::P1 => ("phase1_contribution_data", "phase1_contributions"),
+            PhaseMarker::P2 => ("phase2_contribution_data", "phase2_contributions"),
+        };
+        let conn = storage.pool.get()?;
+        conn.execute(&format!("INSERT INTO {data_table} VALUES (0, x'')"), [])?;
+        conn.execute(
+            &format!("INSERT INTO {contributions_table} VALUES (0, 1, NULL, NULL, 0)"),
+            [],
+        )?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn batch_eligibility_is_in_input_order() -> Result<()> {
+        let (_dir, storage) = temp_storage(Config::default()).await?;
+        put_root(&storage, PhaseMarker::P1)?;
+        let [eligible, contributed, banned, underbid] = addresses();
+        storage
+            .insert_contribution(PhaseMarker::P1, contributed.clone(), vec![1; 32], vec![])
+            .await?;
+        for _ in 0..Config::default().max_strikes {
+            storage.strike(&banned).await?;
+        }
+
+        let bid = Amount::from(5u64);
+        let bids = vec![
+            (banned, bid),
+            (eligible.clone(), bid),
+            (underbid, Amount::zero()),
+            (contributed.clone(), bid),
+            (eligible, bid),
+        ];
+        assert_eq!(
+            storage.eligible_bids(bids).await?,
+            vec![None, Some(bid), None, None, Some(bid)]
+        );
+
+        // Contributing to phase 1 doesn't prevent contributing to phase 2.
+        put_root(&storage, PhaseMarker::P2)?;
+        assert_eq!(
+            storage.eligible_bids(vec![(contributed, bid)]).await?,
+            vec![Some(bid)]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn only_expired_uncommitted_slots_are_reclaimed() -> Result<()> {
+        let (_dir, storage) = temp_storage(Config::default()).await?;
+        put_root(&storage, PhaseMarker::P1)?;
+        let [expired, committed, pending] = addresses();
+        storage.assign_slot(&expired, Duration::ZERO).await?;
+        storage.assign_slot(&committed, Duration::ZERO).await?;
+        storage
+            .assign_slot(&pending, Duration::from_secs(3600))
+            .await?;
+
+        // A contribution lands after the expired assignments are listed, but before
+        // they're reclaimed.
+        let listed = storage.expired_slot_assignments().await?;
+        assert_eq!(listed.len(), 2);
+        storage
+            .insert_contribution(PhaseMarker::P1, committed.clone(), vec![1; 32], vec![])
+            .await?;
+        let mut reclaimed = Vec::new();
+        for address in listed {
+            if storage.reclaim_slot_if_expired(&address).await? {
+                reclaimed.push(address);
+            }
+        }
+        assert_eq!(reclaimed, vec![expired.clone()]);
+        assert_eq!(storage.get_strikes(&expired).await?, 1);
+        assert_eq!(storage.get_strikes(&committed).await?, 0);
+        assert_eq!(storage.get_strikes(&pending).await?, 0);
+
+        // A reclaimed slot isn't struck again when the coordinator times out on it too,
+        // and the assignment that hasn't expired is left for the coordinator.
+        assert!(!storage.release_slot(&expired, true).await?);
+        assert_eq!(storage.get_strikes(&expired).await?, 1);
+        assert!(storage.reclaim_expired_slots().await?.is_empty());
+        assert!(storage.release_slot(&pending, false).await?);
+        assert_eq!(storage.get_strikes(&pending).await?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn summary_describes_the_current_phase() -> Result<()> {
+        let (_dir, storage) = temp_storage(Config::default()).await?;
+        put_root(&storage, PhaseMarker::P1)?;
+        let [first, second] = addresses();
+        storage
+            .insert_contribution(PhaseMarker::P1, first, vec![1; 32], vec![])
+            .await?;
+        storage
+            .insert_contribution(PhaseMarker::P1, second.clone(), vec![2; 32], vec![])
+            .await?;
+
+        let summary = storage.summary().await?;
+        assert_eq!(summary.phase, PhaseMarker::P1);
+        assert_eq!(summary.slot, 2);
+        assert_eq!(summary.contribution_count, 2);
+        assert_eq!(summary.latest_contributor, Some(second));
+        assert_eq!(summary.latest_hash, Some(hex::encode_upper([2; 32])));
+
+        // Once phase 2 starts, only its root is present.
+        put_root(&storage, PhaseMarker::P2)?;
+        let summary = storage.summary().await?;
+        assert_eq!(summary.phase, PhaseMarker::P2);
+        assert_eq!(summary.slot, 0);
+        assert_eq!(summary.contribution_count, 0);
+        assert_eq!(summary.latest_contributor, None);
+        assert_eq!(summary.latest_hash, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn health_check_distinguishes_exhaustion_from_query_failures() -> Result<()> {
+        let pool = PoolConfig::default().with_max_size(Some(1));
+        let (_dir, storage) = temp_storage(Config::default().with_pool(pool)).await?;
+        storage.health_check().await?;
+
+        // With the only connection in use, the check gives up rather than waiting.
+        let held = storage.pool.get()?;
+        assert!(matches!(
+            storage.health_check().await,
+            Err(HealthCheckError::PoolExhausted(_))
+        ));
+        drop(held);
+        storage.health_check().await?;
+
+        // A database file that can't be read fails the query instead.
+        let dir = tempfile::tempdir()?;
+        let path = db_path(&dir);
+        std::fs::write(
+            &path,
+            b"this is not a database, but it is long enough to look like one",
+        )?;
+        let unreadable = Storage {
+            config: Config::default(),
+            pool: Storage::connect(&path, None, PoolConfig::default())?,
+        };
+        assert!(matches!(
+            unreadable.health_check().await,
+            Err(HealthCheckError::Query(_))
+        ));
+
+        Ok(())
+    }
+
+    /// Check that loading the database at `path` with `passphrase` fails with `expected`.
+    async fn assert_key_error(
+        path: &Utf8Path,
+        passphrase: Option<&str>,
+        expected: DatabaseKeyError,
+    ) {
+        let error = Storage::load(Config::default(), path, passphrase.map(str::to_owned))
+            .await
+            .err()
+            .expect("loading should fail");
+        let actual = error
+            .downcast_ref::<DatabaseKeyError>()
+            .unwrap_or_else(|| panic!("expected a key error, found: {error:#}"));
+        assert_eq!(actual, &expected);
+    }
+
+    #[tokio::test]
+    async fn encrypted_database_requires_its_key() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = db_path(&dir);
+        let storage =
+            Storage::load_or_initialize(Config::default(), &path, Some("correct".to_owned()))
+                .await?;
+        put_root(&storage, PhaseMarker::P1)?;
+        drop(storage);
+
+        // The database is encrypted, so it doesn't look like one without the key.
+        assert!(!is_plaintext_database(&path)?);
+        assert_key_error(&path, Some("wrong"), DatabaseKeyError::WrongKey).await;
+        assert_key_error(&path, None, DatabaseKeyError::MissingKey).await;
+
+        let storage = Storage::load(Config::default(), &path, Some("correct".to_owned())).await?;
+        assert_eq!(storage.current_slot(PhaseMarker::P1).await?, 0);
+        assert!(!storage.is_finalized().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn passphrase_for_plaintext_database_is_rejected() -> Result<()> {
+        let (dir, storage) = temp_storage(Config::default()).await?;
+        drop(storage);
+        let path = db_path(&dir);
+
+        assert!(is_plaintext_database(&path)?);
+        assert_key_error(&path, Some("passphrase"), DatabaseKeyError::NotEncrypted).await;
+        Storage::load(Config::default(), &path, None).await?;
+
+        Ok(())
+    }
+
+    /// The number of rows in the contribution data and contribution tables of a phase.
+    fn row_counts(storage: &Storage, marker: PhaseMarker) -> Result<(u64, u64)> {
+        let query = match marker {
+            PhaseMarker::P1 => {
+                "SELECT (SELECT COUNT(*) FROM phase1_contribution_data),
+                        (SELECT COUNT(*) FROM phase1_contributions)"
+            }
+            PhaseMarker::P2 => {
+                "SELECT (SELECT COUNT(*) FROM phase2_contribution_data),
+                        (SELECT COUNT(*) FROM phase2_contributions)"
+            }
+        };
+        Ok(storage.pool.get()?.query_row(query, [], |row| {
+            Ok((row.get::<usize, u64>(0)?, row.get::<usize, u64>(1)?))
+        })?)
+    }
+
+    #[tokio::test]
+    async fn dropped_contribution_is_committed_whole() -> Result<()> {
+        let (_dir, storage) = temp_storage(Config::default()).await?;
+        put_root(&storage, PhaseMarker::P1)?;
+        let [contributor] = addresses();
+
+        // Hold the write lock, so that the contribution is still waiting for it when
+        // its future is dropped.
+        let mut conn = storage.pool.get()?;
+        let lock = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let dropped = tokio::time::timeout(
+            Duration::from_millis(100),
+            storage.insert_contribution(PhaseMarker::P1, contributor, vec![1; 32], vec![]),
+        )
+        .await;
+        assert!(dropped.is_err(), "the contribution should still be pending");
+        lock.rollback()?;
+        drop(conn);
+
+        // The contribution carries on without its future, and lands as a whole.
+        let mut attempts = 0;
+        while storage.current_slot(PhaseMarker::P1).await? == 0 {
+            assert!(attempts < 100, "the contribution was never committed");
+            attempts += 1;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(row_counts(&storage, PhaseMarker::P1)?, (2, 2));
+
+        Ok(())
+    }
+
+    /// Insert `root` as the root of phase 2, in the way that setting the transition does.
+    fn put_phase2_root(storage: &Storage, root: Phase2CeremonyCRS) -> Result<()> {
+        let conn = storage.pool.get()?;
+        conn.execute(
+            "INSERT INTO phase2_contribution_data VALUES (0, ?1)",
+            (pb::CeremonyCrs::try_from(root)?.encode_to_vec(),),
+        )?;
+        conn.execute(
+            "INSERT INTO phase2_contributions VALUES (0, 1, NULL, NULL, 0)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn finalization_blocks_contributions_but_not_reads() -> Result<()> {
+        let (_dir, storage) = temp_storage(Config::default()).await?;
+        let root = Phase2CeremonyCRS::root()?;
+        put_phase2_root(&storage, root.clone())?;
+        let [contributor] = addresses();
+        let bid = Amount::from(5u64);
+        assert_eq!(
+            storage
+                .eligible_bids(vec![(contributor.clone(), bid)])
+                .await?,
+            vec![Some(bid)]
+        );
+
+        storage.finalize().await?;
+        // Finalizing an already finalized ceremony does nothing.
+        storage.finalize().await?;
+        assert!(storage.is_finalized().await?);
+
+        assert_eq!(
+            storage
+                .eligible_bids(vec![(contributor.clone(), bid)])
+                .await?,
+            vec![None]
+        );
+        assert!(storage
+            .insert_contribution(PhaseMarker::P2, contributor, vec![1; 32], vec![])
+            .await
+            .is_err());
+        assert_eq!(row_counts(&storage, PhaseMarker::P2)?, (1, 1));
+        assert_eq!(storage.phase2_current_crs().await?, Some(root));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn finalization_racing_contributions_leaves_no_partial_writes() -> Result<()> {
+        let (_dir, storage) = temp_storage(Config::default()).await?;
+        put_root(&storage, PhaseMarker::P1)?;
+
+        let contributions: Vec<_> = addresses::<8>()
+            .into_iter()
+            .enumerate()
+            .map(|(i, contributor)| {
+                let storage = storage.clone();
+                tokio::spawn(async move {
+                    storage
+                        .insert_contribution(
+                            PhaseMarker::P1,
+                            contributor,
+                            vec![i as u8; 32],
+                            vec![],
+                        )
+                        .await
+                        .is_ok()
+                })
+            })
+            .collect();
+        storage.finalize().await?;
+        let mut accepted = 0;
+        for contribution in contributions {
+            if contribution.await? {
+                accepted += 1;
+            }
+        }
+
+        // Each contribution either landed whole, or left nothing behind.
+        assert_eq!(
+            row_counts(&storage, PhaseMarker::P1)?,
+            (accepted + 1, accepted + 1)
+        );
+        let [late] = addresses();
+        assert!(storage
+            .insert_contribution(PhaseMarker::P1, late, vec![0xff; 32], vec![])
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn exhausted_pool_times_out() -> Result<()> {
+        let pool = PoolConfig::default()
+            .with_max_size(Some(2))
+            .with_connection_timeout_secs(Some(1));
+        let (_dir, storage) = temp_storage(Config::default().with_pool(pool)).await?;
+        put_root(&storage, PhaseMarker::P1)?;
+
+        let held = [storage.pool.get()?, storage.pool.get()?];
+        let start = std::time::Instant::now();
+        assert!(storage.current_slot(PhaseMarker::P1).await.is_err());
+        let waited = start.elapsed();
+        assert!(
+            waited >= Duration::from_secs(1) && waited < Duration::from_secs(10),
+            "waited {waited:?} for a connection"
+        );
+
+        drop(held);
+        assert_eq!(storage.current_slot(PhaseMarker::P1).await?, 0);
+
+        // A pool without any connections could never serve a request.
+        let dir = tempfile::tempdir()?;
+        let empty = Config::default().with_pool(PoolConfig::default().with_max_size(Some(0)));
+        assert!(Storage::load_or_initialize(empty, db_path(&dir), None)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn contributions_since_returns_only_newer_slots() -> Result<()> {
+        let (_dir, storage) = temp_storage(Config::default()).await?;
+        put_root(&storage, PhaseMarker::P1)?;
+        let contributors: [Address; 3] = addresses();
+        for (i, contributor) in contributors.iter().enumerate() {
+            storage
+                .insert_contribution(
+                    PhaseMarker::P1,
+                    contributor.clone(),
+                    vec![i as u8; 32],
+                    vec![i as u8; 4],
+                )
+                .await?;
+        }
+
+        let since_first = storage.contributions_since(1).await?;
+        assert_eq!(
+            since_first,
+            vec![
+                (2, false, vec![1; 4], Some(contributors[1].clone())),
+                (3, false, vec![2; 4], Some(contributors[2].clone())),
+            ]
+        );
+
+        let latest = storage.current_slot(PhaseMarker::P1).await?;
+        assert_eq!(latest, 3);
+        assert!(storage.contributions_since(latest).await?.is_empty());
+
+        // Once phase 2 starts, its contributions are the ones mirrored.
+        put_root(&storage, PhaseMarker::P2)?;
+        assert!(storage.contributions_since(0).await?.is_empty());
+        storage
+            .insert_contribution(
+                PhaseMarker::P2,
+                contributors[0].clone(),
+                vec![3; 32],
+                vec![3; 4],
+            )
+            .await?;
+        assert_eq!(
+            storage.contributions_since(0).await?,
+            vec![(1, false, vec![3; 4], Some(contributors[0].clone()))]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn crs_matches_only_the_current_crs() -> Result<()> {
+        let (_dir, storage) = temp_storage(Config::default()).await?;
+        let root = Phase2CeremonyCRS::root()?;
+        // Nothing matches before phase 2 has started.
+        assert!(!storage.crs_matches(&root).await?);
+        put_phase2_root(&storage, root.clone())?;
+        assert!(storage.crs_matches(&root).await?);
+
+        let [contributor] = addresses();
+        let contribution = Phase2CeremonyContribution::make(&root);
+        let contributed = contribution.new_elements();
+        storage
+            .phase2_commit_contribution(contributor, contribution)
+            .await?;
+
+        // The CRS read back from the database matches both itself, and the elements
+        // it was encoded from.
+        let current = storage
+            .phase2_current_crs()
+            .await?
+            .expect("phase 2 has started");
+        assert!(storage.crs_matches(&current).await?);
+        assert!(storage.crs_matches(&contributed).await?);
+        assert!(!storage.crs_matches(&root).await?);
+
+        Ok(())
+    }
+}
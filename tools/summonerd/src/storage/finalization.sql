@@ -0,0 +1,6 @@
+-- Marks the ceremony as finalized once a row is present; added after the initial
+-- schema, so it is created on load for databases that predate it.
+CREATE TABLE IF NOT EXISTS finalization (
+  id INTEGER PRIMARY KEY,
+  time INTEGER NOT NULL
+);
@@ -0,0 +1,6 @@
+-- The participants currently asked for a contribution, and when their time runs out;
+-- added after the initial schema, so it is created on load for databases that predate it.
+CREATE TABLE IF NOT EXISTS slot_assignments (
+  address BLOB PRIMARY KEY,
+  expires_at INTEGER NOT NULL
+);
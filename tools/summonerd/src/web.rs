@@ -1,6 +1,6 @@
 use askama::Template;
 use axum::{
-    extract::State,
+    extract::{RawQuery, State},
     http::StatusCode,
     response::{Html, IntoResponse, Response},
     routing::get,
@@ -9,7 +9,7 @@ use axum::{
 use penumbra_keys::Address;
 use std::sync::Arc;
 
-use crate::{config::Config, PhaseMarker};
+use crate::{config::Config, penumbra_knower::PenumbraKnower, PhaseMarker};
 use crate::{queue::ParticipantQueue, storage::Storage};
 
 /// The number of previous contributions to display
@@ -19,6 +19,7 @@ const LAST_N: u64 = 50_000;
 pub struct WebAppState {
     _address: Address,
     config: Config,
+    knower: PenumbraKnower,
     phase: PhaseMarker,
     queue: ParticipantQueue,
     storage: Storage,
@@ -69,6 +70,7 @@ async fn serve_woff2(filename: &str) -> impl IntoResponse {
 pub fn web_app(
     _address: Address,
     config: Config,
+    knower: PenumbraKnower,
     phase: PhaseMarker,
     queue: ParticipantQueue,
     storage: Storage,
@@ -76,6 +78,7 @@ pub fn web_app(
     let shared_state = Arc::new(WebAppState {
         _address,
         config,
+        knower,
         phase,
         queue,
         storage,
@@ -84,7 +87,16 @@ pub fn web_app(
     Router::new()
         .route("/", get(main_page).with_state(shared_state.clone()))
         .route("/phase/1", get(phase_1).with_state(shared_state.clone()))
-        .route("/phase/2", get(phase_2).with_state(shared_state))
+        .route("/phase/2", get(phase_2).with_state(shared_state.clone()))
+        .route(
+            "/eligibility",
+            get(eligibility).with_state(shared_state.clone()),
+        )
+        .route(
+            "/contributions",
+            get(contributions).with_state(shared_state.clone()),
+        )
+        .route("/healthz", get(healthz).with_state(shared_state))
         .route("/static/index.css", get(serve_css))
         .route(
             "/static/Iosevka-Term.woff2",
@@ -101,6 +113,92 @@ pub fn web_app(
         .route("/static/summoning.jpg", get(|| serve_summoning_jpg()))
 }
 
+/// Report whether the storage layer is responsive, for use by orchestration.
+pub async fn healthz(State(state): State<Arc<WebAppState>>) -> impl IntoResponse {
+    match state.storage.health_check().await {
+        Ok(()) => (StatusCode::OK, "ok".to_string()),
+        Err(e) => {
+            tracing::warn!(?e, "health check failed");
+            (StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+        }
+    }
+}
+
+/// Report whether each of the addresses given by `address` query parameters can
+/// contribute, one line per address in the order given, with their bid if they can.
+pub async fn eligibility(
+    State(state): State<Arc<WebAppState>>,
+    RawQuery(query): RawQuery,
+) -> impl IntoResponse {
+    let addresses = match url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+        .filter(|(key, _)| key == "address")
+        .map(|(_, address)| address.parse::<Address>())
+        .collect::<anyhow::Result<Vec<_>>>()
+    {
+        Ok(addresses) => addresses,
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("invalid address: {e}")),
+    };
+    match state
+        .storage
+        .can_contribute_batch(&state.knower, &addresses)
+        .await
+    {
+        Ok(bids) => {
+            let lines: Vec<String> = addresses
+                .iter()
+                .zip(bids)
+                .map(|(address, bid)| match bid {
+                    Some(bid) => format!("{address} {bid}upenumbra"),
+                    None => format!("{address} ineligible"),
+                })
+                .collect();
+            (StatusCode::OK, lines.join("\n"))
+        }
+        Err(e) => {
+            tracing::warn!(?e, "failed to check eligibility");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    }
+}
+
+/// List the contributions to the current phase after the slot given by the `since`
+/// query parameter, for mirrors to poll with the last slot they hold.
+///
+/// Each line holds the slot, whether it's the root, the contributor, or `-` for the root,
+/// and the hex encoding of the contribution or CRS, in slot order.
+pub async fn contributions(
+    State(state): State<Arc<WebAppState>>,
+    RawQuery(query): RawQuery,
+) -> impl IntoResponse {
+    let since = url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+        .find(|(key, _)| key == "since")
+        .map(|(_, since)| since.parse::<u64>());
+    let since = match since {
+        Some(Ok(since)) => since,
+        Some(Err(e)) => return (StatusCode::BAD_REQUEST, format!("invalid slot: {e}")),
+        None => return (StatusCode::BAD_REQUEST, "missing slot".to_string()),
+    };
+    match state.storage.contributions_since(since).await {
+        Ok(rows) => {
+            let lines: Vec<String> = rows
+                .into_iter()
+                .map(|(slot, is_root, contribution_or_crs, address)| {
+                    let address = address.map_or_else(|| "-".to_string(), |a| a.to_string());
+                    format!(
+                        "{slot} {is_root} {address} {}",
+                        hex::encode(contribution_or_crs)
+                    )
+                })
+                .collect();
+            (StatusCode::OK, lines.join("\n"))
+        }
+        Err(e) => {
+            tracing::warn!(?e, "failed to list contributions");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    }
+}
+
 pub async fn main_page(State(state): State<Arc<WebAppState>>) -> impl IntoResponse {
     let participants_top_median = snapshot_participants_top_median(state.clone()).await;
 